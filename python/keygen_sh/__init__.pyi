@@ -1,15 +1,35 @@
-from typing import Optional
+from typing import Optional, Union
 
 from .errors import KeygenError
 from .license import SchemeCode, License
+from .signed_key import SignedKey
 
-def verify(scheme: SchemeCode, signed_key: str) -> str:
+def verify(
+    scheme: SchemeCode, signed_key: str, public_key: Optional[Union[str, list[str]]] = None
+) -> str:
     """
     Verify an offline key.
 
     :param scheme: the scheme code
     :param signed_key: the key to verify
+    :param public_key: optionally, a public key or a list of trusted public keys to verify against
+        instead of the public key of the global configuration
     :return: the data in the key
+    :raises: a keygen_sh.errors.KeygenError if the key is invalid or its data is not valid UTF-8
+    """
+    ...
+
+def verify_key(
+    scheme: SchemeCode, signed_key: str, public_key: Optional[Union[str, list[str]]] = None
+) -> SignedKey:
+    """
+    Verify an offline key and return its decoded payload.
+
+    :param scheme: the scheme code
+    :param signed_key: the key to verify
+    :param public_key: optionally, a public key or a list of trusted public keys to verify against
+        instead of the public key of the global configuration
+    :return: the signed key with its raw data, signature, prefix and the public key that matched
     :raises: a keygen_sh.errors.KeygenError if the key is invalid
     """
     ...
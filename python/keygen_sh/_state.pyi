@@ -0,0 +1,41 @@
+from typing import Any, TypeVar
+
+_T = TypeVar("_T", bound="Serializable")
+
+class Serializable:
+    """
+    Methods every wrapped type has. Instances can be pickled and copied, which is built on `to_dict`.
+
+    Instances of the same resource, i.e. with the same id, are equal and have the same hash.
+    """
+
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
+    def to_dict(self) -> dict[str, Any]:
+        """
+        The state of the object as a dict, which `from_dict` restores it from.
+        """
+        ...
+
+    @classmethod
+    def from_dict(cls: type[_T], dict: dict[str, Any]) -> _T:
+        """
+        Restores an object from the dict returned by `to_dict`.
+
+        :raises: a keygen_sh.errors.KeygenError if the dict is not the state of such an object
+        """
+        ...
+
+    def to_json(self) -> str:
+        """
+        The state of the object as JSON, which `from_json` restores it from.
+        """
+        ...
+
+    @classmethod
+    def from_json(cls: type[_T], json: str) -> _T:
+        """
+        Restores an object from the JSON returned by `to_json`.
+        """
+        ...
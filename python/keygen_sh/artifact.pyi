@@ -0,0 +1,31 @@
+import datetime
+from typing import Any, Optional
+from keygen_sh._state import Serializable
+
+class Artifact(Serializable):
+    id: str
+    filename: str
+    filetype: Optional[str]
+    filesize: Optional[int]
+    platform: Optional[str]
+    arch: Optional[str]
+    signature: Optional[str]
+    checksum: Optional[str]
+    status: Optional[str]
+    metadata: dict[str, Any]
+    created: datetime.datetime
+    updated: datetime.datetime
+
+    async def download(self, path: str, public_key: Optional[str] = None) -> str:
+        """
+        Download this artifact with the license key of the config, streaming it to disk.
+
+        The artifact is checked against its checksum, and with a public key, its signature. Until then it is kept
+        at `path` + ".part", which is removed if a check fails.
+
+        :param path: where to save the artifact
+        :param public_key: the hex-encoded Ed25519 public key the release was signed with, e.g. by `keygen dist`
+        :return: the path the artifact was saved to
+        :raises: a keygen_sh.errors.ArtifactChecksumInvalid or keygen_sh.errors.ArtifactSignatureInvalid if a check fails
+        """
+        ...
@@ -0,0 +1,10 @@
+from typing import Optional
+
+def main(argv: Optional[list[str]] = None) -> int:
+    """
+    Run the `keygen-py` command line, e.g. `main(["validate", "--json"])`.
+
+    :param argv: optionally, the arguments instead of `sys.argv[1:]`
+    :return: the exit status: 0 on success, 1 if the command failed and 2 for invalid arguments
+    """
+    ...
@@ -1,9 +1,10 @@
 from dataclasses import dataclass
 from typing import Dict
+from keygen_sh._state import Serializable
 
 
 @dataclass(frozen=True)
-class Component:
+class Component(Serializable):
     id: str
     fingerprint: str
     name: str
@@ -1,8 +1,27 @@
 from dataclasses import dataclass
-from typing import Optional
+from typing import Callable, Optional, TypedDict, Union
+from keygen_sh._state import Serializable
+
+class RequestEvent(TypedDict):
+    method: str
+    url: str
+    headers: dict[str, str]
+
+class ResponseEvent(TypedDict):
+    method: str
+    url: str
+    status: int
+    headers: dict[str, str]
+    elapsed: float
+
+class ErrorEvent(TypedDict):
+    method: str
+    url: str
+    error: str
+    elapsed: float
 
 @dataclass(frozen=True)
-class KeygenConfig:
+class KeygenConfig(Serializable):
     api_url: str
     api_version: str
     api_prefix: str
@@ -17,6 +36,23 @@ class KeygenConfig:
     user_agent: Optional[str] = None
     verify_keygen_signature: Optional[bool] = True
     max_clock_drift: Optional[int] = 5
+    strict_signatures: bool = False
+    on_request: Optional[Callable[[RequestEvent], None]] = None
+    on_response: Optional[Callable[[ResponseEvent], None]] = None
+    on_error: Optional[Callable[[ErrorEvent], None]] = None
+    cache_dir: Optional[str] = None
+    cache_grace_period: int = 604800
+    clock_ledger: Optional[str] = None
+    """
+    A file persisting the latest time the clock has been seen at, to detect the clock being rolled back.
+
+    The ledger is protected by an HMAC keyed by the account, product and license key, so anyone who knows them can
+    forge it; it only guards against naively editing the file. Deleting the ledger is detected while the process
+    that saw it runs, but after a restart a missing ledger is simply created again.
+    """
+    fingerprint: Optional[Union[str, Callable[[], str]]] = None
+    naive_datetimes: bool = False
+    token_refresher: Optional[Callable[[], str]] = None
 
 
 def set_config(config: KeygenConfig) -> None:
@@ -1,7 +1,8 @@
 import datetime
 from typing import Optional
+from keygen_sh._state import Serializable
 
-class Entitlement:
+class Entitlement(Serializable):
     id: str
     name: Optional[str]
     code: str
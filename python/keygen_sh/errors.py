@@ -1,4 +1,5 @@
 from dataclasses import dataclass
+import inspect
 import json
 from typing import Type
 from keygen_sh._errors import KeygenError
@@ -15,7 +16,10 @@ class Error(Exception):
         error_type = error_dict.get("type")
         error_class = cls.get_error_class(error_type)
         if isinstance(error_dict.get("details"), str):
-            return error_class()
+            # some errors only describe themselves, e.g. "License file not encrypted"
+            if len(inspect.signature(error_class).parameters) == 0:
+                return error_class()
+            return error_class(error_dict.get("details"))
 
         return error_class(**error_dict.get("details"))
 
@@ -40,6 +44,7 @@ class Error(Exception):
             "PublicKeyMissing": PublicKeyMissing,
             "PublicKeyInvalid": PublicKeyInvalid,
             "LicenseSchemeUnsupported": LicenseSchemeUnsupported,
+            "LicenseKeyDataInvalid": LicenseKeyDataInvalid,
             "CerificateFileInvalid": CerificateFileInvalid,
             "CertificateFileNotGenuine": CertificateFileNotGenuine,
             "CertificateFileNotSupported": CertificateFileNotSupported,
@@ -65,6 +70,7 @@ class Error(Exception):
             "LicenseKeyInvalid": LicenseKeyInvalid,
             "LicenseTokenInvalid": LicenseTokenInvalid,
             "LicenseTooManyMachines": LicenseTooManyMachines,
+            "LicenseTooManyUses": LicenseTooManyUses,
             "LicenseTooManyCores": LicenseTooManyCores,
             "LicenseTooManyProcesses": LicenseTooManyProcesses,
             "MachineAlreadyActivated": MachineAlreadyActivated,
@@ -83,6 +89,15 @@ class Error(Exception):
             "ValidationComponentsMissing": ValidationComponentsMissing,
             "ValidationProductMissing": ValidationProductMissing,
             "NotFound": NotFound,
+            "KeygenSignatureInvalid": KeygenSignatureInvalid,
+            "KeygenSignatureMissing": KeygenSignatureMissing,
+            "ClockTampered": ClockTampered,
+            "FingerprintUnavailable": FingerprintUnavailable,
+            "TokenMissing": TokenMissing,
+            "ArtifactChecksumInvalid": ArtifactChecksumInvalid,
+            "ArtifactSignatureInvalid": ArtifactSignatureInvalid,
+            "WebhookExpired": WebhookExpired,
+            "WebhookReplayed": WebhookReplayed,
         }
         return error_classes.get(error_type, Error)
 
@@ -249,6 +264,15 @@ class LicenseSchemeUnsupported(Error):
         super().__init__("License scheme unsupported")
 
 
+@dataclass
+class LicenseKeyDataInvalid(Error):
+    details: str
+
+    def __init__(self, details: str):
+        super().__init__(details)
+        self.details = details
+
+
 @dataclass
 class CerificateFileInvalid(Error):
     details: str
@@ -498,6 +522,17 @@ class LicenseTooManyMachines(Error):
         self.detail = detail
 
 
+@dataclass
+class LicenseTooManyUses(Error):
+    code: str
+    detail: str
+
+    def __init__(self, code: str, detail: str):
+        super().__init__("License has exceeded its usage limit")
+        self.code = code
+        self.detail = detail
+
+
 @dataclass
 class LicenseTooManyCores(Error):
     code: str
@@ -686,3 +721,84 @@ class NotFound(Error):
         super().__init__("Not found")
         self.code = code
         self.detail = detail
+
+
+@dataclass
+class KeygenSignatureInvalid(Error):
+    reason: str
+
+    def __init__(self, reason: str):
+        super().__init__(f"Keygen signature validation failed: {reason}")
+        self.reason = reason
+
+
+@dataclass
+class KeygenSignatureMissing(Error):
+
+    def __init__(self):
+        super().__init__("Keygen signature missing")
+
+
+@dataclass
+class ClockTampered(Error):
+    reason: str
+    now: str
+    last_seen: str
+
+    def __init__(self, reason: str, now: str, last_seen: str):
+        super().__init__(f"Clock tampering detected: {reason}")
+        self.reason = reason
+        self.now = now
+        self.last_seen = last_seen
+
+
+@dataclass
+class FingerprintUnavailable(Error):
+    reason: str
+
+    def __init__(self, reason: str):
+        super().__init__(f"Fingerprint unavailable: {reason}")
+        self.reason = reason
+
+
+@dataclass
+class TokenMissing(Error):
+
+    def __init__(self):
+        super().__init__("Token is missing")
+
+
+@dataclass
+class ArtifactChecksumInvalid(Error):
+    detail: str
+
+    def __init__(self, detail: str):
+        super().__init__(f"Artifact checksum invalid: {detail}")
+        self.detail = detail
+
+
+@dataclass
+class ArtifactSignatureInvalid(Error):
+    detail: str
+
+    def __init__(self, detail: str):
+        super().__init__(f"Artifact signature invalid: {detail}")
+        self.detail = detail
+
+
+@dataclass
+class WebhookExpired(Error):
+    detail: str
+
+    def __init__(self, detail: str):
+        super().__init__(f"Webhook expired: {detail}")
+        self.detail = detail
+
+
+@dataclass
+class WebhookReplayed(Error):
+    detail: str
+
+    def __init__(self, detail: str):
+        super().__init__(f"Webhook replayed: {detail}")
+        self.detail = detail
@@ -0,0 +1,15 @@
+def machine_fingerprint() -> str:
+    """
+    The fingerprint of this machine, derived from the machine id of the operating system and the
+    product of the global configuration.
+
+    :raises: a keygen_sh.errors.FingerprintUnavailable if the machine id cannot be read
+    """
+    ...
+
+def current_fingerprint() -> str:
+    """
+    The fingerprint of this machine according to the `fingerprint` of the global configuration,
+    which is either a fixed fingerprint or a callable returning one. Defaults to machine_fingerprint().
+    """
+    ...
@@ -0,0 +1,55 @@
+import datetime
+from typing import Any, List, Optional
+from keygen_sh._state import Serializable
+
+class Group(Serializable):
+    id: str
+    name: str
+    max_users: Optional[int]
+    max_licenses: Optional[int]
+    max_machines: Optional[int]
+    metadata: dict[str, Any]
+    created: datetime.datetime
+    updated: datetime.datetime
+
+    @staticmethod
+    async def list(page_size: int = 100) -> List[Group]:
+        """
+        Get all groups with the token of the config, fetching all pages.
+
+        :param page_size: the number of groups per request, at most 100
+        :raises: a keygen_sh.errors.TokenMissing if the config has no token
+        """
+        ...
+
+    @staticmethod
+    async def create(
+        name: str,
+        *,
+        max_users: Optional[int] = None,
+        max_licenses: Optional[int] = None,
+        max_machines: Optional[int] = None,
+        metadata: Optional[dict[str, Any]] = None,
+    ) -> Group:
+        """
+        Create a group with the token of the config.
+
+        :param name: the name of the group
+        """
+        ...
+
+    async def update(
+        self,
+        *,
+        name: Optional[str] = None,
+        max_users: Optional[int] = None,
+        max_licenses: Optional[int] = None,
+        max_machines: Optional[int] = None,
+        metadata: Optional[dict[str, Any]] = None,
+    ) -> Group:
+        """
+        Update this group with the token of the config. Attributes which are None are left as they are.
+
+        :return: the updated group
+        """
+        ...
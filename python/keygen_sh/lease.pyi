@@ -0,0 +1,28 @@
+from types import TracebackType
+from typing import Optional
+
+from keygen_sh.machine import Machine
+
+class Lease:
+    machine: Optional[Machine]
+
+    async def __aenter__(self) -> Machine:
+        """
+        Activates the machine, or reuses it when it is already activated, and starts its
+        heartbeat if needed.
+
+        :return: the activated machine
+        :raises: a keygen_sh.errors.KeygenError if no seat can be acquired
+        """
+        ...
+
+    async def __aexit__(
+        self,
+        exc_type: Optional[type[BaseException]],
+        exc: Optional[BaseException],
+        traceback: Optional[TracebackType],
+    ) -> bool:
+        """
+        Stops the heartbeat and deactivates the machine.
+        """
+        ...
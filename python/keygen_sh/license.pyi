@@ -1,21 +1,177 @@
 import datetime
 from enum import Enum, auto
-from typing import Optional, List
+from typing import Any, Optional, List, Union
 
 from keygen_sh.component import Component
+from keygen_sh.entitlement import Entitlement
+from keygen_sh.lease import Lease
 from machine import Machine
+from keygen_sh.pagination import PageIterator
+from keygen_sh.group import Group
+from keygen_sh.policy import Policy
+from keygen_sh.product import Product
+from keygen_sh.token import Token
+from keygen_sh.user import User
+from keygen_sh.verification import ResponseVerification
+from keygen_sh._state import Serializable
 
 class SchemeCode(Enum):
     Ed25519Sign = auto()
 
-class License:
+class LicenseStatus(Enum):
+    """
+    Status of a license, which compares equal to its name in the API, e.g. "ACTIVE".
+    """
+
+    Active = "ACTIVE"
+    Inactive = "INACTIVE"
+    Expiring = "EXPIRING"
+    Expired = "EXPIRED"
+    Suspended = "SUSPENDED"
+    Banned = "BANNED"
+
+class License(Serializable):
     id: str
     scheme: SchemeCode
     key: str
     name: Optional[str]
     expiry: Optional[datetime.datetime]
-    status: Optional[str]
+    status: Optional[LicenseStatus]
     policy: Optional[str]
+    uses: Optional[int]
+    max_uses: Optional[int]
+    verification: Optional[ResponseVerification]
+
+    def is_expired(self, now: Optional[Union[datetime.datetime, str]] = None) -> bool:
+        """
+        Whether the license has expired. Licenses without an expiry never do.
+
+        :param now: optionally, the time to check at instead of the current time
+        """
+        ...
+
+    def expires_in(self, now: Optional[Union[datetime.datetime, str]] = None) -> Optional[datetime.timedelta]:
+        """
+        Time until the license expires, negative once it has.
+
+        :param now: optionally, the time to measure from instead of the current time
+        :return: the time until expiry, or None if the license has no expiry
+        """
+        ...
+
+    @staticmethod
+    async def create(
+        policy_id: str,
+        name: Optional[str] = None,
+        metadata: Optional[dict[str, Any]] = None,
+        expiry: Optional[Union[datetime.datetime, str]] = None,
+        user: Optional[str] = None,
+    ) -> License:
+        """
+        Create a license, authenticating with the token of the config.
+
+        :param policy_id: the id of the policy of the license
+        :param name: optionally, the name of the license
+        :param metadata: optionally, the metadata of the license
+        :param expiry: optionally, when the license expires, instead of the duration of the policy
+        :param user: optionally, the id of the user owning the license
+        :raises: a keygen_sh.errors.TokenMissing if the config has no token, or a
+            keygen_sh.errors.TokenNotAllowed if the token may not create licenses
+        """
+        ...
+
+    async def update(
+        self,
+        name: Optional[str] = None,
+        metadata: Optional[dict[str, Any]] = None,
+        expiry: Optional[Union[datetime.datetime, str]] = None,
+    ) -> License:
+        """
+        Update this license with the token of the config. Attributes which are None are left as they are.
+
+        :return: the updated license
+        """
+        ...
+
+    async def suspend(self) -> License:
+        """
+        Suspend this license with the token of the config, failing its validations until it is reinstated.
+
+        :return: the updated license
+        """
+        ...
+
+    async def reinstate(self) -> License:
+        """
+        Reinstate this license with the token of the config after it was suspended.
+
+        :return: the updated license
+        """
+        ...
+
+    async def renew(self) -> License:
+        """
+        Renew this license with the token of the config, extending its expiry by the duration of its policy.
+
+        :return: the updated license
+        """
+        ...
+
+    async def increment_usage(self, n: int = 1) -> License:
+        """
+        Record usage of a metered license. This does not require a token.
+
+        :param n: the number of uses to add
+        :return: the updated license
+        :raises: a keygen_sh.errors.LicenseTooManyUses if the license would exceed its max_uses
+        """
+        ...
+
+    async def decrement_usage(self, n: int = 1) -> License:
+        """
+        Take back usage of a metered license with the token of the config.
+
+        :param n: the number of uses to remove
+        :return: the updated license
+        """
+        ...
+
+    async def reset_usage(self) -> License:
+        """
+        Reset the uses of a metered license to zero with the token of the config.
+
+        :return: the updated license
+        """
+        ...
+
+    async def generate_token(
+        self,
+        expiry: Optional[datetime.datetime] = None,
+        max_activations: Optional[int] = None,
+        max_deactivations: Optional[int] = None,
+    ) -> Token:
+        """
+        Generate an activation token for this license with the token of the config, e.g. to hand out to a
+        customer who activates and deactivates their own machines.
+
+        :param expiry: when the token expires, or None for a token which does not expire
+        :param max_activations: how many machines the token may activate, or None for no limit
+        :param max_deactivations: how many machines the token may deactivate, or None for no limit
+        :return: the token, whose `token` is the secret
+        """
+        ...
+
+    async def revoke(self) -> None:
+        """
+        Revoke this license with the token of the config, which deletes it.
+        """
+        ...
+
+    async def delete(self) -> None:
+        """
+        Delete this license with the token of the config.
+        """
+        ...
 
     @staticmethod
     async def activate_machine(
@@ -45,6 +201,61 @@ class License:
         """
     ...
 
+    async def machine_by_fingerprint(self, fingerprint: str) -> Optional[Machine]:
+        """
+        Find the machine of this license with the given fingerprint.
+
+        :param fingerprint: the fingerprint of the machine
+        :return: the machine, or None if no machine of this license has the fingerprint
+        """
+    ...
+
+    async def deactivate_fingerprint(self, fingerprint: str) -> None:
+        """
+        Deactivate the machine of this license with the given fingerprint.
+
+        :param fingerprint: the fingerprint of the machine
+        :raises: a keygen_sh.errors.MachineNotFound if no machine of this license has the fingerprint
+        """
+    ...
+
+    async def deactivate_current(self) -> None:
+        """
+        Deactivate the machine of this license with the fingerprint of the current machine, as
+        returned by keygen_sh.fingerprint.current_fingerprint.
+
+        :raises: a keygen_sh.errors.MachineNotFound if the current machine is not activated
+        """
+    ...
+
+    async def fetch_policy(self) -> Policy:
+        """
+        Fetch the policy of this license, whose id is `policy`.
+        """
+    ...
+
+    async def user(self) -> Optional[User]:
+        """
+        Fetch the user owning this license.
+
+        :return: the user, or None if the license has no owner
+        """
+    ...
+
+    async def group(self) -> Optional[Group]:
+        """
+        Fetch the group of this license.
+
+        :return: the group, or None if the license has no group
+        """
+    ...
+
+    async def product(self) -> Product:
+        """
+        Fetch the product of this license.
+        """
+    ...
+
     async def machine(self, id: str) -> Machine:
         """
         Get the machines associated with this license.
@@ -54,7 +265,65 @@ class License:
 
     async def machines(self) -> List[Machine]:
         """
-        Get the machines associated with this license.
+        Get the machines associated with this license, fetching all pages.
         :return: Machines associated with this license
         """
     ...
+
+    def iter_machines(
+        self,
+        page_size: int = 100,
+        fingerprint: Optional[str] = None,
+        platform: Optional[str] = None,
+        hostname: Optional[str] = None,
+        heartbeat_status: Optional[str] = None,
+    ) -> PageIterator[Machine]:
+        """
+        Iterate over the machines associated with this license with `async for`, one page at a time.
+
+        :param page_size: the number of machines per request, at most 100
+        :param fingerprint: optionally, only the machine with this fingerprint
+        :param platform: optionally, only machines with this platform
+        :param hostname: optionally, only machines with this hostname
+        :param heartbeat_status: optionally, only machines with this heartbeat status, e.g. "ALIVE"
+        """
+    ...
+
+    async def entitlements(self) -> List[Entitlement]:
+        """
+        Get the entitlements of this license, fetching all pages.
+        :return: Entitlements of this license
+        """
+    ...
+
+    def iter_entitlements(self, page_size: int = 100, code: Optional[str] = None) -> PageIterator[Entitlement]:
+        """
+        Iterate over the entitlements of this license with `async for`, one page at a time.
+
+        :param page_size: the number of entitlements per request, at most 100
+        :param code: optionally, only the entitlement with this code
+        """
+    ...
+
+    def lease(
+        self,
+        fingerprint: str,
+        components: Optional[list[Component]] = None,
+        heartbeat_interval: Optional[int] = None,
+        wait: bool = False,
+        poll_interval: int = 30,
+        timeout: Optional[int] = None,
+    ) -> Lease:
+        """
+        Lease a seat of a floating license for the duration of an `async with` block.
+
+        :param fingerprint: the fingerprint of the machine
+        :param components: optionally a list of components
+        :param heartbeat_interval: optionally, seconds between heartbeat pings, defaults to half
+            the heartbeat duration of machines which require a heartbeat
+        :param wait: whether to wait for a seat while the machine limit is exceeded
+        :param poll_interval: seconds between activation attempts while waiting for a seat
+        :param timeout: optionally, seconds to wait for a seat at most
+        :return: the lease, an async context manager
+        """
+    ...
@@ -1,7 +1,13 @@
 import datetime
+from typing import Optional, Union
 
 from keygen_sh import License
 from keygen_sh.certificate import Certificate
+from keygen_sh.group import Group
+from keygen_sh.policy import Policy
+from keygen_sh.product import Product
+from keygen_sh.user import User
+from keygen_sh._state import Serializable
 
 
 class LicenseFileDataset:
@@ -9,14 +15,39 @@ class LicenseFileDataset:
     issued: datetime.datetime
     expiry: datetime.datetime
     ttl: int
+    policy: Optional[Policy]
+    """The policy of the license, if the file was checked out with include=["policy"]."""
+    product: Optional[Product]
+    """The product of the license, if the file was checked out with include=["product"]."""
+    user: Optional[User]
+    """The user owning the license, if the file was checked out with include=["owner"]."""
+    group: Optional[Group]
+    """The group of the license, if the file was checked out with include=["group"]."""
 
-class LicenseFile:
+class LicenseFile(Serializable):
     id: str
     certificate: str
     issued: datetime.datetime
     expiry: datetime.datetime
     ttl: int
 
+    def is_expired(self, now: Optional[Union[datetime.datetime, str]] = None) -> bool:
+        """
+        Whether the file has expired, allowing for the `max_clock_drift` of the global configuration.
+
+        :param now: optionally, the time to check at instead of the current time
+        """
+        ...
+
+    def time_remaining(self, now: Optional[Union[datetime.datetime, str]] = None) -> datetime.timedelta:
+        """
+        Time until the file expires, allowing for the `max_clock_drift` of the global configuration,
+        or zero once it has.
+
+        :param now: optionally, the time to measure from instead of the current time
+        """
+        ...
+
     @staticmethod
     def build_from_cert(key: str, content: str) -> LicenseFile:
         """
@@ -31,9 +62,13 @@ class LicenseFile:
         """
         ...
 
-    def verify(self) -> None:
+    def verify(self, public_key: Optional[Union[str, list[str]]] = None) -> str:
         """
         Verifies the license file
+
+        :param public_key: optionally, a public key or a list of trusted public keys to verify against
+            instead of the public key of the global configuration
+        :return: the public key that matched the signature
         """
         ...
 
@@ -0,0 +1,73 @@
+from os import PathLike
+from typing import Callable, Literal, Optional, TypedDict, Union
+
+from keygen_sh.license import License
+from keygen_sh.license_file import LicenseFile
+from keygen_sh.machine import Machine
+from keygen_sh.machine_file import MachineFile
+
+class FileEvent(TypedDict):
+    type: Literal["refreshed", "refresh_failed", "expiring", "expired"]
+    path: str
+    id: Optional[str]
+    issued: Optional[str]
+    expiry: Optional[str]
+    error: Optional[str]
+
+class LicenseFileManager:
+    path: str
+    current: Optional[Union[LicenseFile, MachineFile]]
+    running: bool
+
+    def __init__(
+        self,
+        target: Union[License, Machine],
+        path: Union[str, PathLike[str]],
+        license_key: Optional[str] = None,
+        ttl: Optional[int] = None,
+        include: Optional[list[str]] = None,
+        refresh_ratio: float = 0.8,
+        expiring_within: Optional[int] = None,
+        retry_interval: int = 60,
+        on_event: Optional[Callable[[FileEvent], None]] = None,
+    ) -> None:
+        """
+        Keeps a checked-out license file of a license, or machine file of a machine, current on disk.
+
+        :param target: the license or machine to check out a file of
+        :param path: the path of the file on disk
+        :param license_key: for machine files, the license key the file is encrypted with,
+            defaults to the license key of the global configuration
+        :param ttl: optionally, the TTL of checked-out files in seconds
+        :param include: optionally, the relationships to include in checked-out files
+        :param refresh_ratio: the share of the TTL after which a file is refreshed
+        :param expiring_within: seconds before expiry from which an `expiring` event is emitted
+            while the file cannot be refreshed, defaults to 10% of the TTL
+        :param retry_interval: seconds between attempts to refresh the file while the API cannot be reached
+        :param on_event: optionally, a callable receiving every event
+        """
+        ...
+
+    async def start(self) -> Union[LicenseFile, MachineFile]:
+        """
+        Loads the file on disk, or checks out a new file when it is missing, invalid or due for
+        a refresh, then refreshes it in the background.
+
+        :return: the current file
+        :raises: a keygen_sh.errors.KeygenError if there is no valid file and none can be checked out
+        """
+        ...
+
+    async def refresh(self) -> Union[LicenseFile, MachineFile]:
+        """
+        Checks out a new file right away.
+
+        :return: the new file
+        """
+        ...
+
+    def stop(self) -> None:
+        """
+        Stops refreshing the file in the background.
+        """
+        ...
@@ -2,8 +2,10 @@ import datetime
 from typing import Optional, List
 
 from keygen_sh.machine_file import MachineFile
+from keygen_sh.verification import ResponseVerification
+from keygen_sh._state import Serializable
 
-class Machine:
+class Machine(Serializable):
     id: str
     fingerprint: str
     name: Optional[str]
@@ -15,6 +17,7 @@ class Machine:
     heartbeat_duration: Optional[int]
     created: datetime.datetime
     updated: datetime.datetime
+    verification: Optional[ResponseVerification]
 
     async def deactivate(self) -> None:
         """
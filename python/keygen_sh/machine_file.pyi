@@ -1,8 +1,10 @@
 import datetime
+from typing import Optional, Union
 
 from keygen_sh import License
 from keygen_sh.certificate import Certificate
 from keygen_sh.machine import Machine
+from keygen_sh._state import Serializable
 
 class MachineFileDataset:
     license: License
@@ -12,13 +14,30 @@ class MachineFileDataset:
     ttl: int
 
 
-class MachineFile:
+class MachineFile(Serializable):
     id: str
     certificate: str
     issued: datetime.datetime
     expiry: datetime.datetime
     ttl: int
 
+    def is_expired(self, now: Optional[Union[datetime.datetime, str]] = None) -> bool:
+        """
+        Whether the file has expired, allowing for the `max_clock_drift` of the global configuration.
+
+        :param now: optionally, the time to check at instead of the current time
+        """
+        ...
+
+    def time_remaining(self, now: Optional[Union[datetime.datetime, str]] = None) -> datetime.timedelta:
+        """
+        Time until the file expires, allowing for the `max_clock_drift` of the global configuration,
+        or zero once it has.
+
+        :param now: optionally, the time to measure from instead of the current time
+        """
+        ...
+
     @staticmethod
     def from_cert(key: str, content: str) -> MachineFile:
         """
@@ -33,9 +52,13 @@ class MachineFile:
         """
         ...
 
-    def verify(self) -> None:
+    def verify(self, public_key: Optional[Union[str, list[str]]] = None) -> str:
         """
         Verify the validity of the MachineFile
+
+        :param public_key: optionally, a public key or a list of trusted public keys to verify against
+            instead of the public key of the global configuration
+        :return: the public key that matched the signature
         """
         ...
 
@@ -0,0 +1,11 @@
+from typing import AsyncIterator, Generic, TypeVar
+
+T = TypeVar("T")
+
+class PageIterator(Generic[T]):
+    """
+    Iterates over the resources of a list endpoint with `async for`, fetching pages as needed.
+    """
+
+    def __aiter__(self) -> AsyncIterator[T]: ...
+    async def __anext__(self) -> T: ...
@@ -0,0 +1,108 @@
+import datetime
+from typing import Any, List, Optional
+from keygen_sh._state import Serializable
+
+class Policy(Serializable):
+    """
+    The rules of the licenses of a policy. Durations and intervals are in seconds.
+    """
+
+    id: str
+    name: Optional[str]
+    duration: Optional[int]
+    strict: bool
+    floating: bool
+    scheme: Optional[str]
+    require_heartbeat: bool
+    heartbeat_duration: Optional[int]
+    max_machines: Optional[int]
+    max_processes: Optional[int]
+    max_cores: Optional[int]
+    max_uses: Optional[int]
+    max_users: Optional[int]
+    fingerprint_uniqueness_strategy: Optional[str]
+    fingerprint_matching_strategy: Optional[str]
+    expiration_strategy: Optional[str]
+    authentication_strategy: Optional[str]
+    require_check_in: bool
+    check_in_interval: Optional[str]
+    check_in_interval_count: Optional[int]
+    metadata: dict[str, Any]
+    created: datetime.datetime
+    updated: datetime.datetime
+
+    @staticmethod
+    async def list(page_size: int = 100) -> List[Policy]:
+        """
+        Get all policies with the token of the config, fetching all pages.
+
+        :param page_size: the number of policies per request, at most 100
+        :raises: a keygen_sh.errors.TokenMissing if the config has no token
+        """
+        ...
+
+    @staticmethod
+    async def create(
+        name: str,
+        product_id: Optional[str] = None,
+        *,
+        duration: Optional[int] = None,
+        strict: Optional[bool] = None,
+        floating: Optional[bool] = None,
+        scheme: Optional[str] = None,
+        require_heartbeat: Optional[bool] = None,
+        heartbeat_duration: Optional[int] = None,
+        max_machines: Optional[int] = None,
+        max_processes: Optional[int] = None,
+        max_cores: Optional[int] = None,
+        max_uses: Optional[int] = None,
+        max_users: Optional[int] = None,
+        fingerprint_uniqueness_strategy: Optional[str] = None,
+        fingerprint_matching_strategy: Optional[str] = None,
+        expiration_strategy: Optional[str] = None,
+        authentication_strategy: Optional[str] = None,
+        require_check_in: Optional[bool] = None,
+        check_in_interval: Optional[str] = None,
+        check_in_interval_count: Optional[int] = None,
+        metadata: Optional[dict[str, Any]] = None,
+    ) -> Policy:
+        """
+        Create a policy with the token of the config. Rules which are None are left to the defaults of the API.
+
+        :param name: the name of the policy
+        :param product_id: optionally, the id of the product instead of the product of the config
+        :raises: a keygen_sh.errors.TokenMissing if the config has no token, or a
+            keygen_sh.errors.TokenNotAllowed if the token may not create policies
+        """
+        ...
+
+    async def update(
+        self,
+        *,
+        name: Optional[str] = None,
+        duration: Optional[int] = None,
+        strict: Optional[bool] = None,
+        floating: Optional[bool] = None,
+        scheme: Optional[str] = None,
+        require_heartbeat: Optional[bool] = None,
+        heartbeat_duration: Optional[int] = None,
+        max_machines: Optional[int] = None,
+        max_processes: Optional[int] = None,
+        max_cores: Optional[int] = None,
+        max_uses: Optional[int] = None,
+        max_users: Optional[int] = None,
+        fingerprint_uniqueness_strategy: Optional[str] = None,
+        fingerprint_matching_strategy: Optional[str] = None,
+        expiration_strategy: Optional[str] = None,
+        authentication_strategy: Optional[str] = None,
+        require_check_in: Optional[bool] = None,
+        check_in_interval: Optional[str] = None,
+        check_in_interval_count: Optional[int] = None,
+        metadata: Optional[dict[str, Any]] = None,
+    ) -> Policy:
+        """
+        Update this policy with the token of the config. Rules which are None are left as they are.
+
+        :return: the updated policy
+        """
+        ...
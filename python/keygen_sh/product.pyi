@@ -0,0 +1,59 @@
+import datetime
+from typing import Any, List, Optional
+from keygen_sh._state import Serializable
+
+class Product(Serializable):
+    id: str
+    name: str
+    code: Optional[str]
+    url: Optional[str]
+    distribution_strategy: Optional[str]
+    platforms: Optional[List[str]]
+    metadata: dict[str, Any]
+    created: datetime.datetime
+    updated: datetime.datetime
+
+    @staticmethod
+    async def list(page_size: int = 100) -> List[Product]:
+        """
+        Get all products with the token of the config, fetching all pages.
+
+        :param page_size: the number of products per request, at most 100
+        :raises: a keygen_sh.errors.TokenMissing if the config has no token
+        """
+        ...
+
+    @staticmethod
+    async def create(
+        name: str,
+        *,
+        code: Optional[str] = None,
+        url: Optional[str] = None,
+        distribution_strategy: Optional[str] = None,
+        platforms: Optional[List[str]] = None,
+        metadata: Optional[dict[str, Any]] = None,
+    ) -> Product:
+        """
+        Create a product with the token of the config.
+
+        :param name: the name of the product
+        :param distribution_strategy: optionally, one of "LICENSED", "OPEN" or "CLOSED"
+        """
+        ...
+
+    async def update(
+        self,
+        *,
+        name: Optional[str] = None,
+        code: Optional[str] = None,
+        url: Optional[str] = None,
+        distribution_strategy: Optional[str] = None,
+        platforms: Optional[List[str]] = None,
+        metadata: Optional[dict[str, Any]] = None,
+    ) -> Product:
+        """
+        Update this product with the token of the config. Attributes which are None are left as they are.
+
+        :return: the updated product
+        """
+        ...
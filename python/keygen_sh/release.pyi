@@ -0,0 +1,51 @@
+import datetime
+from typing import Any, List, Optional
+from keygen_sh.artifact import Artifact
+from keygen_sh._state import Serializable
+
+class Release(Serializable):
+    id: str
+    name: Optional[str]
+    description: Optional[str]
+    channel: str
+    status: Optional[str]
+    tag: Optional[str]
+    version: str
+    metadata: dict[str, Any]
+    yanked: Optional[datetime.datetime]
+    created: datetime.datetime
+    updated: datetime.datetime
+
+    @staticmethod
+    async def list(channel: Optional[str] = None, page_size: int = 100) -> List[Release]:
+        """
+        Get the releases of the product of the config, fetching all pages.
+
+        :param channel: only get releases of this channel, e.g. "stable" or "beta"
+        :param page_size: the number of releases per request, at most 100
+        """
+        ...
+
+    @staticmethod
+    async def upgrade(
+        current_version: str, constraint: Optional[str] = None, channel: Optional[str] = None
+    ) -> Optional[Release]:
+        """
+        Check for an upgrade of the product of the config.
+
+        :param current_version: the semantic version currently installed, e.g. "1.2.3"
+        :param constraint: only upgrade within this version constraint, e.g. "1.0" for any 1.x release
+        :param channel: only upgrade to releases of this channel, e.g. "stable"
+        :return: the newest release later than current_version, or None if there is none
+        :raises: a ValueError if current_version is not a semantic version
+        """
+        ...
+
+    async def artifacts(self, platform: Optional[str] = None, arch: Optional[str] = None) -> List[Artifact]:
+        """
+        Get the artifacts of this release.
+
+        :param platform: only get artifacts for this platform, e.g. "linux"
+        :param arch: only get artifacts for this architecture, e.g. "amd64"
+        """
+        ...
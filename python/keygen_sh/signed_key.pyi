@@ -0,0 +1,20 @@
+from typing import Any
+
+from keygen_sh.license import SchemeCode
+
+class SignedKey:
+    scheme: SchemeCode
+    prefix: str
+    signature: str
+    public_key: str
+    bytes: bytes
+    data: str
+
+    def json(self) -> Any:
+        """
+        Decodes the key data as JSON.
+
+        :return: the decoded JSON payload
+        :raises: a keygen_sh.errors.KeygenError if the data is not valid JSON
+        """
+        ...
@@ -0,0 +1,71 @@
+import datetime
+from typing import Any, Callable, Optional
+
+class Span:
+    operation: str
+    license_id: Optional[str]
+    machine_id: Optional[str]
+    outcome: str
+    error: Optional[str]
+    start_time: datetime.datetime
+    duration: float
+    requests: int
+    retry_count: int
+
+    def attributes(self) -> dict[str, Any]:
+        """
+        The span as OpenTelemetry attributes, e.g. `keygen.operation` and `keygen.license.id`.
+        """
+        ...
+
+class Histogram:
+    count: int
+    sum: float
+    min: float
+    max: float
+    bounds: list[float]
+    bucket_counts: list[int]
+
+class Metrics:
+    counters: dict[tuple[str, str], int]
+    histograms: dict[str, Histogram]
+
+    def count(self, operation: str, outcome: Optional[str] = None) -> int:
+        """
+        Number of calls of an operation.
+
+        :param operation: the operation, e.g. `license.validate`
+        :param outcome: optionally, only count calls with this outcome, e.g. `OK`
+        :return: the number of calls
+        """
+        ...
+
+class InMemoryExporter:
+    spans: list[Span]
+
+    def __init__(self) -> None: ...
+    def __call__(self, span: Span) -> None: ...
+    def __len__(self) -> int: ...
+    def clear(self) -> None: ...
+
+def set_exporter(exporter: Optional[Callable[[Span], None]] = None) -> None:
+    """
+    Sets a callable which receives every finished span.
+
+    :param exporter: the exporter, or None to remove it
+    """
+    ...
+
+def metrics() -> Metrics:
+    """
+    Gets a snapshot of the metrics collected in-process.
+
+    :return: the metrics
+    """
+    ...
+
+def reset_metrics() -> None:
+    """
+    Resets the metrics collected in-process.
+    """
+    ...
@@ -0,0 +1,277 @@
+import datetime
+from typing import Any, Optional, Union
+from keygen_sh.config import KeygenConfig
+
+class Signer:
+    """
+    Signs keys, license files and machine files like Keygen does for a product, with a key pair of its own,
+    so that the offline path can be tested without the API.
+    """
+
+    public_key: str
+    """The hex-encoded public key, to use as the `public_key` of the config."""
+    private_key: str
+
+    def __init__(self, private_key: Optional[str] = None) -> None:
+        """
+        :param private_key: optionally, a hex-encoded Ed25519 private key instead of a newly generated key pair
+        """
+        ...
+
+    def sign(self, message: Union[str, bytes]) -> str:
+        """
+        Sign a message.
+
+        :return: the base64-encoded Ed25519 signature
+        """
+        ...
+
+    def sign_artifact(self, content: Union[str, bytes]) -> str:
+        """
+        Sign an artifact with Ed25519ph, like `keygen dist` does.
+
+        :return: the base64-encoded Ed25519ph signature, to use as the `signature` of the artifact
+        """
+        ...
+
+    def sign_key(self, data: Union[str, bytes]) -> str:
+        """
+        Sign a license key with the ED25519_SIGN scheme.
+
+        :param data: the data to embed in the key
+        :return: the signed key, e.g. "key/....signature"
+        """
+        ...
+
+    def license_file(
+        self,
+        license_key: str,
+        *,
+        license_id: Optional[str] = None,
+        attributes: Optional[dict[str, Any]] = None,
+        included: Optional[list[dict[str, Any]]] = None,
+        issued: Optional[Union[datetime.datetime, str]] = None,
+        expiry: Optional[Union[datetime.datetime, str]] = None,
+        ttl: Optional[int] = None,
+    ) -> str:
+        """
+        Build a license file, encrypted with the license key.
+
+        :param license_key: the key of the license, which the file is encrypted with
+        :param license_id: optionally, the id of the license instead of a random one
+        :param attributes: optionally, attributes of the license, e.g. {"expiry": ..., "metadata": {...}}
+        :param included: optionally, included resources, e.g. a policy or entitlements
+        :param issued: optionally, when the file was issued instead of now
+        :param expiry: optionally, when the file expires instead of `ttl` seconds after it was issued
+        :param ttl: optionally, the ttl of the file in seconds instead of 30 days, or 0 for a file which
+            does not expire
+        :return: the certificate of the license file
+        """
+        ...
+
+    def machine_file(
+        self,
+        license_key: str,
+        fingerprint: str,
+        *,
+        machine_id: Optional[str] = None,
+        attributes: Optional[dict[str, Any]] = None,
+        license_id: Optional[str] = None,
+        license_attributes: Optional[dict[str, Any]] = None,
+        included: Optional[list[dict[str, Any]]] = None,
+        issued: Optional[Union[datetime.datetime, str]] = None,
+        expiry: Optional[Union[datetime.datetime, str]] = None,
+        ttl: Optional[int] = None,
+    ) -> str:
+        """
+        Build a machine file, encrypted like Keygen does with the license key followed by the fingerprint.
+
+        :param license_key: the key of the license of the machine
+        :param fingerprint: the fingerprint of the machine
+        :param machine_id: optionally, the id of the machine instead of a random one
+        :param attributes: optionally, attributes of the machine, e.g. {"name": ..., "platform": ...}
+        :param license_id: optionally, the id of the license instead of a random one
+        :param license_attributes: optionally, attributes of the license
+        :param included: optionally, included resources besides the license
+        :param issued: optionally, when the file was issued instead of now
+        :param expiry: optionally, when the file expires instead of `ttl` seconds after it was issued
+        :param ttl: optionally, the ttl of the file in seconds instead of 30 days
+        :return: the certificate of the machine file
+        """
+        ...
+
+
+class MockKeygenServer:
+    """
+    A fake Keygen API on localhost, for tests which exercise the bindings without the network.
+
+    It serves validation, machines, activation, deactivation, checkouts, heartbeat pings and entitlements of the
+    licenses and policies it is seeded with, and signs its responses and files with its signer. With a token, licenses
+    can also be created, updated, suspended, reinstated, renewed and deleted. It also serves the releases, upgrades and
+    artifact downloads it is seeded with.
+    """
+
+    url: str
+    """The URL of the server, e.g. "http://127.0.0.1:51234", while it is running."""
+    signer: Signer
+    public_key: str
+    requests: list[tuple[str, str]]
+    """The method and path of every request the server received, in order."""
+
+    def __init__(self, signer: Optional[Signer] = None) -> None:
+        """
+        :param signer: optionally, the signer of responses and files instead of a new one
+        """
+        ...
+
+    def start(self) -> str:
+        """
+        Start serving on a free port of localhost.
+
+        :return: the URL of the server
+        """
+        ...
+
+    def stop(self) -> None: ...
+
+    def __enter__(self) -> MockKeygenServer: ...
+
+    def __exit__(self, *args: Any) -> None: ...
+
+    def config(self, license_key: Optional[str] = None, product: str = "mock-product") -> KeygenConfig:
+        """
+        A config which talks to the server and trusts its public key, to pass to `set_config`.
+        """
+        ...
+
+    def add_policy(
+        self,
+        *,
+        id: Optional[str] = None,
+        name: Optional[str] = None,
+        max_machines: Optional[int] = None,
+        floating: bool = False,
+        require_heartbeat: bool = False,
+        heartbeat_duration: Optional[int] = None,
+        duration: Optional[int] = None,
+    ) -> str:
+        """
+        Add a policy. Like the API, a license of a policy which is not floating can only be activated on one machine.
+
+        :param duration: optionally, the seconds a renewal extends the expiry of a license of the policy by
+        :return: the id of the policy
+        """
+        ...
+
+    def add_license(
+        self,
+        key: str,
+        *,
+        id: Optional[str] = None,
+        policy: Optional[str] = None,
+        name: Optional[str] = None,
+        status: str = "ACTIVE",
+        expiry: Optional[Union[datetime.datetime, str]] = None,
+        metadata: Optional[dict[str, Any]] = None,
+        entitlements: Optional[list[str]] = None,
+    ) -> str:
+        """
+        Add a license, or replace the license with the same id.
+
+        :param key: the key of the license
+        :param policy: optionally, the id of the policy of the license
+        :param status: the status of the license, e.g. "SUSPENDED" to fail validation
+        :param expiry: optionally, when the license expires; validation fails after it
+        :param entitlements: optionally, the codes of the entitlements of the license
+        :return: the id of the license
+        """
+        ...
+
+    def add_machine(
+        self,
+        license: str,
+        fingerprint: str,
+        *,
+        platform: Optional[str] = None,
+        hostname: Optional[str] = None,
+        heartbeat_status: str = "NOT_STARTED",
+    ) -> str:
+        """
+        Add a machine to a license, e.g. to list machines of several platforms and hosts. Listing machines filters
+        them by fingerprint, hostname and heartbeat status like the API.
+
+        :param license: the id or key of the license
+        :param heartbeat_status: the heartbeat status of the machine, e.g. "ALIVE" or "DEAD"
+        :return: the id of the machine
+        """
+        ...
+
+    def add_release(self, version: str, *, id: Optional[str] = None, channel: str = "stable") -> str:
+        """
+        Add a release. Upgrades are to the newest release of the channel, within the major version of the constraint.
+
+        :param version: the semver of the release, e.g. "1.2.0"
+        :param id: optionally, the id of the release instead of a random one
+        :param channel: the channel of the release, e.g. "stable" or "beta"
+        :return: the id of the release
+        """
+        ...
+
+    def add_artifact(
+        self,
+        release: str,
+        filename: str,
+        content: bytes,
+        *,
+        id: Optional[str] = None,
+        platform: Optional[str] = None,
+        arch: Optional[str] = None,
+        checksum: Optional[str] = None,
+        signature: Optional[str] = None,
+        served: Optional[bytes] = None,
+    ) -> str:
+        """
+        Add an artifact to a release, which downloads through a link without credentials like the API.
+
+        :param release: the id of the release
+        :param content: the content of the artifact
+        :param checksum: optionally, the checksum instead of the hex-encoded SHA-256 of the content
+        :param signature: optionally, the signature instead of an Ed25519ph signature of the content by the signer
+        :param served: optionally, what the download serves instead of the content, e.g. to test a corrupted download
+        :return: the id of the artifact
+        """
+        ...
+
+    def inject_error(
+        self,
+        code: str,
+        *,
+        status: Optional[int] = None,
+        detail: Optional[str] = None,
+        path: Optional[str] = None,
+        times: int = 1,
+    ) -> None:
+        """
+        Answer the next requests with an API error instead of handling them.
+
+        :param code: the code of the error, e.g. "RATE_LIMIT_EXCEEDED", "LICENSE_EXPIRED" or "LICENSE_SUSPENDED"
+        :param status: optionally, the status of the response instead of the usual one for the code
+        :param detail: optionally, the detail of the error
+        :param path: optionally, only requests whose path contains this, e.g. "machines"
+        :param times: how many requests to answer with the error
+        """
+        ...
+
+    def tamper(self, tamper: str, *, path: Optional[str] = None, times: int = 1) -> None:
+        """
+        Break the signature of the next responses.
+
+        :param tamper: "unsigned" to leave out the signature, "signature" to sign with another key or "body" to alter the body after signing
+        :param path: optionally, only responses to requests whose path contains this, e.g. "validate-key"
+        :param times: how many responses to break
+        """
+        ...
+
+    def reset(self) -> None:
+        """Forget the machines, injected errors, tamperings and requests, keeping the licenses and policies."""
+        ...
@@ -0,0 +1,44 @@
+import datetime
+from typing import List, Optional
+from keygen_sh._state import Serializable
+
+class Token(Serializable):
+    id: str
+    kind: str
+    token: Optional[str]
+    """The secret, which is only known right after the token was generated or regenerated."""
+    name: Optional[str]
+    expiry: Optional[datetime.datetime]
+    permissions: Optional[List[str]]
+    max_activations: Optional[int]
+    activations: Optional[int]
+    max_deactivations: Optional[int]
+    deactivations: Optional[int]
+    created: datetime.datetime
+    updated: datetime.datetime
+
+    @staticmethod
+    async def login(email: str, password: str, expiry: Optional[datetime.datetime] = None) -> Token:
+        """
+        Log in as a user with their email and password, generating a user token.
+
+        :param email: the email of the user
+        :param password: the password of the user
+        :param expiry: when the token expires, or None for the default of the account
+        :return: the token, whose `token` is the secret
+        """
+        ...
+
+    async def regenerate(self) -> Token:
+        """
+        Regenerate this token with the token of the config, which invalidates its current secret.
+
+        :return: the token with its new secret
+        """
+        ...
+
+    async def revoke(self) -> None:
+        """
+        Revoke this token with the token of the config, after which it can no longer be used.
+        """
+        ...
@@ -0,0 +1,61 @@
+import datetime
+from typing import Any, List, Optional
+from keygen_sh._state import Serializable
+
+class User(Serializable):
+    id: str
+    email: str
+    full_name: Optional[str]
+    first_name: Optional[str]
+    last_name: Optional[str]
+    status: Optional[str]
+    role: Optional[str]
+    metadata: dict[str, Any]
+    created: datetime.datetime
+    updated: datetime.datetime
+
+    @staticmethod
+    async def list(page_size: int = 100) -> List[User]:
+        """
+        Get all users with the token of the config, fetching all pages.
+
+        :param page_size: the number of users per request, at most 100
+        :raises: a keygen_sh.errors.TokenMissing if the config has no token
+        """
+        ...
+
+    @staticmethod
+    async def create(
+        email: str,
+        *,
+        first_name: Optional[str] = None,
+        last_name: Optional[str] = None,
+        password: Optional[str] = None,
+        role: Optional[str] = None,
+        metadata: Optional[dict[str, Any]] = None,
+    ) -> User:
+        """
+        Create a user with the token of the config.
+
+        :param email: the email of the user
+        :param password: optionally, a password the user can log in with
+        :param role: optionally, the role of the user, e.g. "user"
+        """
+        ...
+
+    async def update(
+        self,
+        *,
+        email: Optional[str] = None,
+        first_name: Optional[str] = None,
+        last_name: Optional[str] = None,
+        password: Optional[str] = None,
+        role: Optional[str] = None,
+        metadata: Optional[dict[str, Any]] = None,
+    ) -> User:
+        """
+        Update this user with the token of the config. Attributes which are None are left as they are.
+
+        :return: the updated user
+        """
+        ...
@@ -0,0 +1,10 @@
+from typing import Optional
+
+class ResponseVerification:
+    verified: bool
+    algorithm: Optional[str]
+    key_id: Optional[str]
+    date: Optional[str]
+    digest: Optional[str]
+    signature: Optional[str]
+    cached: bool
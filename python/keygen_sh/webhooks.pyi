@@ -0,0 +1,64 @@
+import datetime
+from typing import Any, Callable, Mapping, Optional, Union
+from keygen_sh.license import License
+from keygen_sh.machine import Machine
+from keygen_sh.verification import ResponseVerification
+from keygen_sh._state import Serializable
+
+class WebhookEvent(Serializable):
+    id: str
+    event: str
+    endpoint: Optional[str]
+    status: Optional[str]
+    payload: Any
+    resource_type: Optional[str]
+    created: datetime.datetime
+    updated: datetime.datetime
+    verification: Optional[ResponseVerification]
+    license: Optional[License]
+    """The license of the payload, if the event is about a license, e.g. `license.expired`."""
+    machine: Optional[Machine]
+    """The machine of the payload, if the event is about a machine, e.g. `machine.deleted`."""
+
+def verify(
+    headers: Mapping[str, str],
+    body: Union[bytes, str],
+    public_key: Optional[Union[str, list[str]]] = None,
+    *,
+    path: str,
+    method: str = "POST",
+    seen: Optional[Callable[[str, datetime.datetime], bool]] = None,
+) -> WebhookEvent:
+    """
+    Verify the signature of a webhook event and parse it.
+
+    The webhook must have been signed within `max_clock_drift` minutes of now, and is only accepted once. Without
+    `seen`, the webhooks received before are only known to this process, so a webhook replayed to another worker
+    process or after a restart is accepted.
+
+    `path` and `method` are keyword-only and `path` has no default: the request-target, e.g. "post /webhooks/keygen",
+    is part of the signed string, and only the application knows the path it receives webhooks at.
+
+    :param headers: the headers of the webhook request
+    :param body: the raw body of the webhook request
+    :param public_key: optionally, a public key or a list of trusted public keys to verify against
+        instead of the public key of the global configuration
+    :param path: the path the webhook was sent to, e.g. "/webhooks/keygen"
+    :param method: the method of the webhook request
+    :param seen: optionally, a function called with the signature of the webhook and the time until which it must
+        be remembered, which returns whether the signature was seen before and remembers it otherwise, e.g. with
+        a shared store such as Redis
+    :return: the webhook event
+    :raises: a keygen_sh.errors.KeygenSignatureInvalid if the signature is invalid, a keygen_sh.errors.WebhookExpired
+        if it was signed too long ago, or a keygen_sh.errors.WebhookReplayed if it was already received
+    """
+    ...
+
+def parse(body: Union[bytes, str]) -> WebhookEvent:
+    """
+    Parse a webhook event without verifying it.
+
+    :param body: the raw body of the webhook request
+    :return: the webhook event
+    """
+    ...
@@ -0,0 +1,54 @@
+use crate::client::{from_resource, Client, Response};
+use keygen_rs::errors::Error;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+/// Creates a resource of the given type, e.g. `policies`, with the given relationships.
+pub(crate) async fn create<T: DeserializeOwned>(client: &Client, kind: &str, attributes: Value, relationships: Option<Value>) -> Result<T, Error> {
+    let response = create_response(client, kind, attributes, relationships).await?;
+    from_resource(&response.body["data"])
+}
+
+/// Like `create`, but returns the whole response, e.g. for the usage counts of a license.
+pub(crate) async fn create_response(client: &Client, kind: &str, attributes: Value, relationships: Option<Value>) -> Result<Response, Error> {
+    let mut params = json!({
+      "data": {
+        "type": kind,
+        "attributes": attributes,
+      }
+    });
+    if let Some(relationships) = relationships {
+        params["data"]["relationships"] = relationships;
+    }
+    client.post(kind, Some(&params), None).await
+}
+
+/// Updates the given attributes of the resource of the given type and id.
+pub(crate) async fn update<T: DeserializeOwned>(client: &Client, kind: &str, id: &str, attributes: Value) -> Result<T, Error> {
+    let response = update_response(client, kind, id, attributes).await?;
+    from_resource(&response.body["data"])
+}
+
+/// Like `update`, but returns the whole response.
+pub(crate) async fn update_response(client: &Client, kind: &str, id: &str, attributes: Value) -> Result<Response, Error> {
+    let params = json!({
+      "data": {
+        "type": kind,
+        "attributes": attributes,
+      }
+    });
+    client.patch(&format!("{}/{}", kind, id), Some(&params)).await
+}
+
+/// The relationship to the resource of the given type and id, e.g. the product of a policy.
+pub(crate) fn relationship(kind: &str, id: &str) -> Value {
+    json!({ "data": { "type": kind, "id": id } })
+}
+
+/// Drops the attributes which are left out, i.e. `None` in Python.
+pub(crate) fn attributes(mut attributes: Value) -> Value {
+    if let Some(object) = attributes.as_object_mut() {
+        object.retain(|_, value| !value.is_null());
+    }
+    attributes
+}
@@ -0,0 +1,275 @@
+use crate::client::{from_resource, from_resources, Client, Response};
+use crate::date::Date;
+use crate::errors::KeygenError;
+use crate::identity::Identity;
+use crate::json::JsonValue;
+use crate::state::JsonState;
+use crate::telemetry::instrument;
+use crate::utils::interface_methods;
+use crate::verifier::Verifier;
+use base64::{engine::general_purpose, Engine};
+use chrono::{DateTime, Utc};
+use keygen_rs::errors::Error;
+use pyo3::prelude::*;
+use pyo3::{pyclass, pymodule, Bound, PyErr, PyResult, Python};
+use reqwest::Client as ReqwestClient;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::fs::{self, File};
+use tokio::io::AsyncWriteExt;
+
+#[pymodule(name = "artifact")]
+pub fn artifact_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Hack: workaround for https://github.com/PyO3/pyo3/issues/759
+    Python::attach(|py| {
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("keygen_sh.artifact", m)
+    })?;
+
+    m.add_class::<Artifact>()?;
+    Ok(())
+}
+
+#[pyclass(frozen, module = "keygen_sh.artifact")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Artifact {
+    pub id: String,
+    pub filename: String,
+    pub filetype: Option<String>,
+    pub filesize: Option<u64>,
+    pub platform: Option<String>,
+    pub arch: Option<String>,
+    pub signature: Option<String>,
+    pub checksum: Option<String>,
+    pub status: Option<String>,
+    #[serde(default)]
+    pub metadata: HashMap<String, Value>,
+    pub created: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+}
+
+/// Why a downloaded artifact was rejected.
+struct ArtifactInvalid {
+    kind: &'static str,
+    detail: String,
+}
+
+impl From<ArtifactInvalid> for PyErr {
+    fn from(value: ArtifactInvalid) -> Self {
+        KeygenError::from_kind(value.kind, json!({ "detail": value.detail }))
+    }
+}
+
+/// The digests of a downloaded artifact, computed while it is written to disk.
+struct Digests {
+    sha256: Sha256,
+    sha512: Sha512,
+    /// The SHA-512 of the version ed25519-dalek prehashes Ed25519ph signatures with.
+    prehash: sha2_09::Sha512,
+}
+
+impl Digests {
+    fn new() -> Self {
+        Self { sha256: Sha256::new(), sha512: Sha512::new(), prehash: sha2_09::Digest::new() }
+    }
+
+    fn update(&mut self, chunk: &[u8]) {
+        self.sha256.update(chunk);
+        self.sha512.update(chunk);
+        sha2_09::Digest::update(&mut self.prehash, chunk);
+    }
+}
+
+impl Artifact {
+    /// Builds artifacts from an API response whose `data` is a list of artifact resources.
+    pub(crate) fn from_list_response(response: Response) -> Result<Vec<Artifact>, Error> {
+        from_resources(&response.body["data"])
+    }
+
+    /// Downloads the artifact with the given id to `part`, returning the artifact as currently
+    /// known to the API and the digests of what was downloaded.
+    async fn download_with(artifact_id: &str, part: &str) -> Result<(Artifact, Digests), Error> {
+        let response = Client::default()
+            .without_redirect()
+            .get(&format!("artifacts/{}", artifact_id), None)
+            .await?;
+        let artifact: Artifact = from_resource(&response.body["data"])?;
+        let Some(url) = response.body["data"]["links"]["redirect"].as_str() else {
+            return Err(Error::UnexpectedError(format!("Artifact {} has no download link", artifact_id)));
+        };
+
+        // the download link is signed, so it is requested without credentials, and without the
+        // timeout of API requests since artifacts may be large
+        let client = ReqwestClient::builder().connect_timeout(Duration::from_secs(30)).build()?;
+        let mut download = client.get(url).send().await?.error_for_status()?;
+
+        let mut file = File::create(part).await.map_err(|e| Error::UnexpectedError(format!("Failed to create {}: {}", part, e)))?;
+        let mut digests = Digests::new();
+        while let Some(chunk) = download.chunk().await? {
+            digests.update(&chunk);
+            file.write_all(&chunk).await.map_err(|e| Error::UnexpectedError(format!("Failed to write {}: {}", part, e)))?;
+        }
+        file.flush().await.map_err(|e| Error::UnexpectedError(format!("Failed to write {}: {}", part, e)))?;
+        Ok((artifact, digests))
+    }
+
+    /// Checks the downloaded artifact against its checksum, a SHA-256 or SHA-512 in hex or base64.
+    fn verify_checksum(&self, digests: &Digests) -> Result<(), ArtifactInvalid> {
+        let Some(checksum) = &self.checksum else {
+            return Ok(());
+        };
+        let invalid = |detail: String| ArtifactInvalid { kind: "ArtifactChecksumInvalid", detail };
+
+        let expected = match hex::decode(checksum) {
+            Ok(bytes) => bytes,
+            Err(_) => general_purpose::STANDARD
+                .decode(checksum)
+                .map_err(|_| invalid(format!("checksum {:?} is neither hex nor base64", checksum)))?,
+        };
+        let actual = match expected.len() {
+            32 => digests.sha256.clone().finalize().to_vec(),
+            64 => digests.sha512.clone().finalize().to_vec(),
+            n => return Err(invalid(format!("checksum of {} bytes is neither a SHA-256 nor a SHA-512", n))),
+        };
+        if actual != expected {
+            return Err(invalid(format!("{} does not match its checksum", self.filename)));
+        }
+        Ok(())
+    }
+
+    /// Checks the Ed25519ph signature of the downloaded artifact, as made by `keygen dist`, against
+    /// the public key of the key pair the release was signed with.
+    fn verify_signature(&self, digests: Digests, public_key: &str) -> Result<(), ArtifactInvalid> {
+        let invalid = |detail: String| ArtifactInvalid { kind: "ArtifactSignatureInvalid", detail };
+        let Some(signature) = &self.signature else {
+            return Err(invalid(format!("{} is not signed", self.filename)));
+        };
+
+        Verifier::verify_prehashed(public_key, digests.prehash, signature)
+            .map_err(|e| invalid(format!("{}: {}", self.filename, e)))
+    }
+}
+
+impl JsonState for Artifact {
+    fn to_state(&self) -> Result<Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
+
+    fn from_state(state: Value) -> Result<Self, serde_json::Error> {
+        serde_json::from_value(state)
+    }
+}
+
+impl Identity for Artifact {
+    fn resource_id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+
+    fn repr_fields(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("id", Some(self.id.clone())),
+            ("filename", Some(self.filename.clone())),
+            ("platform", self.platform.clone()),
+            ("arch", self.arch.clone()),
+        ]
+    }
+}
+
+interface_methods! {
+impl Artifact {
+    #[getter]
+    fn id(&self) -> PyResult<String> {
+        Ok(self.id.clone())
+    }
+
+    #[getter]
+    fn filename(&self) -> PyResult<String> {
+        Ok(self.filename.clone())
+    }
+
+    #[getter]
+    fn filetype(&self) -> PyResult<Option<String>> {
+        Ok(self.filetype.clone())
+    }
+
+    #[getter]
+    fn filesize(&self) -> PyResult<Option<u64>> {
+        Ok(self.filesize)
+    }
+
+    #[getter]
+    fn platform(&self) -> PyResult<Option<String>> {
+        Ok(self.platform.clone())
+    }
+
+    #[getter]
+    fn arch(&self) -> PyResult<Option<String>> {
+        Ok(self.arch.clone())
+    }
+
+    #[getter]
+    fn signature(&self) -> PyResult<Option<String>> {
+        Ok(self.signature.clone())
+    }
+
+    #[getter]
+    fn checksum(&self) -> PyResult<Option<String>> {
+        Ok(self.checksum.clone())
+    }
+
+    #[getter]
+    fn status(&self) -> PyResult<Option<String>> {
+        Ok(self.status.clone())
+    }
+
+    #[getter]
+    fn metadata(&self) -> PyResult<JsonValue> {
+        Ok(JsonValue(json!(self.metadata)))
+    }
+
+    #[getter]
+    fn created(&self) -> PyResult<Date> {
+        Ok(Date::from(self.created))
+    }
+
+    #[getter]
+    fn updated(&self) -> PyResult<Date> {
+        Ok(Date::from(self.updated))
+    }
+
+    #[pyo3(signature = (path, public_key=None))]
+    fn download<'a>(&'a self, py: Python<'a>, path: String, public_key: Option<String>) -> PyResult<Bound<'a, PyAny>> {
+        let artifact_id = self.id.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            // the artifact is only moved to `path` once it has been verified
+            let part = format!("{}.part", path);
+            let result = instrument("artifact.download", None, None, Artifact::download_with(&artifact_id, &part)).await;
+            let verified = match result {
+                Ok((artifact, digests)) => artifact.verify_checksum(&digests).and_then(|_| match &public_key {
+                    Some(public_key) => artifact.verify_signature(digests, public_key),
+                    None => Ok(()),
+                }),
+                Err(e) => {
+                    let _ = fs::remove_file(&part).await;
+                    return Err(KeygenError::from_error(e));
+                }
+            };
+            if let Err(e) = verified {
+                let _ = fs::remove_file(&part).await;
+                return Err(e.into());
+            }
+
+            fs::rename(&part, &path)
+                .await
+                .map_err(|e| KeygenError::from_error(Error::UnexpectedError(format!("Failed to move {} to {}: {}", part, path, e))))?;
+            Ok(path)
+        })
+    }
+}
+}
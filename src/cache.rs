@@ -0,0 +1,69 @@
+use crate::config::Settings;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// A signed API response as it was received, so its `Keygen-Signature` can be checked again
+/// when it is replayed.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CacheEntry {
+    pub request_target: String,
+    pub host: String,
+    pub signature: Option<String>,
+    pub date: Option<String>,
+    pub digest: Option<String>,
+    pub body: String,
+}
+
+impl CacheEntry {
+    pub(crate) fn header(&self, name: &str) -> Option<String> {
+        match name {
+            "keygen-signature" => self.signature.clone(),
+            "date" => self.date.clone(),
+            "digest" => self.digest.clone(),
+            _ => None,
+        }
+    }
+
+    /// The signed `Date` of the response, which cannot be altered without breaking the signature.
+    pub(crate) fn date(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc2822(self.date.as_deref()?)
+            .ok()
+            .map(|date| date.with_timezone(&Utc))
+    }
+}
+
+/// Directory of signed validation responses, replayed when the API cannot be reached.
+pub(crate) struct Cache {
+    dir: PathBuf,
+    pub grace_period: i64,
+}
+
+impl Cache {
+    pub(crate) fn from_settings(settings: &Settings) -> Option<Self> {
+        settings.cache_dir.as_ref().map(|dir| Self {
+            dir: PathBuf::from(dir),
+            grace_period: settings.cache_grace_period,
+        })
+    }
+
+    pub(crate) fn store(&self, key: &str, entry: &CacheEntry) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path(key);
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec(entry)?)?;
+        fs::rename(tmp, path)
+    }
+
+    pub(crate) fn load(&self, key: &str) -> Option<CacheEntry> {
+        let bytes = fs::read(self.path(key)).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", hex::encode(Sha256::digest(key.as_bytes()))))
+    }
+}
@@ -0,0 +1,475 @@
+use crate::client::Client;
+use crate::clock;
+use crate::config::{set_config, KeygenConfig};
+use crate::errors::KeygenError;
+use crate::fingerprint::current_fingerprint;
+use crate::json::JsonValue;
+use crate::license::License;
+use crate::license_file::decrypt_payload;
+use crate::machine::Machine;
+use crate::pagination::{Pager, MAX_PAGE_SIZE};
+use crate::state::{JsonState, PyState};
+use crate::verifier::Verifier;
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand};
+use keygen_rs::errors::Error;
+use keygen_rs::license::License as KeygenRsLicense;
+use keygen_rs::license_file::LicenseFile as KeygenRsLicenseFile;
+use keygen_rs::machine_file::MachineFile as KeygenRsMachineFile;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::{pyfunction, pymodule, wrap_pyfunction, Bound, PyErr, PyResult, Python};
+use serde_json::{json, Map, Value};
+use std::env;
+use std::future::Future;
+use std::path::PathBuf;
+
+#[pymodule(name = "cli")]
+pub fn cli_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Hack: workaround for https://github.com/PyO3/pyo3/issues/759
+    Python::attach(|py| {
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("keygen_sh.cli", m)
+    })?;
+
+    m.add_function(wrap_pyfunction!(main, m)?)?;
+    Ok(())
+}
+
+/// Licensing operations from the command line, e.g. to debug the license of a customer.
+///
+/// The config is read from the JSON file of `--config` or $KEYGEN_CONFIG, whose keys are the
+/// arguments of `KeygenConfig`, and from $KEYGEN_API_URL, $KEYGEN_ACCOUNT_ID, $KEYGEN_PRODUCT_ID,
+/// $KEYGEN_LICENSE_KEY, $KEYGEN_PUBLIC_KEY, $KEYGEN_TOKEN and $KEYGEN_ENVIRONMENT, which take
+/// precedence over the file.
+#[derive(Parser)]
+#[command(name = "keygen-py", bin_name = "keygen-py")]
+struct Cli {
+    /// A JSON file with the arguments of `KeygenConfig`
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// The license key, instead of the one of the config
+    #[arg(long, global = true)]
+    license_key: Option<String>,
+
+    /// Print JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Validate the license, optionally scoped to fingerprints and entitlements
+    Validate {
+        /// A fingerprint to scope the validation to; further fingerprints are components
+        #[arg(long = "fingerprint")]
+        fingerprints: Vec<String>,
+        /// The code of an entitlement the license must have
+        #[arg(long = "entitlement")]
+        entitlements: Vec<String>,
+    },
+    /// Verify a signed key offline and print its data
+    Verify {
+        /// The signed key, e.g. "key/....signature"
+        key: String,
+    },
+    /// Activate a machine for the license
+    Activate {
+        /// The fingerprint of the machine, instead of the one of this machine
+        #[arg(long)]
+        fingerprint: Option<String>,
+    },
+    /// Deactivate a machine of the license
+    Deactivate {
+        /// The fingerprint of the machine, instead of the one of this machine
+        #[arg(long)]
+        fingerprint: Option<String>,
+    },
+    /// List the machines of the license
+    Machines,
+    /// Check out a license file, or the machine file of a machine
+    Checkout {
+        /// Check out the machine file of the machine instead of a license file
+        #[arg(long)]
+        machine: bool,
+        /// The fingerprint of the machine, instead of the one of this machine
+        #[arg(long)]
+        fingerprint: Option<String>,
+        /// The ttl of the file in seconds
+        #[arg(long)]
+        ttl: Option<i64>,
+        /// Relationships to include in the file, e.g. "entitlements,policy"
+        #[arg(long, value_delimiter = ',', default_value = "entitlements")]
+        include: Vec<String>,
+        /// A file to write the certificate to, instead of printing it
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Verify a license or machine file, decrypt it with the license key and print it
+    InspectFile {
+        /// The license or machine file, e.g. "license.lic"
+        path: PathBuf,
+        /// The fingerprint a machine file was checked out for, instead of the one of this machine
+        #[arg(long)]
+        fingerprint: Option<String>,
+    },
+    /// Print the fingerprint of this machine
+    Fingerprint,
+}
+
+/// What a command prints: `json` with `--json`, otherwise `text`.
+struct Output {
+    json: Value,
+    text: String,
+}
+
+/// Runs the command line, by default `sys.argv[1:]`, and returns the exit status: 0 on success,
+/// 1 if the command failed and 2 for invalid arguments.
+#[pyfunction]
+#[pyo3(signature = (argv=None))]
+fn main(py: Python<'_>, argv: Option<Vec<String>>) -> PyResult<i32> {
+    let argv = match argv {
+        Some(argv) => argv,
+        None => py.import("sys")?.getattr("argv")?.extract::<Vec<String>>()?.into_iter().skip(1).collect(),
+    };
+    let cli = match Cli::try_parse_from(std::iter::once("keygen-py".to_string()).chain(argv)) {
+        Ok(cli) => cli,
+        Err(e) => {
+            let stream = if e.use_stderr() { "stderr" } else { "stdout" };
+            write(py, stream, &e.render().to_string())?;
+            return Ok(e.exit_code());
+        }
+    };
+
+    let json = cli.json;
+    match run(py, cli) {
+        Ok(output) if json => write(py, "stdout", &pretty(&output.json))?,
+        Ok(output) => write(py, "stdout", &output.text)?,
+        Err(e) => {
+            let (kind, details) = describe_error(py, &e);
+            if json {
+                write(py, "stdout", &pretty(&json!({ "error": { "type": kind, "details": details } })))?;
+            } else {
+                let details = match details {
+                    Value::String(details) => details,
+                    details => details.to_string(),
+                };
+                write(py, "stderr", &format!("error: {}: {}\n", kind, details))?;
+            }
+            return Ok(1);
+        }
+    }
+    Ok(0)
+}
+
+fn run(py: Python<'_>, cli: Cli) -> PyResult<Output> {
+    load_config(py, cli.config, cli.license_key)?;
+
+    match cli.command {
+        Command::Validate { fingerprints, entitlements } => {
+            let key = require_license_key()?;
+            let license = block_on(py, License::validate_key_with(key, &fingerprints, &entitlements))?;
+            let state = license.to_state().map_err(Error::from).map_err(KeygenError::from_error)?;
+            Ok(Output {
+                text: format!("License {} is valid\n{}", license.inner().id, fields(&state)),
+                json: json!({ "valid": true, "license": state }),
+            })
+        }
+        Command::Verify { key } => {
+            let (data, public_key) = Verifier::new(None).verify_key(&key).map_err(KeygenError::from_error)?;
+            let data = String::from_utf8_lossy(&data).into_owned();
+            Ok(Output {
+                text: format!("Key is genuine, signed by {}\n{}\n", public_key, data),
+                json: json!({ "public_key": public_key, "data": data }),
+            })
+        }
+        Command::Activate { fingerprint } => {
+            require_license_key()?;
+            let fingerprint = fingerprint_or_current(py, fingerprint)?;
+            let (machine, activated) = block_on(py, async {
+                let license = current_license().await?;
+                match Machine::find_by_fingerprint(&license.id, &fingerprint).await? {
+                    Some(machine) => Ok((machine, false)),
+                    None => Ok((License::activate_with(&license.id, &fingerprint, &[]).await?, true)),
+                }
+            })?;
+            let state = machine.to_state().map_err(Error::from).map_err(KeygenError::from_error)?;
+            let text = if activated {
+                format!("Activated machine {} for fingerprint {}\n", machine.inner().id, fingerprint)
+            } else {
+                format!("Fingerprint {} is already activated as machine {}\n", fingerprint, machine.inner().id)
+            };
+            Ok(Output { text, json: json!({ "activated": activated, "machine": state }) })
+        }
+        Command::Deactivate { fingerprint } => {
+            require_license_key()?;
+            let fingerprint = fingerprint_or_current(py, fingerprint)?;
+            block_on(py, async {
+                let license = current_license().await?;
+                License::deactivate_fingerprint_with(&license.id, &fingerprint).await
+            })?;
+            Ok(Output {
+                text: format!("Deactivated the machine with fingerprint {}\n", fingerprint),
+                json: json!({ "deactivated": fingerprint }),
+            })
+        }
+        Command::Machines => {
+            require_license_key()?;
+            let machines = block_on(py, async {
+                let license = current_license().await?;
+                Pager::<Machine>::new(format!("licenses/{}/machines", license.id), json!({}), MAX_PAGE_SIZE)
+                    .map_err(|e| Error::UnexpectedError(e.to_string()))?
+                    .collect()
+                    .await
+            })?;
+            let states = machines
+                .iter()
+                .map(JsonState::to_state)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(Error::from)
+                .map_err(KeygenError::from_error)?;
+            let text = machines
+                .iter()
+                .map(|machine| {
+                    let machine = machine.inner();
+                    format!(
+                        "{}  {}  {}  {}\n",
+                        machine.id,
+                        machine.fingerprint,
+                        machine.name.as_deref().unwrap_or("-"),
+                        machine.heartbeat_status
+                    )
+                })
+                .collect();
+            Ok(Output { text, json: Value::Array(states) })
+        }
+        Command::Checkout { machine, fingerprint, ttl, include, output } => {
+            require_license_key()?;
+            let include = include.join(",");
+            let fingerprint = machine.then(|| fingerprint_or_current(py, fingerprint)).transpose()?;
+            let (id, certificate, issued, expiry, ttl) = block_on(py, async {
+                let license = current_license().await?;
+                match &fingerprint {
+                    Some(fingerprint) => {
+                        let machine = Machine::find_by_fingerprint(&license.id, fingerprint)
+                            .await?
+                            .ok_or(Error::MachineNotFound)?;
+                        let file = Machine::checkout_with(&machine.inner().id, ttl, &include).await?;
+                        Ok((file.id, file.certificate, file.issued, file.expiry, file.ttl))
+                    }
+                    None => {
+                        let file = License::checkout_with(&license.id, ttl, &include).await?;
+                        Ok((file.id, file.certificate, file.issued, file.expiry, file.ttl))
+                    }
+                }
+            })?;
+
+            let kind = if machine { "machine" } else { "license" };
+            let mut json = json!({
+                "kind": kind,
+                "id": id,
+                "issued": issued.to_rfc3339(),
+                "expiry": expiry.to_rfc3339(),
+                "ttl": ttl,
+                "certificate": certificate,
+            });
+            let text = match output {
+                Some(path) => {
+                    std::fs::write(&path, &certificate)?;
+                    json["path"] = json!(path.display().to_string());
+                    format!("Wrote the {} file {} to {}, which expires at {}\n", kind, id, path.display(), expiry.to_rfc3339())
+                }
+                None => certificate,
+            };
+            Ok(Output { text, json })
+        }
+        Command::InspectFile { path, fingerprint } => {
+            let content = std::fs::read_to_string(&path)?;
+            inspect_file(py, content, fingerprint)
+        }
+        Command::Fingerprint => {
+            let fingerprint = current_fingerprint(py)?;
+            Ok(Output { text: format!("{}\n", fingerprint), json: json!({ "fingerprint": fingerprint }) })
+        }
+    }
+}
+
+/// Verifies a license or machine file and, given the license key, decrypts it. Unlike
+/// `LicenseFile.build_from_cert`, expired files are inspected too.
+fn inspect_file(py: Python<'_>, content: String, fingerprint: Option<String>) -> PyResult<Output> {
+    let is_machine_file = content.contains("-----BEGIN MACHINE FILE-----");
+    let placeholder = DateTime::<Utc>::UNIX_EPOCH;
+    let (kind, certificate, public_key) = if is_machine_file {
+        let file = KeygenRsMachineFile { id: String::new(), certificate: content, issued: placeholder, expiry: placeholder, ttl: 0 };
+        let public_key = Verifier::new(None).verify_machine_file(&file);
+        ("machine", file.certificate(), public_key)
+    } else {
+        let file = KeygenRsLicenseFile { id: String::new(), certificate: content, issued: placeholder, expiry: placeholder, ttl: 0 };
+        let public_key = Verifier::new(None).verify_license_file(&file);
+        ("license", file.certificate(), public_key)
+    };
+    let certificate = certificate.map_err(KeygenError::from_error)?;
+    let public_key = public_key.map_err(KeygenError::from_error)?;
+
+    let mut json = json!({ "kind": kind, "public_key": public_key, "alg": certificate.alg });
+    let mut text = format!("The {} file is genuine, signed by {}\n", kind, public_key);
+
+    let Some(license_key) = keygen_rs::config::get_config().license_key else {
+        text.push_str("No license key is configured to decrypt it with\n");
+        return Ok(Output { text, json });
+    };
+    let key = if is_machine_file {
+        license_key + &fingerprint_or_current(py, fingerprint)?
+    } else {
+        license_key
+    };
+    let payload = decrypt_payload(&certificate.enc, &key).map_err(KeygenError::from_error)?;
+
+    let meta = &payload["meta"];
+    let expiry = meta["expiry"].as_str().and_then(|expiry| DateTime::parse_from_rfc3339(expiry).ok());
+    let expired = expiry.is_some_and(|expiry| expiry + clock::max_drift() < Utc::now());
+    json["issued"] = meta["issued"].clone();
+    json["expiry"] = meta["expiry"].clone();
+    json["ttl"] = meta["ttl"].clone();
+    json["expired"] = json!(expired);
+    json["data"] = payload.clone();
+
+    text.push_str(&fields(&json!({
+        "issued": meta["issued"],
+        "expiry": meta["expiry"],
+        "ttl": meta["ttl"],
+        "expired": expired,
+    })));
+    text.push('\n');
+    text.push_str(&pretty(&payload));
+    Ok(Output { text, json })
+}
+
+/// Sets the global config from the config file and the environment, like `set_config`.
+fn load_config(py: Python<'_>, path: Option<PathBuf>, license_key: Option<String>) -> PyResult<()> {
+    let mut config = Map::new();
+    config.insert("api_url".into(), json!("https://api.keygen.sh"));
+    config.insert("api_version".into(), json!("1.7"));
+    config.insert("api_prefix".into(), json!("v1"));
+    config.insert("account".into(), json!(""));
+    config.insert("product".into(), json!(""));
+
+    if let Some(path) = path.or_else(|| env::var_os("KEYGEN_CONFIG").map(PathBuf::from)) {
+        let content = std::fs::read_to_string(&path)?;
+        match serde_json::from_str(&content) {
+            Ok(Value::Object(file)) => config.extend(file),
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "{} is not a JSON object of KeygenConfig arguments",
+                    path.display()
+                )))
+            }
+        }
+    }
+
+    let variables = [
+        ("KEYGEN_API_URL", "api_url"),
+        ("KEYGEN_ACCOUNT_ID", "account"),
+        ("KEYGEN_PRODUCT_ID", "product"),
+        ("KEYGEN_LICENSE_KEY", "license_key"),
+        ("KEYGEN_PUBLIC_KEY", "public_key"),
+        ("KEYGEN_TOKEN", "token"),
+        ("KEYGEN_ENVIRONMENT", "environment"),
+    ];
+    for (variable, argument) in variables {
+        if let Ok(value) = env::var(variable) {
+            config.insert(argument.into(), json!(value));
+        }
+    }
+    if let Some(license_key) = license_key {
+        config.insert("license_key".into(), json!(license_key));
+    }
+
+    let config = JsonValue(Value::Object(config)).into_pyobject(py)?;
+    set_config(py, KeygenConfig::from_dict(&config)?)
+}
+
+/// The license key of the global config, which the online commands need besides the account and
+/// product.
+fn require_license_key() -> PyResult<String> {
+    let config = keygen_rs::config::get_config();
+    if config.account.is_empty() {
+        return Err(missing("account"));
+    }
+    if config.product.is_empty() {
+        return Err(missing("product"));
+    }
+    config.license_key.filter(|key| !key.is_empty()).ok_or_else(|| missing("license_key"))
+}
+
+fn missing(argument: &str) -> PyErr {
+    PyValueError::new_err(format!(
+        "no {} is configured, set it in the config file or the environment",
+        argument
+    ))
+}
+
+/// The license of the configured license key, which the API authenticates as.
+async fn current_license() -> Result<KeygenRsLicense, Error> {
+    let response = Client::default().get("me", None).await?;
+    License::from_resource(&response.body["data"])
+}
+
+fn fingerprint_or_current(py: Python<'_>, fingerprint: Option<String>) -> PyResult<String> {
+    match fingerprint {
+        Some(fingerprint) => Ok(fingerprint),
+        None => current_fingerprint(py),
+    }
+}
+
+/// Runs a future on the runtime of the bindings, without the GIL so that hooks can run.
+fn block_on<T: Send>(py: Python<'_>, future: impl Future<Output = Result<T, Error>> + Send) -> PyResult<T> {
+    py.detach(|| pyo3_async_runtimes::tokio::get_runtime().block_on(future))
+        .map_err(KeygenError::from_error)
+}
+
+/// The scalar fields of a JSON object, one per line.
+fn fields(state: &Value) -> String {
+    state
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(_, value)| !value.is_null() && !value.is_object() && !value.is_array())
+        .map(|(name, value)| match value {
+            Value::String(value) => format!("  {}: {}\n", name, value),
+            value => format!("  {}: {}\n", name, value),
+        })
+        .collect()
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default() + "\n"
+}
+
+/// The type and details of an error, as raised by `keygen_sh.errors`.
+fn describe_error(py: Python<'_>, error: &PyErr) -> (String, Value) {
+    let message = error.value(py).to_string();
+    if error.is_instance_of::<KeygenError>(py) {
+        if let Ok(Value::Object(info)) = serde_json::from_str::<Value>(&message) {
+            let kind = info.get("type").and_then(Value::as_str).unwrap_or("KeygenError").to_string();
+            return (kind, info.get("details").cloned().unwrap_or(Value::Null));
+        }
+    }
+    let kind = error
+        .get_type(py)
+        .name()
+        .map(|name| name.to_string())
+        .unwrap_or_else(|_| "Error".to_string());
+    (kind, Value::String(message))
+}
+
+/// Writes to `sys.stdout` or `sys.stderr`, so that output can be captured like any other.
+fn write(py: Python<'_>, stream: &str, text: &str) -> PyResult<()> {
+    py.import("sys")?.getattr(stream)?.call_method1("write", (text,))?;
+    Ok(())
+}
@@ -0,0 +1,569 @@
+use crate::cache::{Cache, CacheEntry};
+use crate::clock;
+use crate::config::{get_settings, Settings};
+use crate::errors::KeygenError;
+use crate::logging::Secrets;
+use crate::telemetry::{record_request, record_retry};
+use crate::verification::ResponseVerification;
+use crate::verifier::{PublicKeys, SignatureParameters, Verifier};
+use base64::{engine::general_purpose, Engine};
+use keygen_rs::config::{get_config, set_config, KeygenConfig};
+use keygen_rs::errors::Error;
+use pyo3::{PyResult, Python};
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE, USER_AGENT};
+use reqwest::{Client as ReqwestClient, Method, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// HTTP client for the Keygen API.
+///
+/// Mirrors the client of `keygen_rs`, which is private to that crate, but keeps the response
+/// headers around so the outcome of the `Keygen-Signature` check can be surfaced to Python.
+pub(crate) struct Client {
+    /// The HTTP client, or why it could not be built, which fails the requests of this client.
+    inner: Result<ReqwestClient, String>,
+    config: KeygenConfig,
+    settings: Settings,
+    cache: Option<Cache>,
+    auth: Auth,
+    /// Ask for a link to follow rather than a redirect, e.g. to the download of an artifact.
+    no_redirect: bool,
+}
+
+/// How a client authenticates its requests.
+#[derive(Debug, Clone)]
+enum Auth {
+    /// With the license key, or with the token when no license key is configured.
+    Default,
+    /// With the token, even when a license key is configured.
+    Token,
+    /// With the email and password of a user, to obtain a user token.
+    Basic { email: String, password: String },
+}
+
+#[derive(Debug)]
+pub(crate) struct Response {
+    #[allow(dead_code)]
+    pub status: StatusCode,
+    pub body: Value,
+    pub verification: ResponseVerification,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorMeta {
+    code: String,
+    detail: String,
+}
+
+impl Client {
+    pub(crate) fn default() -> Self {
+        Self::new(get_config(), get_settings())
+    }
+
+    pub(crate) fn new(config: KeygenConfig, settings: Settings) -> Self {
+        let inner = ReqwestClient::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| e.to_string());
+
+        let cache = Cache::from_settings(&settings);
+        Self { inner, config, settings, cache, auth: Auth::Default, no_redirect: false }
+    }
+
+    /// A client for admin operations, which authenticates with the configured token rather than
+    /// the license key.
+    pub(crate) fn admin() -> PyResult<Self> {
+        let client = Self::default();
+        if client.config.token.is_none() {
+            return Err(KeygenError::from_kind("TokenMissing", json!("Token missing")));
+        }
+        Ok(Self { auth: Auth::Token, ..client })
+    }
+
+    /// A client which authenticates as the user with the given email and password.
+    pub(crate) fn login(email: String, password: String) -> Self {
+        Self { auth: Auth::Basic { email, password }, ..Self::default() }
+    }
+
+    /// A client which asks the API not to redirect, see `no_redirect`.
+    pub(crate) fn without_redirect(self) -> Self {
+        Self { no_redirect: true, ..self }
+    }
+
+    pub(crate) async fn get(&self, path: &str, query: Option<&Value>) -> Result<Response, Error> {
+        self.send(Method::GET, path, None, query, None).await
+    }
+
+    pub(crate) async fn post(&self, path: &str, body: Option<&Value>, query: Option<&Value>) -> Result<Response, Error> {
+        self.send(Method::POST, path, body, query, None).await
+    }
+
+    pub(crate) async fn put(&self, path: &str, body: Option<&Value>) -> Result<Response, Error> {
+        self.send(Method::PUT, path, body, None, None).await
+    }
+
+    pub(crate) async fn patch(&self, path: &str, body: Option<&Value>) -> Result<Response, Error> {
+        self.send(Method::PATCH, path, body, None, None).await
+    }
+
+    pub(crate) async fn delete(&self, path: &str) -> Result<Response, Error> {
+        self.send(Method::DELETE, path, None, None, None).await
+    }
+
+    /// Like `get`, but replays the last signed response for `cache_key` when the API cannot be reached.
+    pub(crate) async fn get_cached(&self, path: &str, cache_key: &str) -> Result<Response, Error> {
+        self.send(Method::GET, path, None, None, Some(cache_key)).await
+    }
+
+    /// Like `post`, but replays the last signed response for `cache_key` when the API cannot be reached.
+    ///
+    /// `cache_key` must identify everything the response depends on besides the path and credentials,
+    /// e.g. the scope of a validation.
+    pub(crate) async fn post_cached(&self, path: &str, body: Option<&Value>, cache_key: &str) -> Result<Response, Error> {
+        self.send(Method::POST, path, body, None, Some(cache_key)).await
+    }
+
+    async fn send(&self, method: Method, path: &str, body: Option<&Value>, query: Option<&Value>, cache_key: Option<&str>) -> Result<Response, Error> {
+        let url = self.url(path, query)?;
+        let key = cache_key;
+        let cache_key = key.map(|key| self.cache_key(&method, &url, key));
+        let started = Instant::now();
+        let secrets = self.secrets(body);
+
+        let result = match self.dispatch(&method, &url, body, started, cache_key.as_deref()).await {
+            Err(e @ Error::TokenExpired { .. }) if self.uses_token() => match self.refreshed() {
+                Some(client) => {
+                    record_retry();
+                    // later requests look the response up with the new token
+                    let cache_key = key.map(|key| client.cache_key(&method, &url, key));
+                    client.dispatch(&method, &url, body, started, cache_key.as_deref()).await
+                }
+                None => Err(e),
+            },
+            result => result,
+        };
+        match result {
+            Err(e) => {
+                log::warn!("{} {} failed after {:?}: {}", method, secrets.redact(url.as_str()), started.elapsed(), secrets.redact(&e.to_string()));
+                self.settings.hooks.error(&method, &url, &e, started.elapsed(), &secrets);
+
+                match (&e, cache_key) {
+                    (Error::HttpClient(_) | Error::UnexpectedError(_), Some(key)) => {
+                        record_retry();
+                        self.replay(&method, &url, body, &key).ok_or(e)
+                    }
+                    _ => Err(e),
+                }
+            }
+            response => response,
+        }
+    }
+
+    async fn dispatch(&self, method: &Method, url: &Url, body: Option<&Value>, started: Instant, cache_key: Option<&str>) -> Result<Response, Error> {
+        let (request_target, host) = signed_target(method, url);
+
+        let request_headers = self.headers(body.is_some())?;
+        let secrets = self.secrets(body);
+        log::debug!("{} {}", method, secrets.redact(url.as_str()));
+        record_request();
+        self.settings.hooks.request(method, url, &request_headers, &secrets);
+
+        let inner = self
+            .inner
+            .as_ref()
+            .map_err(|e| Error::UnexpectedError(format!("failed to build the HTTP client: {}", e)))?;
+        let mut request = inner.request(method.clone(), url.clone()).headers(request_headers);
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        log::debug!("{} {} responded {} in {:?}", method, secrets.redact(url.as_str()), status, started.elapsed());
+        self.settings.hooks.response(method, url, status, &headers, started.elapsed(), &secrets);
+
+        if status.is_client_error() || status.is_server_error() {
+            let error_body: Value = response.json().await.unwrap_or(Value::Null);
+            return Err(match Self::handle_error(status, &headers, error_body) {
+                // the API does not always give a code when a token lacks the permission
+                Error::KeygenApiError { code, detail, .. } if matches!(self.auth, Auth::Token) && status == StatusCode::FORBIDDEN => {
+                    Error::TokenNotAllowed { code, detail }
+                }
+                e => e,
+            });
+        }
+        let bytes = response.bytes().await?;
+
+        let verification = self.verify(&headers, &bytes, &request_target, &host)?;
+        if let (Some(cache), Some(key)) = (&self.cache, cache_key) {
+            // only responses which can be verified again are worth replaying
+            if verification.verified {
+                let entry = CacheEntry {
+                    request_target,
+                    host,
+                    signature: verification.signature.clone(),
+                    date: verification.date.clone(),
+                    digest: verification.digest.clone(),
+                    body: String::from_utf8_lossy(&bytes).into_owned(),
+                };
+                if let Err(e) = cache.store(key, &entry) {
+                    log::warn!("failed to cache response of {} {}: {}", method, secrets.redact(url.as_str()), e);
+                }
+            }
+        }
+
+        let body = if status == StatusCode::NO_CONTENT || bytes.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_slice(&bytes)?
+        };
+
+        Ok(Response { status, body, verification })
+    }
+
+    fn url(&self, path: &str, query: Option<&Value>) -> Result<Url, Error> {
+        let mut url = Url::parse(&self.config.api_url)?;
+
+        if self.config.api_url == "https://api.keygen.sh" {
+            url.path_segments_mut()
+                .map_err(|_| Error::InvalidUrl)?
+                .push(self.config.api_prefix.as_str())
+                .push("accounts")
+                .push(self.config.account.as_str())
+                .extend(path.split('/'));
+        } else {
+            url.path_segments_mut()
+                .map_err(|_| Error::InvalidUrl)?
+                .push(self.config.api_prefix.as_str())
+                .extend(path.split('/'));
+        }
+
+        if let Some(query) = query {
+            url.set_query(Some(&encode_query(query)));
+        }
+        Ok(url)
+    }
+
+    fn headers(&self, has_body: bool) -> Result<HeaderMap, Error> {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static("application/vnd.api+json"));
+        if has_body {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/vnd.api+json"));
+        }
+        if let Some(user_agent) = &self.config.user_agent {
+            headers.insert(USER_AGENT, HeaderValue::from_str(user_agent)?);
+        }
+        if let Some(env) = &self.config.environment {
+            headers.insert("Keygen-Environment", HeaderValue::from_str(env)?);
+        }
+        headers.insert("Keygen-Version", HeaderValue::from_str(&self.config.api_version)?);
+        if self.no_redirect {
+            headers.insert("Prefer", HeaderValue::from_static("no-redirect"));
+        }
+
+        match (&self.auth, &self.config.license_key, &self.config.token) {
+            (Auth::Basic { email, password }, _, _) => {
+                let credentials = general_purpose::STANDARD.encode(format!("{}:{}", email, password));
+                headers.insert(AUTHORIZATION, HeaderValue::from_str(&format!("Basic {}", credentials))?);
+            }
+            (Auth::Default, Some(key), _) => {
+                headers.insert(AUTHORIZATION, HeaderValue::from_str(&format!("License {}", key))?);
+            }
+            (_, _, Some(token)) => {
+                headers.insert(AUTHORIZATION, HeaderValue::from_str(&format!("Bearer {}", token))?);
+            }
+            _ => {}
+        }
+        Ok(headers)
+    }
+
+    /// The credentials a request is made with, to keep them out of the logs and hooks: those
+    /// `headers` authenticates with, and the license key a validation sends in its body.
+    fn secrets(&self, body: Option<&Value>) -> Secrets {
+        let password = match &self.auth {
+            Auth::Basic { password, .. } => Some(password.clone()),
+            _ => None,
+        };
+        let key = body.and_then(|body| body["meta"]["key"].as_str()).map(str::to_string);
+        Secrets::new([self.config.license_key.clone(), self.config.token.clone(), password, key])
+    }
+
+    /// Whether requests are authenticated with the configured token, see `headers`.
+    fn uses_token(&self) -> bool {
+        match self.auth {
+            Auth::Default => self.config.license_key.is_none() && self.config.token.is_some(),
+            Auth::Token => true,
+            Auth::Basic { .. } => false,
+        }
+    }
+
+    /// A client authenticating with a new token in place of the expired one, which is asked from
+    /// the `token_refresher` and replaces the token of the global config.
+    fn refreshed(&self) -> Option<Client> {
+        let mut config = get_config();
+        // another request may have refreshed the token in the meantime
+        if config.token == self.config.token {
+            let refresher = self.settings.token_refresher.as_ref()?;
+            let token = Python::attach(|py| refresher.call0(py)?.extract::<String>(py));
+            match token {
+                Ok(token) => config.token = Some(token),
+                Err(e) => {
+                    log::warn!("token_refresher failed: {}", e);
+                    return None;
+                }
+            }
+            log::info!("refreshed expired token");
+            set_config(config.clone());
+        }
+
+        let config = KeygenConfig { token: config.token, ..self.config.clone() };
+        let cache = Cache::from_settings(&self.settings);
+        Some(Client { inner: self.inner.clone(), config, settings: self.settings.clone(), cache, auth: self.auth.clone(), no_redirect: self.no_redirect })
+    }
+
+    /// Checks the `Keygen-Signature` of a response the same way `keygen_rs` does, additionally
+    /// failing unsigned responses in strict mode.
+    fn verify(&self, headers: &HeaderMap, body: &[u8], request_target: &str, host: &str) -> Result<ResponseVerification, Error> {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+
+        let public_key = self.config.public_key.clone().filter(|key| !key.is_empty());
+        let verification = match public_key {
+            Some(public_key) if self.config.verify_keygen_signature.unwrap_or(true) => {
+                Verifier::new(Some(PublicKeys::One(public_key)))
+                    .verify_signature(header, body, request_target, host)?
+            }
+            _ => {
+                let signature = header("keygen-signature");
+                let parameters = signature.as_deref().and_then(SignatureParameters::parse);
+                ResponseVerification {
+                    verified: false,
+                    algorithm: parameters.as_ref().map(|p| p.algorithm.clone()),
+                    key_id: parameters.map(|p| p.key_id),
+                    date: header("date"),
+                    digest: header("digest"),
+                    signature,
+                    cached: false,
+                }
+            }
+        };
+
+        if self.settings.strict_signatures && !verification.verified {
+            return Err(Error::KeygenSignatureMissing);
+        }
+        Ok(verification)
+    }
+
+    /// Replays the cached response for `key`, provided its signature still verifies against the
+    /// configured public key, it was signed within the grace period and it answered the very same
+    /// request: the signed request-target and host, and the license key and scope of the body.
+    fn replay(&self, method: &Method, url: &Url, body: Option<&Value>, key: &str) -> Option<Response> {
+        let cache = self.cache.as_ref()?;
+        let entry = cache.load(key)?;
+        let public_key = self.config.public_key.clone().filter(|key| !key.is_empty())?;
+
+        let (request_target, host) = signed_target(method, url);
+        if entry.request_target != request_target || entry.host != host {
+            log::warn!(
+                "ignoring cached response of {} {} replayed for {} {}",
+                entry.request_target, entry.host, request_target, host
+            );
+            return None;
+        }
+
+        let verification = Verifier::new(Some(PublicKeys::One(public_key)))
+            .verify_signature(|name| entry.header(name), entry.body.as_bytes(), &request_target, &host);
+        let verification = match verification {
+            Ok(verification) => verification,
+            Err(e) => {
+                log::warn!("ignoring cached response of {}: {}", request_target, e);
+                return None;
+            }
+        };
+
+        let date = entry.date()?;
+        if let Err(e) = clock::check(&[date]) {
+            log::warn!("ignoring cached response of {}: {}", request_target, e);
+            return None;
+        }
+
+        let age = chrono::Utc::now().signed_duration_since(date).num_seconds();
+        if age > cache.grace_period {
+            log::warn!(
+                "ignoring cached response of {}: signed {}s ago, past the grace period of {}s",
+                request_target, age, cache.grace_period
+            );
+            return None;
+        }
+
+        let cached: Value = serde_json::from_str(&entry.body).ok()?;
+        if let Some(mismatch) = body.and_then(|body| Self::mismatch(&body["meta"], &cached)) {
+            log::warn!("ignoring cached response of {}: answered another {}", request_target, mismatch);
+            return None;
+        }
+
+        log::info!("replaying cached response of {} signed {}s ago", request_target, age);
+        Some(Response {
+            status: StatusCode::OK,
+            body: cached,
+            verification: ResponseVerification { cached: true, ..verification },
+        })
+    }
+
+    /// What a cached validation answered differently from the request with the given `meta`: the
+    /// license key, which the API echoes in `meta.key` or as the key of the license, or the scope.
+    fn mismatch(meta: &Value, cached: &Value) -> Option<&'static str> {
+        if let Some(key) = meta["key"].as_str() {
+            let cached_key = cached["meta"]["key"].as_str().or(cached["data"]["attributes"]["key"].as_str());
+            if cached_key != Some(key) {
+                return Some("license key");
+            }
+        }
+        if !meta["scope"].is_null() && cached["meta"]["scope"] != meta["scope"] {
+            return Some("scope");
+        }
+        None
+    }
+
+    /// Identifies a cached response by the API, account and credentials it was requested with.
+    fn cache_key(&self, method: &Method, url: &Url, key: &str) -> String {
+        let credentials = self.config.license_key.as_deref().or(self.config.token.as_deref()).unwrap_or_default();
+        format!("{}\n{}\n{}\n{}\n{}", self.config.account, credentials, method, url, key)
+    }
+
+    fn handle_error(status: StatusCode, headers: &HeaderMap, body: Value) -> Error {
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => Self::handle_rate_limit_error(headers),
+            StatusCode::FORBIDDEN => Self::handle_forbidden_error(&body),
+            _ if status.is_server_error() => Error::UnexpectedError(format!(
+                "Unexpected API error: status={}, body={}",
+                status, body
+            )),
+            _ => Self::handle_other_error(&body),
+        }
+    }
+
+    fn handle_rate_limit_error(headers: &HeaderMap) -> Error {
+        fn number<T: FromStr + Default>(headers: &HeaderMap, name: &str) -> T {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default()
+        }
+
+        Error::RateLimitExceeded {
+            window: headers
+                .get("X-RateLimit-Window")
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("")
+                .to_string(),
+            count: number(headers, "X-RateLimit-Count"),
+            limit: number(headers, "X-RateLimit-Limit"),
+            remaining: number(headers, "X-RateLimit-Remaining"),
+            reset: number(headers, "X-RateLimit-Reset"),
+            retry_after: number(headers, "Retry-After"),
+        }
+    }
+
+    fn handle_forbidden_error(body: &Value) -> Error {
+        match serde_json::from_value::<ErrorMeta>(body["errors"][0].clone()) {
+            Ok(ErrorMeta { code, detail }) => match code.as_str() {
+                "TOKEN_NOT_ALLOWED" => Error::TokenNotAllowed { code, detail },
+                "TOKEN_FORMAT_INVALID" => Error::TokenFormatInvalid { code, detail },
+                "TOKEN_INVALID" => Error::TokenInvalid { code, detail },
+                "TOKEN_EXPIRED" => Error::TokenExpired { code, detail },
+                "LICENSE_NOT_ALLOWED" => Error::LicenseNotAllowed { code, detail },
+                "LICENSE_SUSPENDED" => Error::LicenseSuspended { code, detail },
+                "LICENSE_EXPIRED" => Error::LicenseExpired { code, detail },
+                _ => Error::KeygenApiError { code, detail, body: body.clone() },
+            },
+            Err(_) => Self::unknown_error(body),
+        }
+    }
+
+    fn handle_other_error(body: &Value) -> Error {
+        match serde_json::from_value::<ErrorMeta>(body["errors"][0].clone()) {
+            Ok(ErrorMeta { code, detail }) => match code.as_str() {
+                "ENVIRONMENT_NOT_SUPPORTED" | "ENVIRONMENT_INVALID" => Error::EnvironmentError { code, detail },
+                "MACHINE_HEARTBEAT_DEAD" | "PROCESS_HEARTBEAT_DEAD" => Error::HeartbeatDead { code, detail },
+                "FINGERPRINT_TAKEN" => Error::MachineAlreadyActivated { code, detail },
+                "MACHINE_LIMIT_EXCEEDED" => Error::MachineLimitExceeded { code, detail },
+                "MACHINE_PROCESS_LIMIT_EXCEEDED" => Error::ProcessLimitExceeded { code, detail },
+                "COMPONENTS_FINGERPRINT_CONFLICT" => Error::ComponentConflict { code, detail },
+                "COMPONENTS_FINGERPRINT_TAKEN" => Error::ComponentAlreadyActivated { code, detail },
+                "TOKEN_INVALID" => Error::LicenseTokenInvalid { code, detail },
+                "TOKEN_EXPIRED" => Error::TokenExpired { code, detail },
+                "LICENSE_INVALID" => Error::LicenseKeyInvalid { code, detail },
+                "NOT_FOUND" => Error::NotFound { code, detail },
+                _ => Error::KeygenApiError { code, detail, body: body.clone() },
+            },
+            Err(_) => Self::unknown_error(body),
+        }
+    }
+
+    fn unknown_error(body: &Value) -> Error {
+        Error::KeygenApiError {
+            code: "API_ERROR".to_string(),
+            detail: "Unknown error".to_string(),
+            body: body.clone(),
+        }
+    }
+}
+
+/// Deserializes a JSON:API resource object by merging its `id` into its `attributes`,
+/// which matches the serde representation of the `keygen_rs` types.
+pub(crate) fn from_resource<T: DeserializeOwned>(data: &Value) -> Result<T, Error> {
+    let mut object = data["attributes"].clone();
+    object["id"] = data["id"].clone();
+    Ok(serde_json::from_value(object)?)
+}
+
+/// Deserializes a list of JSON:API resource objects, see `from_resource`.
+pub(crate) fn from_resources<T: DeserializeOwned>(data: &Value) -> Result<Vec<T>, Error> {
+    data.as_array().into_iter().flatten().map(from_resource).collect()
+}
+
+/// Finds the resource of the given type among the resources included in e.g. a license file.
+pub(crate) fn find_included<T: DeserializeOwned>(included: &Value, kind: &str) -> Option<T> {
+    let resource = included.as_array()?.iter().find(|resource| resource["type"] == kind)?;
+    match from_resource(resource) {
+        Ok(resource) => Some(resource),
+        Err(e) => {
+            log::warn!("ignoring included {}: {}", kind, e);
+            None
+        }
+    }
+}
+
+/// The request-target and host a response to `method` `url` is signed for.
+fn signed_target(method: &Method, url: &Url) -> (String, String) {
+    let request_target = match url.query() {
+        Some(query) => format!("{} {}?{}", method.as_str().to_lowercase(), url.path(), query),
+        None => format!("{} {}", method.as_str().to_lowercase(), url.path()),
+    };
+    let host = match (url.host_str(), url.port()) {
+        (Some(h), Some(p)) => format!("{}:{}", h, p),
+        (Some(h), None) => h.to_string(),
+        _ => "api.keygen.sh".to_string(),
+    };
+    (request_target, host)
+}
+
+/// Encodes a flat JSON object as a query string, e.g. `{"limit": 100}` as `limit=100`.
+fn encode_query(query: &Value) -> String {
+    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+    if let Value::Object(params) = query {
+        for (key, value) in params {
+            match value {
+                Value::String(s) => serializer.append_pair(key, s),
+                Value::Null => continue,
+                other => serializer.append_pair(key, &other.to_string()),
+            };
+        }
+    }
+    serializer.finish()
+}
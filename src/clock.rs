@@ -0,0 +1,164 @@
+use crate::config::get_settings;
+use crate::errors::KeygenError;
+use chrono::{DateTime, Duration, Utc};
+use pyo3::PyErr;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// The ledgers which existed at some point during this process, so deleting one is noticed.
+static LEDGERS: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+/// The local clock is behind a time it has already been seen at.
+#[derive(Debug)]
+pub(crate) struct ClockTampered {
+    reason: String,
+    now: DateTime<Utc>,
+    last_seen: DateTime<Utc>,
+}
+
+impl fmt::Display for ClockTampered {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (now {}, last seen {})", self.reason, self.now, self.last_seen)
+    }
+}
+
+impl From<ClockTampered> for PyErr {
+    fn from(value: ClockTampered) -> Self {
+        KeygenError::from_kind(
+            "ClockTampered",
+            json!({
+                "reason": value.reason,
+                "now": value.now.to_rfc3339(),
+                "last_seen": value.last_seen.to_rfc3339(),
+            }),
+        )
+    }
+}
+
+/// The latest time the clock has been seen at, persisted with an HMAC so it cannot be rolled back
+/// by editing the file.
+#[derive(Debug, Serialize, Deserialize)]
+struct Ledger {
+    last_seen: DateTime<Utc>,
+    mac: String,
+}
+
+impl Ledger {
+    fn load(path: &Path, key: &[u8]) -> Result<Option<DateTime<Utc>>, String> {
+        let content = match fs::read(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                if LEDGERS.lock().unwrap().contains(path) {
+                    return Err("Clock ledger was deleted".to_string());
+                }
+                return Ok(None);
+            }
+            Err(e) => return Err(format!("Clock ledger cannot be read: {}", e)),
+        };
+        LEDGERS.lock().unwrap().insert(path.to_path_buf());
+
+        let ledger: Ledger = serde_json::from_slice(&content).map_err(|_| "Clock ledger is corrupt".to_string())?;
+        let mac = hex::decode(&ledger.mac).map_err(|_| "Clock ledger is corrupt".to_string())?;
+        Self::mac(key, ledger.last_seen)
+            .verify_slice(&mac)
+            .map_err(|_| "Clock ledger signature is invalid".to_string())?;
+        Ok(Some(ledger.last_seen))
+    }
+
+    fn store(path: &Path, key: &[u8], last_seen: DateTime<Utc>) {
+        let ledger = Ledger {
+            last_seen,
+            mac: hex::encode(Self::mac(key, last_seen).finalize().into_bytes()),
+        };
+        let write = || -> std::io::Result<()> {
+            if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                fs::create_dir_all(dir)?;
+            }
+            let tmp = path.with_extension("tmp");
+            fs::write(&tmp, serde_json::to_vec(&ledger)?)?;
+            fs::rename(&tmp, path)
+        };
+        match write() {
+            Ok(()) => {
+                LEDGERS.lock().unwrap().insert(path.to_path_buf());
+            }
+            Err(e) => log::warn!("failed to update clock ledger {}: {}", path.display(), e),
+        }
+    }
+
+    fn mac(key: &[u8], last_seen: DateTime<Utc>) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+        mac.update(last_seen.to_rfc3339().as_bytes());
+        mac
+    }
+}
+
+/// Checks that the clock is not behind any of the `observed` times, e.g. the `issued` time of a
+/// license file, nor behind the clock ledger of the config, by more than `max_clock_drift` minutes.
+///
+/// Updates the ledger with the latest time seen.
+pub(crate) fn check(observed: &[DateTime<Utc>]) -> Result<(), ClockTampered> {
+    let config = keygen_rs::config::get_config();
+    let threshold = max_drift();
+    let now = Utc::now();
+
+    let tampered = |reason: &str, last_seen: DateTime<Utc>| ClockTampered {
+        reason: reason.to_string(),
+        now,
+        last_seen,
+    };
+
+    let latest = observed.iter().max().copied();
+    if let Some(latest) = latest.filter(|latest| *latest - now > threshold) {
+        return Err(tampered("System clock is behind the time a file was issued or modified at", latest));
+    }
+
+    let Some(path) = get_settings().clock_ledger else {
+        return Ok(());
+    };
+    let path = Path::new(&path);
+    let key = ledger_key(&config);
+
+    let last_seen = Ledger::load(path, &key).map_err(|reason| tampered(&reason, now))?;
+    let last_seen = last_seen.into_iter().chain(modified(path)).max();
+    if let Some(last_seen) = last_seen.filter(|last_seen| *last_seen - now > threshold) {
+        return Err(tampered("System clock moved backwards since it was last seen", last_seen));
+    }
+
+    let latest = [Some(now), last_seen, latest].into_iter().flatten().max().unwrap_or(now);
+    Ledger::store(path, &key, latest);
+    Ok(())
+}
+
+/// How far the clock may be off, the `max_clock_drift` of the config.
+pub(crate) fn max_drift() -> Duration {
+    Duration::minutes(keygen_rs::config::get_config().max_clock_drift.unwrap_or(5))
+}
+
+/// The time a file was last modified at, if it exists.
+pub(crate) fn modified(path: &Path) -> Option<DateTime<Utc>> {
+    fs::metadata(path).and_then(|m| m.modified()).ok().map(DateTime::<Utc>::from)
+}
+
+/// The ledger is keyed by the account, product and license key, so it only verifies for the
+/// configuration that wrote it.
+///
+/// Anyone who knows the configuration can recompute the key, so the HMAC only guards against
+/// naively editing the file, not against forging it.
+fn ledger_key(config: &keygen_rs::config::KeygenConfig) -> Vec<u8> {
+    let material = format!(
+        "{}:{}:{}",
+        config.account,
+        config.product,
+        config.license_key.as_deref().unwrap_or_default()
+    );
+    Sha256::digest(material.as_bytes()).to_vec()
+}
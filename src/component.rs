@@ -1,6 +1,9 @@
 use pyo3::prelude::*;
 use keygen_rs::component::Component as KeygenRsComponent;
 use crate::json::JsonValue;
+use crate::identity::Identity;
+use crate::state::InnerState;
+use crate::utils::{create_interface, interface_methods};
 
 #[pymodule(name = "component")]
 pub fn component_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -15,19 +18,29 @@ pub fn component_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     Ok(())
 }
 
-#[pyclass(frozen)]
-#[derive(Debug, Clone)]
-pub struct Component {
-    inner: KeygenRsComponent,
+create_interface!(Component, KeygenRsComponent, module = "keygen_sh.component");
+
+impl InnerState for KeygenRsComponent {}
+
+impl Identity for Component {
+    fn resource_id(&self) -> Option<&str> {
+        Some(&self.inner.id)
+    }
+
+    fn repr_fields(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("id", Some(self.inner.id.clone())),
+            ("fingerprint", Some(self.inner.fingerprint.clone())),
+            ("name", Some(self.inner.name.clone())),
+        ]
+    }
 }
 
-#[pymethods]
+interface_methods! {
 impl Component {
     #[new]
     fn new(id: String, fingerprint: String, name: String) -> Self {
-        Self {
-            inner: KeygenRsComponent { id, fingerprint, name }
-        }
+        Self::from(KeygenRsComponent { id, fingerprint, name })
     }
 
     #[getter]
@@ -50,6 +63,7 @@ impl Component {
         Ok(JsonValue(object))
     }
 }
+}
 
 impl From<Component> for KeygenRsComponent {
     fn from(c: Component) -> KeygenRsComponent {
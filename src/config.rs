@@ -1,6 +1,13 @@
+use crate::hooks::Hooks;
+use crate::identity::{redact_secret, Identity};
+use crate::logging;
+use crate::state::PyState;
+use crate::utils::interface_methods;
+use std::sync::{Arc, RwLock};
 use keygen_rs;
 use pyo3::prelude::*;
-use pyo3::{pyclass, pyfunction, pymethods, pymodule, wrap_pyfunction, Bound, PyResult};
+use pyo3::types::PyDict;
+use pyo3::{pyclass, pyfunction, pymodule, wrap_pyfunction, Bound, PyResult};
 
 
 #[pymodule(name = "config")]
@@ -18,9 +25,46 @@ pub fn config_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     Ok(())
 }
 
+/// Settings of the bindings which have no counterpart in `keygen_rs::config::KeygenConfig`.
+#[derive(Debug, Clone)]
+pub(crate) struct Settings {
+    pub strict_signatures: bool,
+    pub hooks: Hooks,
+    pub cache_dir: Option<String>,
+    /// Seconds a cached response is trusted after the date it was signed at.
+    pub cache_grace_period: i64,
+    /// A file persisting the latest time the clock has been seen at, see `clock::check`.
+    pub clock_ledger: Option<String>,
+    /// A fixed fingerprint, or a callable returning the fingerprint of the current machine.
+    pub fingerprint: Option<Arc<Py<PyAny>>>,
+    /// Return naive datetimes in UTC, as before they were timezone-aware.
+    pub naive_datetimes: bool,
+    /// A callable returning a new token, called when the configured token has expired.
+    pub token_refresher: Option<Arc<Py<PyAny>>>,
+}
+
+static SETTINGS: RwLock<Settings> = RwLock::new(Settings {
+    strict_signatures: false,
+    hooks: Hooks::none(),
+    cache_dir: None,
+    cache_grace_period: DEFAULT_CACHE_GRACE_PERIOD,
+    clock_ledger: None,
+    fingerprint: None,
+    naive_datetimes: false,
+    token_refresher: None,
+});
+
+const DEFAULT_CACHE_GRACE_PERIOD: i64 = 7 * 24 * 60 * 60;
+
+pub(crate) fn get_settings() -> Settings {
+    SETTINGS.read().unwrap().clone()
+}
+
 #[pyfunction]
-fn set_config(config: KeygenConfig) -> PyResult<()> {
+pub(crate) fn set_config(py: Python<'_>, config: KeygenConfig) -> PyResult<()> {
+    *SETTINGS.write().unwrap() = config.settings.clone();
     keygen_rs::config::set_config(config.into());
+    logging::refresh_level(py);
     Ok(())
 }
 
@@ -28,18 +72,20 @@ fn set_config(config: KeygenConfig) -> PyResult<()> {
 fn get_config() -> PyResult<KeygenConfig> {
     Ok(KeygenConfig {
         inner: keygen_rs::config::get_config(),
+        settings: get_settings(),
     })
 }
 
-#[pyclass]
+#[pyclass(module = "keygen_sh.config")]
 #[derive(Debug, Clone)]
 pub struct KeygenConfig {
     inner: keygen_rs::config::KeygenConfig,
+    settings: Settings,
 }
 
-#[pymethods]
+interface_methods! {
 impl KeygenConfig {
-    #[pyo3(signature = (api_url, api_version, api_prefix, account, product, package=None, environment=None, license_key=None, token=None, public_key=None, platform=None, user_agent=None, verify_keygen_signature=true, max_clock_drift=5)
+    #[pyo3(signature = (api_url, api_version, api_prefix, account, product, package=None, environment=None, license_key=None, token=None, public_key=None, platform=None, user_agent=None, verify_keygen_signature=true, max_clock_drift=5, strict_signatures=false, on_request=None, on_response=None, on_error=None, cache_dir=None, cache_grace_period=DEFAULT_CACHE_GRACE_PERIOD, clock_ledger=None, fingerprint=None, naive_datetimes=false, token_refresher=None)
     )]
     #[new]
     #[allow(clippy::too_many_arguments)]
@@ -57,7 +103,16 @@ impl KeygenConfig {
                user_agent: Option<String>,
                verify_keygen_signature: Option<bool>,
                max_clock_drift: Option<i64>,
-
+               strict_signatures: bool,
+               on_request: Option<Py<PyAny>>,
+               on_response: Option<Py<PyAny>>,
+               on_error: Option<Py<PyAny>>,
+               cache_dir: Option<String>,
+               cache_grace_period: i64,
+               clock_ledger: Option<String>,
+               fingerprint: Option<Py<PyAny>>,
+               naive_datetimes: bool,
+               token_refresher: Option<Py<PyAny>>,
     ) -> Self {
         KeygenConfig {
             inner: keygen_rs::config::KeygenConfig {
@@ -75,7 +130,21 @@ impl KeygenConfig {
                 platform,
                 user_agent,
                 max_clock_drift,
-            }
+            },
+            settings: Settings {
+                strict_signatures,
+                hooks: Hooks {
+                    on_request: on_request.map(Arc::new),
+                    on_response: on_response.map(Arc::new),
+                    on_error: on_error.map(Arc::new),
+                },
+                cache_dir,
+                cache_grace_period,
+                clock_ledger,
+                fingerprint: fingerprint.map(Arc::new),
+                naive_datetimes,
+                token_refresher: token_refresher.map(Arc::new),
+            },
         }
     }
 
@@ -143,6 +212,118 @@ impl KeygenConfig {
     fn max_clock_drift(&self) -> PyResult<Option<i64>> {
         Ok(self.inner.max_clock_drift)
     }
+
+    #[getter]
+    fn verify_keygen_signature(&self) -> PyResult<Option<bool>> {
+        Ok(self.inner.verify_keygen_signature)
+    }
+
+    #[getter]
+    fn strict_signatures(&self) -> PyResult<bool> {
+        Ok(self.settings.strict_signatures)
+    }
+
+    #[getter]
+    fn on_request(&self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        Ok(self.settings.hooks.on_request.as_ref().map(|hook| hook.clone_ref(py)))
+    }
+
+    #[getter]
+    fn on_response(&self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        Ok(self.settings.hooks.on_response.as_ref().map(|hook| hook.clone_ref(py)))
+    }
+
+    #[getter]
+    fn on_error(&self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        Ok(self.settings.hooks.on_error.as_ref().map(|hook| hook.clone_ref(py)))
+    }
+
+    #[getter]
+    fn cache_dir(&self) -> PyResult<Option<String>> {
+        Ok(self.settings.cache_dir.clone())
+    }
+
+    #[getter]
+    fn cache_grace_period(&self) -> PyResult<i64> {
+        Ok(self.settings.cache_grace_period)
+    }
+
+    #[getter]
+    fn clock_ledger(&self) -> PyResult<Option<String>> {
+        Ok(self.settings.clock_ledger.clone())
+    }
+
+    #[getter]
+    fn fingerprint(&self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        Ok(self.settings.fingerprint.as_ref().map(|fingerprint| fingerprint.clone_ref(py)))
+    }
+
+    #[getter]
+    fn naive_datetimes(&self) -> PyResult<bool> {
+        Ok(self.settings.naive_datetimes)
+    }
+
+    #[getter]
+    fn token_refresher(&self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        Ok(self.settings.token_refresher.as_ref().map(|refresher| refresher.clone_ref(py)))
+    }
+}
+}
+
+/// The state of a config is its constructor arguments, which include callables, so it is kept as
+/// Python objects rather than JSON.
+impl PyState for KeygenConfig {
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let dict = PyDict::new(py);
+        dict.set_item("api_url", self.api_url()?)?;
+        dict.set_item("api_version", self.api_version()?)?;
+        dict.set_item("api_prefix", self.api_prefix()?)?;
+        dict.set_item("account", self.account()?)?;
+        dict.set_item("product", self.product()?)?;
+        dict.set_item("package", self.package()?)?;
+        dict.set_item("environment", self.environment()?)?;
+        dict.set_item("license_key", self.license_key()?)?;
+        dict.set_item("token", self.token()?)?;
+        dict.set_item("public_key", self.public_key()?)?;
+        dict.set_item("platform", self.platform()?)?;
+        dict.set_item("user_agent", self.user_agent()?)?;
+        dict.set_item("verify_keygen_signature", self.verify_keygen_signature()?)?;
+        dict.set_item("max_clock_drift", self.max_clock_drift()?)?;
+        dict.set_item("strict_signatures", self.strict_signatures()?)?;
+        dict.set_item("on_request", self.on_request(py)?)?;
+        dict.set_item("on_response", self.on_response(py)?)?;
+        dict.set_item("on_error", self.on_error(py)?)?;
+        dict.set_item("cache_dir", self.cache_dir()?)?;
+        dict.set_item("cache_grace_period", self.cache_grace_period()?)?;
+        dict.set_item("clock_ledger", self.clock_ledger()?)?;
+        dict.set_item("fingerprint", self.fingerprint(py)?)?;
+        dict.set_item("naive_datetimes", self.naive_datetimes()?)?;
+        dict.set_item("token_refresher", self.token_refresher(py)?)?;
+        Ok(dict.into_any())
+    }
+
+    fn from_dict(dict: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let kwargs = dict.cast::<PyDict>()?;
+        dict.py().get_type::<KeygenConfig>().call((), Some(kwargs))?.extract()
+    }
+}
+
+/// A config is not a resource, so it is only equal to itself.
+impl Identity for KeygenConfig {
+    fn resource_id(&self) -> Option<&str> {
+        None
+    }
+
+    fn repr_fields(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("api_url", Some(self.inner.api_url.clone())),
+            ("account", Some(self.inner.account.clone())),
+            ("product", Some(self.inner.product.clone())),
+            ("environment", self.inner.environment.clone()),
+            ("license_key", self.inner.license_key.as_deref().map(redact_secret)),
+            ("token", self.inner.token.as_deref().map(redact_secret)),
+        ]
+    }
 }
 
 impl From<KeygenConfig> for keygen_rs::config::KeygenConfig {
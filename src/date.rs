@@ -1,5 +1,8 @@
-use chrono::{DateTime, Datelike, Timelike, Utc};
-use pyo3::types::PyDateTime;
+use crate::config::get_settings;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Timelike, Utc};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDateAccess, PyDateTime, PyDelta, PyTimeAccess, PyTzInfo, PyTzInfoAccess};
 use pyo3::{Bound, IntoPyObject, PyErr, Python};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -10,8 +13,11 @@ impl<'py> IntoPyObject<'py> for Date {
     type Output = Bound<'py, Self::Target>;
     type Error = PyErr;
 
+    /// A datetime in UTC, which is naive if `naive_datetimes` is set in the config.
     fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
         let datetime = self.0;
+        let utc = PyTzInfo::utc(py)?;
+        let tzinfo = (!get_settings().naive_datetimes).then_some(&*utc);
 
         PyDateTime::new(
             py,
@@ -22,13 +28,75 @@ impl<'py> IntoPyObject<'py> for Date {
             datetime.minute() as u8,
             datetime.second() as u8,
             datetime.timestamp_subsec_micros(),
-            None
+            tzinfo
         )
     }
 }
 
+impl<'py> FromPyObject<'py> for Date {
+    /// Accepts a datetime, which is taken to be in UTC if it is naive, or an RFC 3339 string.
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(text) = ob.extract::<String>() {
+            return DateTime::parse_from_rfc3339(&text)
+                .map(|datetime| Date(datetime.with_timezone(&Utc)))
+                .map_err(|e| PyValueError::new_err(format!("invalid date {:?}: {}", text, e)));
+        }
+
+        let mut datetime = ob.cast::<PyDateTime>()?.clone();
+        if datetime.get_tzinfo().is_some() {
+            datetime = datetime.call_method1("astimezone", (PyTzInfo::utc(ob.py())?,))?.cast_into()?;
+        }
+        NaiveDate::from_ymd_opt(datetime.get_year(), datetime.get_month().into(), datetime.get_day().into())
+            .and_then(|date| {
+                date.and_hms_micro_opt(
+                    datetime.get_hour().into(),
+                    datetime.get_minute().into(),
+                    datetime.get_second().into(),
+                    datetime.get_microsecond(),
+                )
+            })
+            .map(|datetime| Date(datetime.and_utc()))
+            .ok_or_else(|| PyValueError::new_err("invalid date"))
+    }
+}
+
 impl From<DateTime<Utc>> for Date {
     fn from(value: DateTime<Utc>) -> Self {
         Date(value)
     }
 }
+
+impl From<Date> for DateTime<Utc> {
+    fn from(value: Date) -> Self {
+        value.0
+    }
+}
+
+/// A duration, as a Python timedelta.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Delta(Duration);
+
+impl<'py> IntoPyObject<'py> for Delta {
+    type Target = PyDelta;
+    type Output = Bound<'py, Self::Target>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let seconds = self.0.num_seconds();
+        let micros = (self.0 - Duration::seconds(seconds)).num_microseconds().unwrap_or_default();
+        let days = i32::try_from(seconds.div_euclid(86400)).map_err(|_| PyValueError::new_err("duration out of range"))?;
+
+        PyDelta::new(py, days, seconds.rem_euclid(86400) as i32, micros as i32, true)
+    }
+}
+
+impl From<Duration> for Delta {
+    fn from(value: Duration) -> Self {
+        Delta(value)
+    }
+}
+
+/// The given time, or the current time.
+pub(crate) fn now_or(now: Option<Date>) -> DateTime<Utc> {
+    now.map(DateTime::from).unwrap_or_else(Utc::now)
+}
@@ -1,6 +1,14 @@
+use crate::client::{from_resources, Response};
+use crate::identity::Identity;
+use crate::state::JsonState;
+use crate::utils::interface_methods;
+use crate::date::Date;
 use chrono::{DateTime, Utc};
+use keygen_rs::errors::Error;
 use pyo3::prelude::{PyAnyMethods, PyModule, PyModuleMethods};
 use pyo3::{pyclass, pymodule, Bound, PyResult, Python};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 #[pymodule(name = "entitlement")]
 pub fn entitlement_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -15,8 +23,8 @@ pub fn entitlement_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     Ok(())
 }
 
-#[pyclass(frozen)]
-#[derive(Debug, Clone)]
+#[pyclass(frozen, module = "keygen_sh.entitlement")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Entitlement {
     pub id: String,
     pub name: Option<String>,
@@ -26,13 +34,61 @@ pub struct Entitlement {
 }
 
 impl Entitlement {
-    pub(crate) fn build(
-        id: String,
-        name: Option<String>,
-        code: String,
-        created: DateTime<Utc>,
-        updated: DateTime<Utc>,
-    ) -> Self {
-        Self { id, name, code, created, updated }
-    }
-}
\ No newline at end of file
+    /// Builds entitlements from an API response whose `data` is a list of entitlement resources.
+    pub(crate) fn from_list_response(response: Response) -> Result<Vec<Entitlement>, Error> {
+        from_resources(&response.body["data"])
+    }
+}
+
+impl JsonState for Entitlement {
+    fn to_state(&self) -> Result<Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
+
+    fn from_state(state: Value) -> Result<Self, serde_json::Error> {
+        serde_json::from_value(state)
+    }
+}
+
+impl Identity for Entitlement {
+    fn resource_id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+
+    fn repr_fields(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("id", Some(self.id.clone())),
+            ("code", Some(self.code.clone())),
+            ("name", self.name.clone()),
+        ]
+    }
+}
+
+interface_methods! {
+impl Entitlement {
+    #[getter]
+    fn id(&self) -> PyResult<String> {
+        Ok(self.id.clone())
+    }
+
+    #[getter]
+    fn name(&self) -> PyResult<Option<String>> {
+        Ok(self.name.clone())
+    }
+
+    #[getter]
+    fn code(&self) -> PyResult<String> {
+        Ok(self.code.clone())
+    }
+
+    #[getter]
+    fn created(&self) -> PyResult<Date> {
+        Ok(Date::from(self.created))
+    }
+
+    #[getter]
+    fn updated(&self) -> PyResult<Date> {
+        Ok(Date::from(self.updated))
+    }
+}
+}
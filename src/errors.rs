@@ -6,6 +6,9 @@ use serde_json::json;
 
 create_exception!(errors_module, KeygenError, PyException);
 
+/// Codes of validations and API errors caused by exceeding the usage limit of a license.
+pub(crate) const USAGE_LIMIT_CODES: &[&str] = &["TOO_MANY_USES", "USAGE_EXCEEDED"];
+
 #[pymodule]
 #[pyo3(name = "_errors")]
 pub fn errors_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -20,305 +23,155 @@ pub fn errors_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
 }
 
 impl KeygenError {
+    /// Builds an error raised as the class `kind` of `keygen_sh.errors`, e.g. for failures that
+    /// originate in the bindings rather than in `keygen_rs`.
+    pub(crate) fn from_kind(kind: &str, details: serde_json::Value) -> PyErr {
+        let info = json!({ "type": kind, "details": details });
+        serde_json::to_string(&info)
+            .map(KeygenError::new_err)
+            .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
+    }
+
     pub(crate) fn from_error(value: Error) -> PyErr {
-        match value {
-            Error::UnexpectedError(detail) => {
-                let info = json!({ "type": "UnexpectedError", "details": detail });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::InvalidUrl => {
-                let info = json!({ "type": "InvalidUrl", "details": "Invalid URL" });
-                KeygenError::new_err(serde_json::to_string(&info).unwrap_or_else(|_| "Serialization error".to_string()))
-            }
-            Error::SystemClockUnsynced => {
-                let info = json!({ "type": "SystemClockUnsynced", "details": "System clock unsynced" });
-                KeygenError::new_err(serde_json::to_string(&info).unwrap_or_else(|_| "Serialization error".to_string()))
-            }
-            Error::DecryptionError(detail) => {
-                let info = json!({ "type": "DecryptionError", "details": detail });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::RateLimitExceeded { window, count, limit, remaining, reset, retry_after } => {
-                let info = json!({ "type": "RateLimitExceeded", "details": { "window": window, "count" : count, "limit": limit, "remaining": remaining, "reset": reset, "retry_after": retry_after } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::LicenseKeyMissing => {
-                let info = json!({ "type": "LicenseKeyMissing", "details": "License key missing" });
-                KeygenError::new_err(serde_json::to_string(&info).unwrap_or_else(|_| "Serialization error".to_string()))
-            }
-            Error::LicenseSchemeMissing => {
-                let info = json!({ "type": "LicenseSchemeMissing", "details": "License scheme missing" });
-                KeygenError::new_err(serde_json::to_string(&info).unwrap_or_else(|_| "Serialization error".to_string()))
-            }
-            Error::LicenseSchemeNotSupported => {
-                let info = json!({ "type": "LicenseSchemeNotSupported", "details": "License scheme not supported" });
-                KeygenError::new_err(serde_json::to_string(&info).unwrap_or_else(|_| "Serialization error".to_string()))
-            }
-            Error::LicenseNotSigned => {
-                let info = json!({ "type": "LicenseNotSigned", "details": "License not signed" });
-                KeygenError::new_err(serde_json::to_string(&info).unwrap_or_else(|_| "Serialization error".to_string()))
-            }
-            Error::LicenseKeyNotGenuine => {
-                let info = json!({ "type": "LicenseKeyNotGenuine", "details": "License key not genuine" });
-                KeygenError::new_err(serde_json::to_string(&info).unwrap_or_else(|_| "Serialization error".to_string()))
-            }
-            Error::PublicKeyMissing => {
-                let info = json!({ "type": "PublicKeyMissing", "details": "Public key missing" });
-                KeygenError::new_err(serde_json::to_string(&info).unwrap_or_else(|_| "Serialization error".to_string()))
-            }
-            Error::PublicKeyInvalid => {
-                let info = json!({ "type": "PublicKeyInvalid", "details": "Public key invalid" });
-                KeygenError::new_err(serde_json::to_string(&info).unwrap_or_else(|_| "Serialization error".to_string()))
-            }
-            Error::LicenseSchemeUnsupported => {
-                let info = json!({ "type": "LicenseSchemeUnsupported", "details": "License scheme unsupported" });
-                KeygenError::new_err(serde_json::to_string(&info).unwrap_or_else(|_| "Serialization error".to_string()))
-            }
-            Error::CerificateFileInvalid(detail) => {
-                let info = json!({ "type": "CerificateFileInvalid", "details": detail });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::CertificateFileNotGenuine(detail) => {
-                let info = json!({ "type": "CertificateFileNotGenuine", "details": detail });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::CertificateFileNotSupported(detail) => {
-                let info = json!({ "type": "CertificateFileNotSupported", "details": detail });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::CerificateFileExpired => {
-                let info = json!({ "type": "CerificateFileExpired", "details": "Certificate file expired" });
-                KeygenError::new_err(serde_json::to_string(&info).unwrap_or_else(|_| "Serialization error".to_string()))
-            }
-            Error::LicenseFileInvalid(detail) => {
-                let info = json!({ "type": "LicenseFileInvalid", "details": detail });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::LicenseFileNotGenuine(detail) => {
-                let info = json!({ "type": "LicenseFileNotGenuine", "details": detail });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::LicenseFileNotSupported(detail) => {
-                let info = json!({ "type": "LicenseFileNotSupported", "details": detail });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::LicenseFileNotEncrypted => {
-                let info = json!({ "type": "LicenseFileNotEncrypted", "details": "License file not encrypted" });
-                KeygenError::new_err(serde_json::to_string(&info).unwrap_or_else(|_| "Serialization error".to_string()))
-            }
-            Error::LicenseFileExpired(detail) => {
-                let info = json!({ "type": "LicenseFileExpired", "details": detail });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::MachineFileInvalid(detail) => {
-                let info = json!({ "type": "MachineFileInvalid", "details": detail });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::MachineFileNotGenuine(detail) => {
-                let info = json!({ "type": "MachineFileNotGenuine", "details": detail });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::MachineFileNotSupported(detail) => {
-                let info = json!({ "type": "MachineFileNotSupported", "details": detail });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::MachineFileExpired(detail) => {
-                let info = json!({ "type": "MachineFileExpired", "details": detail });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::KeygenApiError {code, detail, body} => {
-                let info = json!({ "type": "KeygenApiError", "details": {
-                    "code": code,
-                    "detail": detail,
-                    "body": body
-                } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::TokenNotAllowed { detail, code } => {
-                let info = json!({ "type": "TokenNotAllowed", "details": { "detail": detail, "code": code } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::TokenFormatInvalid {code, detail} => {
-                let info = json!({ "type": "TokenFormatInvalid", "details": { "detail": detail, "code": code} });
-                KeygenError::new_err(serde_json::to_string(&info).unwrap_or_else(|_| "Serialization error".to_string()))
-            }
-            Error::TokenInvalid {code, detail} => {
-                let info = json!({ "type": "TokenInvalid", "details": {"detail": detail, "code": code} });
-                KeygenError::new_err(serde_json::to_string(&info).unwrap_or_else(|_| "Serialization error".to_string()))
-            }
-            Error::TokenExpired {code, detail} => {
-                let info = json!({ "type": "TokenExpired", "details": { "detail": detail, "code": code } });
-                KeygenError::new_err(serde_json::to_string(&info).unwrap_or_else(|_| "Serialization error".to_string()))
-            }
-            Error::LicenseSuspended { code, detail } => {
-                let info = json!({ "type": "LicenseSuspended", "details": { "detail": detail, "code": code  } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::LicenseExpired { detail, code } => {
-                let info = json!({ "type": "LicenseExpired", "details": { "detail": detail, "code": code  } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::LicenseNotAllowed { detail, code } => {
-                let info = json!({ "type": "LicenseNotAllowed", "details": { "detail": detail, "code": code } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::LicenseNotActivated { code, detail, license } => {
-                let info = json!({ "type": "LicenseNotActivated", "details": { "detail": detail, "code": code, "license": license } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::LicenseKeyInvalid { detail, code } => {
-                let info = json!({ "type": "LicenseKeyInvalid", "details": { "detail": detail, "code": code  } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::LicenseTokenInvalid { detail, code } => {
-                let info = json!({ "type": "LicenseTokenInvalid", "details": { "detail": detail, "code": code  } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::LicenseTooManyMachines { detail, code } => {
-                let info = json!({ "type": "LicenseTooManyMachines", "details": { "detail": detail, "code": code  } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::LicenseTooManyCores { code, detail } => {
-                let info = json!({ "type": "LicenseTooManyCores", "details": { "detail": detail, "code": code  }});
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::LicenseTooManyProcesses { detail, code } => {
-                let info = json!({ "type": "LicenseTooManyProcesses", "details": { "detail": detail, "code": code  } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::MachineAlreadyActivated { detail, code } => {
-                let info = json!({ "type": "MachineAlreadyActivated", "details": { "detail": detail, "code": code  } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::MachineLimitExceeded { detail, code} => {
-                let info = json!({ "type": "MachineLimitExceeded", "details": { "detail": detail, "code": code  } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::MachineNotFound => {
-                let info = json!({ "type": "MachineNotFound", "details": "Machine not found" });
-                KeygenError::new_err(serde_json::to_string(&info).unwrap_or_else(|_| "Serialization error".to_string()))
-            }
-            Error::ProcessLimitExceeded { detail, code } => {
-                let info = json!({ "type": "ProcessLimitExceeded", "details": {"detail": detail, "code": code } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::ProcessNotFound => {
-                let info = json!({ "type": "ProcessNotFound", "details": "Process not found" });
-                KeygenError::new_err(serde_json::to_string(&info).unwrap_or_else(|_| "Serialization error".to_string()))
-            }
-            Error::ComponentConflict { code, detail } => {
-                let info = json!({ "type": "ComponentConflict", "details": { "detail": detail, "code": code } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::ComponentAlreadyActivated { code, detail } => {
-                let info = json!({ "type": "ComponentAlreadyActivated", "details": { "detail": detail, "code": code  } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::ComponentNotActivated { code, detail } => {
-                let info = json!({ "type": "ComponentNotActivated", "details": { "detail": detail, "code": code  } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::EnvironmentError { code, detail } => {
-                let info = json!({ "type": "EnvironmentError", "details": { "detail": detail, "code": code } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::HeartbeatDead { code, detail } => {
-                let info = json!({ "type": "HeartbeatDead", "details": { "detail": detail, "code": code  } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::HeartbeatPingFailed { code, detail } => {
-                let info = json!({ "type": "HeartbeatPingFailed", "details": { "detail": detail, "code": code  } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::HeartbeatRequired { code, detail } => {
-                let info = json!({ "type": "HeartbeatRequired", "details": { "detail": detail, "code": code  } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::ValidationFingerprintMissing { code, detail } => {
-                let info = json!({ "type": "ValidationFingerprintMissing", "details": { "detail": detail, "code": code  } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            Error::ValidationComponentsMissing { code, detail } => {
-                let info = json!({ "type": "ValidationComponentsMissing", "details": { "detail": detail, "code": code } });
-                serde_json::to_string(&info)
-                    .map(KeygenError::new_err)
-                    .unwrap_or_else(|_| KeygenError::new_err("Serialization error"))
-            }
-            _ => {
-                KeygenError::new_err("Unknown error")
-            }
-        }
+        let kind = error_type(&value);
+        let details = match value {
+            Error::UnexpectedError(detail) => json!(detail),
+            Error::InvalidUrl => json!("Invalid URL"),
+            Error::SystemClockUnsynced => json!("System clock unsynced"),
+            Error::DecryptionError(detail) => json!(detail),
+            Error::RateLimitExceeded { window, count, limit, remaining, reset, retry_after } => json!({ "window": window, "count": count, "limit": limit, "remaining": remaining, "reset": reset, "retry_after": retry_after }),
+            Error::LicenseKeyMissing => json!("License key missing"),
+            Error::LicenseSchemeMissing => json!("License scheme missing"),
+            Error::LicenseSchemeNotSupported => json!("License scheme not supported"),
+            Error::LicenseNotSigned => json!("License not signed"),
+            Error::LicenseKeyNotGenuine => json!("License key not genuine"),
+            Error::PublicKeyMissing => json!("Public key missing"),
+            Error::PublicKeyInvalid => json!("Public key invalid"),
+            Error::LicenseSchemeUnsupported => json!("License scheme unsupported"),
+            Error::CerificateFileInvalid(detail) => json!(detail),
+            Error::CertificateFileNotGenuine(detail) => json!(detail),
+            Error::CertificateFileNotSupported(detail) => json!(detail),
+            Error::CerificateFileExpired => json!("Certificate file expired"),
+            Error::LicenseFileInvalid(detail) => json!(detail),
+            Error::LicenseFileNotGenuine(detail) => json!(detail),
+            Error::LicenseFileNotSupported(detail) => json!(detail),
+            Error::LicenseFileNotEncrypted => json!("License file not encrypted"),
+            Error::LicenseFileExpired(detail) => json!({ "dataset": detail }),
+            Error::MachineFileInvalid(detail) => json!(detail),
+            Error::MachineFileNotGenuine(detail) => json!(detail),
+            Error::MachineFileNotSupported(detail) => json!(detail),
+            Error::MachineFileExpired(detail) => json!({ "dataset": detail }),
+            // `keygen_rs` has no error for the usage limit of metered licenses
+            Error::KeygenApiError { code, detail, .. } if USAGE_LIMIT_CODES.contains(&code.as_str()) => json!({ "detail": detail, "code": code }),
+            Error::KeygenApiError { code, detail, body } => json!({ "code": code, "detail": detail, "body": body }),
+            Error::TokenNotAllowed { detail, code } => json!({ "detail": detail, "code": code }),
+            Error::TokenFormatInvalid { code, detail } => json!({ "detail": detail, "code": code }),
+            Error::TokenInvalid { code, detail } => json!({ "detail": detail, "code": code }),
+            Error::TokenExpired { code, detail } => json!({ "detail": detail, "code": code }),
+            Error::LicenseSuspended { code, detail } => json!({ "detail": detail, "code": code }),
+            Error::LicenseExpired { detail, code } => json!({ "detail": detail, "code": code }),
+            Error::LicenseNotAllowed { detail, code } => json!({ "detail": detail, "code": code }),
+            Error::LicenseNotActivated { code, detail, license } => json!({ "detail": detail, "code": code, "license": license }),
+            Error::LicenseKeyInvalid { detail, code } => json!({ "detail": detail, "code": code }),
+            Error::LicenseTokenInvalid { detail, code } => json!({ "detail": detail, "code": code }),
+            Error::LicenseTooManyMachines { detail, code } => json!({ "detail": detail, "code": code }),
+            Error::LicenseTooManyCores { code, detail } => json!({ "detail": detail, "code": code }),
+            Error::LicenseTooManyProcesses { detail, code } => json!({ "detail": detail, "code": code }),
+            Error::MachineAlreadyActivated { detail, code } => json!({ "detail": detail, "code": code }),
+            Error::MachineLimitExceeded { detail, code } => json!({ "detail": detail, "code": code }),
+            Error::MachineNotFound => json!("Machine not found"),
+            Error::ProcessLimitExceeded { detail, code } => json!({ "detail": detail, "code": code }),
+            Error::ProcessNotFound => json!("Process not found"),
+            Error::ComponentConflict { code, detail } => json!({ "detail": detail, "code": code }),
+            Error::ComponentAlreadyActivated { code, detail } => json!({ "detail": detail, "code": code }),
+            Error::ComponentNotActivated { code, detail } => json!({ "detail": detail, "code": code }),
+            Error::EnvironmentError { code, detail } => json!({ "detail": detail, "code": code }),
+            Error::HeartbeatDead { code, detail } => json!({ "detail": detail, "code": code }),
+            Error::HeartbeatPingFailed { code, detail } => json!({ "detail": detail, "code": code }),
+            Error::HeartbeatRequired { code, detail } => json!({ "detail": detail, "code": code }),
+            Error::ValidationFingerprintMissing { code, detail } => json!({ "detail": detail, "code": code }),
+            Error::ValidationComponentsMissing { code, detail } => json!({ "detail": detail, "code": code }),
+            Error::ValidationProductMissing { code, detail } => json!({ "detail": detail, "code": code }),
+            Error::NotFound { code, detail } => json!({ "detail": detail, "code": code }),
+            Error::HttpClient(e) => json!({ "details": e.to_string() }),
+            Error::JsonError(e) => json!({ "details": e.to_string() }),
+            Error::KeygenSignatureInvalid { reason } => json!({ "reason": reason }),
+            Error::KeygenSignatureMissing => json!("Keygen signature missing"),
+            Error::UrlParse(e) => json!({ "details": e.to_string() }),
+            Error::InvalidHeader(e) => json!({ "details": e.to_string() }),
+            Error::UrlEncode(e) => json!({ "details": e.to_string() }),
+        };
+        Self::from_kind(kind, details)
+    }
+}
+
+/// The name of the class of `keygen_sh.errors` an error is raised as, which is also the outcome
+/// of its telemetry span.
+pub(crate) fn error_type(error: &Error) -> &'static str {
+    match error {
+        Error::UnexpectedError(_) => "UnexpectedError",
+        Error::InvalidUrl => "InvalidUrl",
+        Error::SystemClockUnsynced => "SystemClockUnsynced",
+        Error::DecryptionError(_) => "DecryptionError",
+        Error::HttpClient(_) => "HttpClient",
+        Error::UrlParse(_) => "UrlParse",
+        Error::JsonError(_) => "JsonError",
+        Error::InvalidHeader(_) => "InvalidHeader",
+        Error::UrlEncode(_) => "UrlEncode",
+        Error::RateLimitExceeded { .. } => "RateLimitExceeded",
+        Error::LicenseKeyMissing => "LicenseKeyMissing",
+        Error::LicenseSchemeMissing => "LicenseSchemeMissing",
+        Error::LicenseSchemeNotSupported => "LicenseSchemeNotSupported",
+        Error::LicenseNotSigned => "LicenseNotSigned",
+        Error::LicenseKeyNotGenuine => "LicenseKeyNotGenuine",
+        Error::PublicKeyMissing => "PublicKeyMissing",
+        Error::PublicKeyInvalid => "PublicKeyInvalid",
+        Error::LicenseSchemeUnsupported => "LicenseSchemeUnsupported",
+        Error::CerificateFileInvalid(_) => "CerificateFileInvalid",
+        Error::CertificateFileNotGenuine(_) => "CertificateFileNotGenuine",
+        Error::CertificateFileNotSupported(_) => "CertificateFileNotSupported",
+        Error::CerificateFileExpired => "CerificateFileExpired",
+        Error::LicenseFileInvalid(_) => "LicenseFileInvalid",
+        Error::LicenseFileNotGenuine(_) => "LicenseFileNotGenuine",
+        Error::LicenseFileNotSupported(_) => "LicenseFileNotSupported",
+        Error::LicenseFileNotEncrypted => "LicenseFileNotEncrypted",
+        Error::LicenseFileExpired(_) => "LicenseFileExpired",
+        Error::MachineFileInvalid(_) => "MachineFileInvalid",
+        Error::MachineFileNotGenuine(_) => "MachineFileNotGenuine",
+        Error::MachineFileNotSupported(_) => "MachineFileNotSupported",
+        Error::MachineFileExpired(_) => "MachineFileExpired",
+        Error::KeygenApiError { code, .. } if USAGE_LIMIT_CODES.contains(&code.as_str()) => "LicenseTooManyUses",
+        Error::KeygenApiError { .. } => "KeygenApiError",
+        Error::TokenNotAllowed { .. } => "TokenNotAllowed",
+        Error::TokenFormatInvalid { .. } => "TokenFormatInvalid",
+        Error::TokenInvalid { .. } => "TokenInvalid",
+        Error::TokenExpired { .. } => "TokenExpired",
+        Error::LicenseSuspended { .. } => "LicenseSuspended",
+        Error::LicenseExpired { .. } => "LicenseExpired",
+        Error::LicenseNotAllowed { .. } => "LicenseNotAllowed",
+        Error::LicenseNotActivated { .. } => "LicenseNotActivated",
+        Error::LicenseKeyInvalid { .. } => "LicenseKeyInvalid",
+        Error::LicenseTokenInvalid { .. } => "LicenseTokenInvalid",
+        Error::LicenseTooManyMachines { .. } => "LicenseTooManyMachines",
+        Error::LicenseTooManyCores { .. } => "LicenseTooManyCores",
+        Error::LicenseTooManyProcesses { .. } => "LicenseTooManyProcesses",
+        Error::MachineAlreadyActivated { .. } => "MachineAlreadyActivated",
+        Error::MachineLimitExceeded { .. } => "MachineLimitExceeded",
+        Error::MachineNotFound => "MachineNotFound",
+        Error::ProcessLimitExceeded { .. } => "ProcessLimitExceeded",
+        Error::ProcessNotFound => "ProcessNotFound",
+        Error::ComponentConflict { .. } => "ComponentConflict",
+        Error::ComponentAlreadyActivated { .. } => "ComponentAlreadyActivated",
+        Error::ComponentNotActivated { .. } => "ComponentNotActivated",
+        Error::EnvironmentError { .. } => "EnvironmentError",
+        Error::HeartbeatDead { .. } => "HeartbeatDead",
+        Error::HeartbeatPingFailed { .. } => "HeartbeatPingFailed",
+        Error::HeartbeatRequired { .. } => "HeartbeatRequired",
+        Error::ValidationFingerprintMissing { .. } => "ValidationFingerprintMissing",
+        Error::ValidationComponentsMissing { .. } => "ValidationComponentsMissing",
+        Error::ValidationProductMissing { .. } => "ValidationProductMissing",
+        Error::NotFound { .. } => "NotFound",
+        Error::KeygenSignatureInvalid { .. } => "KeygenSignatureInvalid",
+        Error::KeygenSignatureMissing => "KeygenSignatureMissing",
     }
 }
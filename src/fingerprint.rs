@@ -0,0 +1,95 @@
+use crate::config::get_settings;
+use crate::errors::KeygenError;
+use hmac::{Hmac, Mac};
+use pyo3::prelude::*;
+use pyo3::{pyfunction, pymodule, wrap_pyfunction, Bound, PyResult, Python};
+use serde_json::json;
+use sha2::Sha256;
+
+#[pymodule(name = "fingerprint")]
+pub fn fingerprint_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Hack: workaround for https://github.com/PyO3/pyo3/issues/759
+    Python::attach(|py| {
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("keygen_sh.fingerprint", m)
+    })?;
+
+    m.add_function(wrap_pyfunction!(machine_fingerprint, m)?)?;
+    m.add_function(wrap_pyfunction!(current_fingerprint, m)?)?;
+    Ok(())
+}
+
+/// The fingerprint of this machine: the machine id of the operating system, hashed with the
+/// product so it cannot be correlated across products.
+///
+/// The product is the HMAC key and the machine id the message, like the `hashed_id` of
+/// py-machineid, so both derive the same fingerprint.
+#[pyfunction]
+pub fn machine_fingerprint() -> PyResult<String> {
+    let Some(machine_id) = machine_id() else {
+        return Err(KeygenError::from_kind(
+            "FingerprintUnavailable",
+            json!({ "reason": "The machine id of the operating system cannot be read" }),
+        ));
+    };
+    let product = keygen_rs::config::get_config().product;
+    let mut mac = Hmac::<Sha256>::new_from_slice(product.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(machine_id.as_bytes());
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+/// The fingerprint of this machine according to the `fingerprint` of the global config, which is
+/// either a fixed fingerprint or a callable returning one, falling back to `machine_fingerprint()`.
+#[pyfunction]
+pub fn current_fingerprint(py: Python<'_>) -> PyResult<String> {
+    let Some(fingerprint) = get_settings().fingerprint else {
+        return machine_fingerprint();
+    };
+    let fingerprint = fingerprint.bind(py);
+    if fingerprint.is_callable() {
+        fingerprint.call0()?.extract()
+    } else {
+        fingerprint.extract()
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn machine_id() -> Option<String> {
+    ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+        .find(|id| !id.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+fn machine_id() -> Option<String> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.contains("IOPlatformUUID"))
+        .and_then(|line| line.split('"').nth(3))
+        .map(str::to_string)
+}
+
+#[cfg(windows)]
+fn machine_id() -> Option<String> {
+    let output = std::process::Command::new("reg")
+        .args(["query", r"HKLM\SOFTWARE\Microsoft\Cryptography", "/v", "MachineGuid"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.contains("MachineGuid"))
+        .and_then(|line| line.split_whitespace().last())
+        .map(str::to_string)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn machine_id() -> Option<String> {
+    None
+}
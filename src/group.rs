@@ -0,0 +1,200 @@
+use crate::admin;
+use crate::client::{find_included, from_resources, Client, Response};
+use crate::date::Date;
+use crate::errors::KeygenError;
+use crate::identity::Identity;
+use crate::json::JsonValue;
+use crate::pagination::{Pager, MAX_PAGE_SIZE};
+use crate::state::JsonState;
+use crate::telemetry::instrument;
+use crate::utils::interface_methods;
+use chrono::{DateTime, Utc};
+use keygen_rs::errors::Error;
+use pyo3::prelude::*;
+use pyo3::{pyclass, pymodule, Bound, PyResult, Python};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+#[pymodule(name = "group")]
+pub fn group_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Hack: workaround for https://github.com/PyO3/pyo3/issues/759
+    Python::attach(|py| {
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("keygen_sh.group", m)
+    })?;
+
+    m.add_class::<Group>()?;
+    Ok(())
+}
+
+#[pyclass(frozen, module = "keygen_sh.group")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Group {
+    pub id: String,
+    pub name: String,
+    pub max_users: Option<i64>,
+    pub max_licenses: Option<i64>,
+    pub max_machines: Option<i64>,
+    #[serde(default)]
+    pub metadata: HashMap<String, Value>,
+    pub created: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+}
+
+impl Group {
+    /// Builds groups from an API response whose `data` is a list of group resources.
+    pub(crate) fn from_list_response(response: Response) -> Result<Vec<Group>, Error> {
+        from_resources(&response.body["data"])
+    }
+
+    /// Finds the group among the resources included in e.g. a license file.
+    pub(crate) fn from_included(included: &Value) -> Option<Group> {
+        find_included(included, "groups")
+    }
+
+    /// The attributes of a group which are set, skipping the ones left out.
+    fn attributes(
+        name: Option<String>,
+        max_users: Option<i64>,
+        max_licenses: Option<i64>,
+        max_machines: Option<i64>,
+        metadata: Option<JsonValue>,
+    ) -> Value {
+        admin::attributes(json!({
+            "name": name,
+            "maxUsers": max_users,
+            "maxLicenses": max_licenses,
+            "maxMachines": max_machines,
+            "metadata": metadata.map(|JsonValue(metadata)| metadata),
+        }))
+    }
+}
+
+impl JsonState for Group {
+    fn to_state(&self) -> Result<Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
+
+    fn from_state(state: Value) -> Result<Self, serde_json::Error> {
+        serde_json::from_value(state)
+    }
+}
+
+impl Identity for Group {
+    fn resource_id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+
+    fn repr_fields(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("id", Some(self.id.clone())),
+            ("name", Some(self.name.clone())),
+        ]
+    }
+}
+
+interface_methods! {
+impl Group {
+    #[getter]
+    fn id(&self) -> PyResult<String> {
+        Ok(self.id.clone())
+    }
+
+    #[getter]
+    fn name(&self) -> PyResult<String> {
+        Ok(self.name.clone())
+    }
+
+    #[getter]
+    fn max_users(&self) -> PyResult<Option<i64>> {
+        Ok(self.max_users)
+    }
+
+    #[getter]
+    fn max_licenses(&self) -> PyResult<Option<i64>> {
+        Ok(self.max_licenses)
+    }
+
+    #[getter]
+    fn max_machines(&self) -> PyResult<Option<i64>> {
+        Ok(self.max_machines)
+    }
+
+    #[getter]
+    fn metadata(&self) -> PyResult<JsonValue> {
+        Ok(JsonValue(json!(self.metadata)))
+    }
+
+    #[getter]
+    fn created(&self) -> PyResult<Date> {
+        Ok(Date::from(self.created))
+    }
+
+    #[getter]
+    fn updated(&self) -> PyResult<Date> {
+        Ok(Date::from(self.updated))
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (page_size=MAX_PAGE_SIZE))]
+    fn list(py: Python<'_>, page_size: u32) -> PyResult<Bound<'_, PyAny>> {
+        let pager = Pager::<Group>::new("groups".to_string(), json!({}), page_size)?.with_client(Client::admin()?);
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("group.list", None, None, pager.collect()).await;
+            match result {
+                Ok(groups) => Ok(groups),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (name, *, max_users=None, max_licenses=None, max_machines=None, metadata=None))]
+    fn create(
+        py: Python<'_>,
+        name: String,
+        max_users: Option<i64>,
+        max_licenses: Option<i64>,
+        max_machines: Option<i64>,
+        metadata: Option<JsonValue>,
+    ) -> PyResult<Bound<'_, PyAny>> {
+        let client = Client::admin()?;
+        let attributes = Group::attributes(Some(name), max_users, max_licenses, max_machines, metadata);
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("group.create", None, None, admin::create::<Group>(&client, "groups", attributes, None)).await;
+            match result {
+                Ok(group) => Ok(group),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    #[pyo3(signature = (*, name=None, max_users=None, max_licenses=None, max_machines=None, metadata=None))]
+    fn update<'a>(
+        &'a self,
+        py: Python<'a>,
+        name: Option<String>,
+        max_users: Option<i64>,
+        max_licenses: Option<i64>,
+        max_machines: Option<i64>,
+        metadata: Option<JsonValue>,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let client = Client::admin()?;
+        let group_id = self.id.clone();
+        let attributes = Group::attributes(name, max_users, max_licenses, max_machines, metadata);
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("group.update", None, None, admin::update::<Group>(&client, "groups", &group_id, attributes)).await;
+            match result {
+                Ok(group) => Ok(group),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+}
+}
@@ -0,0 +1,87 @@
+use crate::json::JsonValue;
+use crate::logging::Secrets;
+use keygen_rs::errors::Error;
+use pyo3::prelude::*;
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode, Url};
+use serde_json::{json, Map, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Python callables invoked around every HTTP call made by the bindings.
+///
+/// Each hook receives a single dict describing the call, with the license keys and tokens of
+/// the config and of the call itself redacted.
+/// Exceptions raised by a hook are logged and never fail the call itself.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Hooks {
+    pub on_request: Option<Arc<Py<PyAny>>>,
+    pub on_response: Option<Arc<Py<PyAny>>>,
+    pub on_error: Option<Arc<Py<PyAny>>>,
+}
+
+impl Hooks {
+    pub(crate) const fn none() -> Self {
+        Self {
+            on_request: None,
+            on_response: None,
+            on_error: None,
+        }
+    }
+
+    pub(crate) fn request(&self, method: &Method, url: &Url, headers: &HeaderMap, secrets: &Secrets) {
+        Self::call("on_request", &self.on_request, || {
+            json!({
+                "method": method.as_str(),
+                "url": secrets.redact(url.as_str()),
+                "headers": Self::headers(headers, secrets),
+            })
+        });
+    }
+
+    pub(crate) fn response(&self, method: &Method, url: &Url, status: StatusCode, headers: &HeaderMap, elapsed: Duration, secrets: &Secrets) {
+        Self::call("on_response", &self.on_response, || {
+            json!({
+                "method": method.as_str(),
+                "url": secrets.redact(url.as_str()),
+                "status": status.as_u16(),
+                "headers": Self::headers(headers, secrets),
+                "elapsed": elapsed.as_secs_f64(),
+            })
+        });
+    }
+
+    pub(crate) fn error(&self, method: &Method, url: &Url, error: &Error, elapsed: Duration, secrets: &Secrets) {
+        Self::call("on_error", &self.on_error, || {
+            json!({
+                "method": method.as_str(),
+                "url": secrets.redact(url.as_str()),
+                "error": secrets.redact(&error.to_string()),
+                "elapsed": elapsed.as_secs_f64(),
+            })
+        });
+    }
+
+    fn call(name: &str, hook: &Option<Arc<Py<PyAny>>>, event: impl FnOnce() -> Value) {
+        let Some(hook) = hook else {
+            return;
+        };
+
+        Python::attach(|py| {
+            if let Err(e) = hook.call1(py, (JsonValue(event()),)) {
+                log::warn!("{} hook failed: {}", name, e);
+            }
+        });
+    }
+
+    fn headers(headers: &HeaderMap, secrets: &Secrets) -> Value {
+        let headers: Map<String, Value> = headers
+            .iter()
+            .filter_map(|(name, value)| {
+                let value = value.to_str().ok()?;
+                Some((name.to_string(), Value::String(secrets.redact_header(name.as_str(), value))))
+            })
+            .collect();
+        Value::Object(headers)
+    }
+}
@@ -0,0 +1,31 @@
+/// What the `__repr__`, `__eq__` and `__hash__` generated by `interface_methods!` are based on.
+pub(crate) trait Identity {
+    /// The id of the resource, which objects are compared and hashed by. Objects without an id
+    /// are only equal to themselves.
+    fn resource_id(&self) -> Option<&str>;
+
+    /// The fields shown by `__repr__`.
+    fn repr_fields(&self) -> Vec<(&'static str, Option<String>)>;
+}
+
+/// Formats an object like a Python dataclass, e.g. `License(id='1', name=None)`.
+pub(crate) fn repr(name: &str, fields: Vec<(&'static str, Option<String>)>) -> String {
+    let fields: Vec<String> = fields
+        .into_iter()
+        .map(|(field, value)| match value {
+            Some(value) => format!("{}='{}'", field, value.replace('\\', "\\\\").replace('\'', "\\'")),
+            None => format!("{}=None", field),
+        })
+        .collect();
+    format!("{}({})", name, fields.join(", "))
+}
+
+/// Hides all but the first characters of a license key or token, enough to tell them apart.
+pub(crate) fn redact_secret(secret: &str) -> String {
+    let prefix: String = secret.chars().take(4).collect();
+    if secret.chars().count() <= 8 {
+        "…".to_string()
+    } else {
+        format!("{}…", prefix)
+    }
+}
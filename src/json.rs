@@ -1,3 +1,5 @@
+use crate::errors::KeygenError;
+use keygen_rs::errors::Error;
 use pyo3::prelude::*;
 use pyo3::types::{PyBool, PyDict, PyList, PyNone, PyString};
 use serde_json::Value;
@@ -42,4 +44,13 @@ impl<'py> IntoPyObject<'py> for JsonValue {
             }
         }
     }
-}
\ No newline at end of file
+}
+impl<'py> FromPyObject<'py> for JsonValue {
+    /// Accepts anything `json.dumps` can serialize.
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let json: String = ob.py().import("json")?.call_method1("dumps", (ob,))?.extract()?;
+        serde_json::from_str(&json)
+            .map(JsonValue)
+            .map_err(|e| KeygenError::from_error(Error::from(e)))
+    }
+}
@@ -0,0 +1,296 @@
+use crate::errors::KeygenError;
+use crate::license::License;
+use crate::machine::Machine;
+use crate::telemetry::instrument;
+use keygen_rs::component::Component as KeygenRsComponent;
+use keygen_rs::errors::Error;
+use pyo3::prelude::*;
+use pyo3::{pyclass, pymethods, pymodule, Bound, PyResult, Python};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+#[pymodule(name = "lease")]
+pub fn lease_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Hack: workaround for https://github.com/PyO3/pyo3/issues/759
+    Python::attach(|py| {
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("keygen_sh.lease", m)
+    })?;
+
+    m.add_class::<Lease>()?;
+    Ok(())
+}
+
+/// Heartbeat interval of machines which require a heartbeat but do not report its duration,
+/// half of the default heartbeat duration of Keygen.
+const DEFAULT_HEARTBEAT_INTERVAL: u64 = 300;
+
+#[derive(Debug, Clone)]
+pub(crate) struct LeaseOptions {
+    pub heartbeat_interval: Option<u64>,
+    pub wait: bool,
+    pub poll_interval: u64,
+    pub timeout: Option<u64>,
+}
+
+#[derive(Default)]
+struct LeaseState {
+    machine: Option<Machine>,
+    heartbeat: Option<JoinHandle<()>>,
+    event_loop: Option<Py<PyAny>>,
+    task: Option<Py<PyAny>>,
+    /// The signals handled while the lease is held, with the handlers they had before.
+    handlers: Vec<(i32, Py<PyAny>)>,
+    signal: Option<i32>,
+}
+
+/// A seat of a floating license, held by activating a machine and returned by deactivating it.
+///
+/// Use it as an async context manager: `async with license.lease(fingerprint) as machine:`.
+#[pyclass(frozen)]
+pub struct Lease {
+    license_id: String,
+    fingerprint: String,
+    components: Vec<KeygenRsComponent>,
+    options: LeaseOptions,
+    state: Arc<Mutex<LeaseState>>,
+}
+
+impl Lease {
+    pub(crate) fn new(license_id: String, fingerprint: String, components: Vec<KeygenRsComponent>, options: LeaseOptions) -> Self {
+        Self {
+            license_id,
+            fingerprint,
+            components,
+            options,
+            state: Arc::new(Mutex::new(LeaseState::default())),
+        }
+    }
+
+    /// Activates the machine, reusing it when it is already activated and, if enabled, waiting
+    /// for a seat while the machine limit is exceeded.
+    async fn acquire(license_id: &str, fingerprint: &str, components: &[KeygenRsComponent], options: &LeaseOptions) -> Result<Machine, Error> {
+        let started = Instant::now();
+        loop {
+            match License::activate_with(license_id, fingerprint, components).await {
+                Ok(machine) => return Ok(machine),
+                Err(Error::MachineAlreadyActivated { code, detail }) => {
+                    log::debug!("machine {} is already activated, reusing it", fingerprint);
+                    return match Machine::find_by_fingerprint(license_id, fingerprint).await? {
+                        Some(machine) => Ok(machine),
+                        None => Err(Error::MachineAlreadyActivated { code, detail }),
+                    };
+                }
+                Err(e @ Error::MachineLimitExceeded { .. }) if options.wait => {
+                    let timed_out = options
+                        .timeout
+                        .is_some_and(|timeout| started.elapsed() + Duration::from_secs(options.poll_interval) > Duration::from_secs(timeout));
+                    if timed_out {
+                        return Err(e);
+                    }
+                    log::info!("machine limit exceeded, retrying in {}s", options.poll_interval);
+                    tokio::time::sleep(Duration::from_secs(options.poll_interval)).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Pings the machine right away, which starts its heartbeat, then every `interval` seconds.
+    async fn heartbeat(machine_id: String, interval: u64) {
+        loop {
+            let result = instrument("lease.heartbeat", None, Some(machine_id.clone()), Machine::ping_with(&machine_id)).await;
+            if let Err(e) = result {
+                log::warn!("heartbeat of machine {} failed: {}", machine_id, e);
+            }
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+        }
+    }
+
+    fn heartbeat_interval(&self, machine: &Machine) -> Option<u64> {
+        let inner = machine.inner();
+        self.options.heartbeat_interval.or_else(|| {
+            inner.require_heartbeat.then(|| {
+                inner
+                    .heartbeat_duration
+                    .map(|duration| (duration / 2).max(1) as u64)
+                    .unwrap_or(DEFAULT_HEARTBEAT_INTERVAL)
+            })
+        })
+    }
+
+    /// Cancels the task holding the lease on SIGINT and SIGTERM, so the lease is released by the
+    /// regular exit of the context manager.
+    fn install_signal_handlers(slf: &Bound<'_, Self>) -> PyResult<()> {
+        let py = slf.py();
+        let asyncio = py.import("asyncio")?;
+        let signal = py.import("signal")?;
+        let event_loop = asyncio.call_method0("get_running_loop")?;
+
+        let mut state = slf.get().state.lock().unwrap();
+        state.task = Some(asyncio.call_method0("current_task")?.unbind());
+        for name in ["SIGINT", "SIGTERM"] {
+            let Ok(signum) = signal.getattr(name) else {
+                continue;
+            };
+            let previous = signal.call_method1("getsignal", (&signum,))?;
+            // only possible on Unix and in the main thread
+            match event_loop.call_method1("add_signal_handler", (&signum, slf.getattr("_on_signal")?, &signum)) {
+                Ok(_) => state.handlers.push((signum.extract()?, previous.unbind())),
+                Err(e) => log::debug!("not handling {}: {}", name, e),
+            }
+        }
+        state.event_loop = Some(event_loop.unbind());
+        Ok(())
+    }
+
+    fn restore_signal_handlers(py: Python<'_>, state: &mut LeaseState) -> PyResult<()> {
+        let signal = py.import("signal")?;
+        let handlers = std::mem::take(&mut state.handlers);
+        if let Some(event_loop) = &state.event_loop {
+            for (signum, previous) in handlers {
+                event_loop.call_method1(py, "remove_signal_handler", (signum,))?;
+                // `getsignal` returns None for a handler which was not installed from Python
+                if !previous.is_none(py) {
+                    signal.call_method1("signal", (signum, previous))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Calls a method of the lease on the thread of the event loop it was entered in.
+    fn call_soon(lease: &Py<Self>, method: &str) {
+        Python::attach(|py| {
+            let event_loop = lease.get().state.lock().unwrap().event_loop.as_ref().map(|l| l.clone_ref(py));
+            let Some(event_loop) = event_loop else {
+                return;
+            };
+            let result = lease
+                .getattr(py, method)
+                .and_then(|method| event_loop.call_method1(py, "call_soon_threadsafe", (method,)));
+            if let Err(e) = result {
+                log::warn!("failed to schedule {}: {}", method, e);
+            }
+        });
+    }
+}
+
+#[pymethods]
+impl Lease {
+    #[getter]
+    fn machine(&self) -> PyResult<Option<Machine>> {
+        Ok(self.state.lock().unwrap().machine.clone())
+    }
+
+    fn __aenter__<'a>(slf: Bound<'a, Self>, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        Self::install_signal_handlers(&slf)?;
+        let on_entered = slf.getattr("_on_entered")?;
+        let lease = slf.unbind();
+
+        let future = pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let this = lease.get();
+            let result = instrument(
+                "lease.acquire",
+                Some(this.license_id.clone()),
+                None,
+                Self::acquire(&this.license_id, &this.fingerprint, &this.components, &this.options),
+            )
+            .await;
+
+            match result {
+                Ok(machine) => {
+                    let mut state = this.state.lock().unwrap();
+                    if let Some(interval) = this.heartbeat_interval(&machine) {
+                        let machine_id = machine.inner().id.clone();
+                        state.heartbeat = Some(pyo3_async_runtimes::tokio::get_runtime().spawn(Self::heartbeat(machine_id, interval)));
+                    }
+                    state.machine = Some(machine.clone());
+                    Ok(machine)
+                }
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })?;
+        future.call_method1("add_done_callback", (on_entered,))?;
+        Ok(future)
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __aexit__<'a>(slf: Bound<'a, Self>, py: Python<'a>, _args: &Bound<'a, PyAny>) -> PyResult<Bound<'a, PyAny>> {
+        let (machine, heartbeat) = {
+            let mut state = slf.get().state.lock().unwrap();
+            let taken = (state.machine.take(), state.heartbeat.take());
+            // the machine is deactivated even if the handlers cannot be restored
+            if let Err(e) = Self::restore_signal_handlers(py, &mut state) {
+                log::warn!("failed to restore signal handlers: {}", e);
+            }
+            taken
+        };
+        let license_id = slf.get().license_id.clone();
+        let lease = slf.unbind();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            if let Some(heartbeat) = heartbeat {
+                heartbeat.abort();
+            }
+            if let Some(machine) = machine {
+                let machine_id = machine.inner().id.clone();
+                let result = instrument("lease.release", Some(license_id), Some(machine_id.clone()), Machine::deactivate_with(&machine_id)).await;
+                match result {
+                    Ok(_) | Err(Error::NotFound { .. }) => {}
+                    Err(e) => log::warn!("failed to deactivate machine {}: {}", machine_id, e),
+                }
+            }
+            Self::call_soon(&lease, "_redeliver_signal");
+            Ok(false)
+        })
+    }
+
+    /// Called by the event loop on SIGINT or SIGTERM while the lease is held.
+    fn _on_signal(&self, py: Python<'_>, signum: i32) -> PyResult<()> {
+        let mut state = self.state.lock().unwrap();
+        log::info!("received signal {}, releasing lease", signum);
+        state.signal = Some(signum);
+        if let Some(task) = &state.task {
+            task.call_method0(py, "cancel")?;
+        }
+        Ok(())
+    }
+
+    /// Called by the event loop once `__aenter__` is done. The lease is not held if it failed or
+    /// was cancelled, e.g. by `_on_signal` while waiting for a seat, so `__aexit__` is not called.
+    fn _on_entered(&self, py: Python<'_>, future: &Bound<'_, PyAny>) -> PyResult<()> {
+        if !future.call_method0("cancelled")?.is_truthy()? && future.call_method0("exception")?.is_none() {
+            return Ok(());
+        }
+        if let Err(e) = Self::restore_signal_handlers(py, &mut self.state.lock().unwrap()) {
+            log::warn!("failed to restore signal handlers: {}", e);
+        }
+        self._redeliver_signal(py)
+    }
+
+    /// Delivers a signal which was handled while the lease was held again, now that the lease is
+    /// released and the previous handler is restored, e.g. to terminate the process on SIGTERM.
+    fn _redeliver_signal(&self, py: Python<'_>) -> PyResult<()> {
+        let (task, signal) = {
+            let mut state = self.state.lock().unwrap();
+            state.event_loop = None;
+            (state.task.take(), state.signal.take())
+        };
+        let Some(signum) = signal else {
+            return Ok(());
+        };
+
+        // withdraw the cancellation requested by `_on_signal`, so e.g. `asyncio.run` turns the
+        // cancellation requested by its own SIGINT handler into a `KeyboardInterrupt`
+        if let Some(task) = task {
+            if task.bind(py).hasattr("uncancel")? {
+                task.call_method0(py, "uncancel")?;
+            }
+        }
+        py.import("signal")?.call_method1("raise_signal", (signum,))?;
+        Ok(())
+    }
+}
@@ -1,28 +1,72 @@
+// `keygen_rs::errors::Error` is large, but it is the error type shared with `keygen_rs`
+// and converted into a `KeygenError` at the Python boundary.
+#![allow(clippy::result_large_err)]
+
+use crate::client::Client;
 use crate::license::{License, SchemeCode};
+use crate::signed_key::SignedKey;
+use crate::telemetry::{instrument, record_license};
+use crate::verifier::{PublicKeys, Verifier};
 use crate::utils::pylist_to_string_slice;
 use pyo3::prelude::PyModuleMethods;
 use pyo3::types::{PyList, PyModule};
 use pyo3::{pyfunction, pymodule, wrap_pyfunction, wrap_pymodule, Bound, PyAny, PyResult, Python};
 use crate::errors::KeygenError;
 
+pub(crate) mod admin;
+pub(crate) mod cache;
+pub(crate) mod client;
+pub(crate) mod clock;
 pub(crate) mod date;
+pub(crate) mod hooks;
+pub(crate) mod identity;
 pub(crate) mod json;
+pub(crate) mod logging;
+pub(crate) mod mock_server;
+pub(crate) mod state;
 pub(crate) mod utils;
+pub(crate) mod verifier;
+pub mod artifact;
 pub mod certificate;
+pub mod cli;
 pub mod component;
 pub mod config;
 pub mod entitlement;
 pub mod errors;
+pub mod fingerprint;
+pub mod group;
+pub mod lease;
 pub mod license;
 pub mod license_file;
+pub mod license_file_manager;
 pub mod machine;
 pub mod machine_file;
+pub mod pagination;
+pub mod policy;
+pub mod product;
+pub mod release;
+pub mod signed_key;
+pub mod telemetry;
+pub mod testing;
+pub mod token;
+pub mod user;
+pub mod verification;
+pub mod webhooks;
+
+#[pyfunction]
+#[pyo3(signature = (scheme, signed_key, public_key=None))]
+fn verify(scheme: SchemeCode, signed_key: &str, public_key: Option<PublicKeys>) -> PyResult<String> {
+    verify_key(scheme, signed_key, public_key)?.utf8()
+}
 
 #[pyfunction]
-fn verify(scheme: SchemeCode, signed_key: &str) -> PyResult<String> {
-    match keygen_rs::verify(scheme.into(), signed_key) {
-        Ok(data) => Ok(String::from_utf8_lossy(&data).to_string()),
-        Err(e) => Err(KeygenError::from_error(e)),
+#[pyo3(signature = (scheme, signed_key, public_key=None))]
+fn verify_key(scheme: SchemeCode, signed_key: &str, public_key: Option<PublicKeys>) -> PyResult<SignedKey> {
+    match scheme {
+        SchemeCode::Ed25519Sign => match Verifier::new(public_key).verify_key(signed_key) {
+            Ok((data, public_key)) => Ok(SignedKey::build(scheme, signed_key, data, public_key)),
+            Err(e) => Err(KeygenError::from_error(e)),
+        },
     }
 }
 
@@ -36,28 +80,54 @@ fn validate<'a>(py: Python<'a>, fingerprints: Option<Bound<'a, PyList>>, entitle
     let entitlements_vec = pylist_to_string_slice(entitlements)?;
 
     pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let result = keygen_rs::validate(&fingerprints_vec, &entitlements_vec).await;
+        let result = instrument("validate", None, None, async {
+            let response = Client::default().get_cached("me", "").await?;
+            let license = License::from_resource(&response.body["data"])?;
+            record_license(&license.id);
+            License::validate_key_with(license.key, &fingerprints_vec, &entitlements_vec).await
+        })
+        .await;
 
         match result {
-            Ok(license) => Ok(License::from(license)),
+            Ok(license) => Ok(license),
             Err(e) => Err(KeygenError::from_error(e)),
         }
     })
 }
 
 #[pymodule]
-fn keygen_sh(_: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
+fn keygen_sh(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    logging::init(py);
+
+    m.add_wrapped(wrap_pymodule!(artifact::artifact_module))?;
     m.add_wrapped(wrap_pymodule!(certificate::certificate_module))?;
+    m.add_wrapped(wrap_pymodule!(cli::cli_module))?;
     m.add_wrapped(wrap_pymodule!(component::component_module))?;
     m.add_wrapped(wrap_pymodule!(config::config_module))?;
     m.add_wrapped(wrap_pymodule!(entitlement::entitlement_module))?;
     m.add_wrapped(wrap_pymodule!(errors::errors_module))?;
+    m.add_wrapped(wrap_pymodule!(fingerprint::fingerprint_module))?;
+    m.add_wrapped(wrap_pymodule!(group::group_module))?;
+    m.add_wrapped(wrap_pymodule!(lease::lease_module))?;
     m.add_wrapped(wrap_pymodule!(license::license_module))?;
     m.add_wrapped(wrap_pymodule!(license_file::license_file_module))?;
+    m.add_wrapped(wrap_pymodule!(license_file_manager::license_file_manager_module))?;
     m.add_wrapped(wrap_pymodule!(machine::machine_module))?;
     m.add_wrapped(wrap_pymodule!(machine_file::machine_file_module))?;
+    m.add_wrapped(wrap_pymodule!(pagination::pagination_module))?;
+    m.add_wrapped(wrap_pymodule!(policy::policy_module))?;
+    m.add_wrapped(wrap_pymodule!(product::product_module))?;
+    m.add_wrapped(wrap_pymodule!(release::release_module))?;
+    m.add_wrapped(wrap_pymodule!(signed_key::signed_key_module))?;
+    m.add_wrapped(wrap_pymodule!(telemetry::telemetry_module))?;
+    m.add_wrapped(wrap_pymodule!(testing::testing_module))?;
+    m.add_wrapped(wrap_pymodule!(token::token_module))?;
+    m.add_wrapped(wrap_pymodule!(user::user_module))?;
+    m.add_wrapped(wrap_pymodule!(verification::verification_module))?;
+    m.add_wrapped(wrap_pymodule!(webhooks::webhooks_module))?;
 
     m.add_function(wrap_pyfunction!(validate, m)?)?;
     m.add_function(wrap_pyfunction!(verify, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_key, m)?)?;
     Ok(())
 }
\ No newline at end of file
@@ -1,15 +1,36 @@
 use std::collections::HashMap;
-use crate::date::Date;
+use std::env;
+use crate::admin;
+use crate::client::{from_resource, Client, Response};
+use crate::date::{now_or, Date, Delta};
 use crate::entitlement::Entitlement;
+use crate::lease::{Lease, LeaseOptions};
 use crate::machine::Machine;
-use crate::utils::{create_interface, pylist_to_string_slice};
+use crate::pagination::{PageIterator, Pager, MAX_PAGE_SIZE};
+use crate::group::Group;
+use crate::policy::Policy;
+use crate::product::Product;
+use crate::token::Token;
+use crate::user::User;
+use serde::de::DeserializeOwned;
+use crate::identity::{redact_secret, Identity};
+use crate::json::JsonValue;
+use crate::state::InnerState;
+use crate::utils::{create_interface, interface_methods, pylist_to_string_slice};
+use crate::verification::ResponseVerification;
+use chrono::{DateTime, Utc};
 use keygen_rs;
+use keygen_rs::errors::Error;
 use keygen_rs::license::License as KeygenRsLicense;
+use keygen_rs::license_file::LicenseFile as KeygenRsLicenseFile;
 use pyo3::prelude::*;
-use pyo3::types::PyList;
+use pyo3::types::{PyBool, PyList, PyString};
+use serde_json::{json, Value};
 use crate::component::Component;
-use crate::errors::KeygenError;
+use crate::errors::{KeygenError, USAGE_LIMIT_CODES};
+use crate::fingerprint::current_fingerprint;
 use crate::license_file::LicenseFile;
+use crate::telemetry::instrument;
 
 #[pymodule(name = "license")]
 pub fn license_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -21,6 +42,7 @@ pub fn license_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     })?;
 
     m.add_class::<SchemeCode>()?;
+    m.add_class::<LicenseStatus>()?;
     m.add_class::<License>()?;
     Ok(())
 }
@@ -31,11 +53,372 @@ pub enum SchemeCode {
     Ed25519Sign,
 }
 
-create_interface!(License, KeygenRsLicense);
+/// Status of a license, which compares equal to its name in the API, e.g. `"ACTIVE"`.
+#[pyclass(frozen, module = "keygen_sh.license")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LicenseStatus {
+    Active,
+    Inactive,
+    Expiring,
+    Expired,
+    Suspended,
+    Banned,
+}
 
-pub struct LicenseCheckoutOpts {}
+impl LicenseStatus {
+    fn parse(status: &str) -> Option<Self> {
+        match status {
+            "ACTIVE" => Some(Self::Active),
+            "INACTIVE" => Some(Self::Inactive),
+            "EXPIRING" => Some(Self::Expiring),
+            "EXPIRED" => Some(Self::Expired),
+            "SUSPENDED" => Some(Self::Suspended),
+            "BANNED" => Some(Self::Banned),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Active => "ACTIVE",
+            Self::Inactive => "INACTIVE",
+            Self::Expiring => "EXPIRING",
+            Self::Expired => "EXPIRED",
+            Self::Suspended => "SUSPENDED",
+            Self::Banned => "BANNED",
+        }
+    }
+}
 
 #[pymethods]
+impl LicenseStatus {
+    /// The name of the status in the API.
+    #[getter]
+    fn value(&self) -> &'static str {
+        self.as_str()
+    }
+
+    fn __str__(&self) -> &'static str {
+        self.as_str()
+    }
+
+    fn __eq__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        let equal = if let Ok(other) = other.cast::<LicenseStatus>() {
+            *self == *other.get()
+        } else if let Ok(other) = other.extract::<String>() {
+            self.as_str() == other
+        } else {
+            return Ok(py.NotImplemented());
+        };
+        Ok(PyBool::new(py, equal).to_owned().into_any().unbind())
+    }
+
+    /// Hashes like its name, as it compares equal to it.
+    fn __hash__(&self, py: Python<'_>) -> PyResult<isize> {
+        PyString::new(py, self.as_str()).hash()
+    }
+}
+
+create_interface!(License, KeygenRsLicense, module = "keygen_sh.license", verification: Option<ResponseVerification>, uses: Option<u64>, max_uses: Option<u64>);
+
+impl InnerState for KeygenRsLicense {
+    /// `keygen_rs` skips the scheme when serializing, which `verify` needs.
+    fn to_state(&self) -> Result<Value, serde_json::Error> {
+        let mut state = serde_json::to_value(self)?;
+        state["scheme"] = serde_json::to_value(&self.scheme)?;
+        Ok(state)
+    }
+}
+
+impl Identity for License {
+    fn resource_id(&self) -> Option<&str> {
+        Some(&self.inner.id)
+    }
+
+    fn repr_fields(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("id", Some(self.inner.id.clone())),
+            ("name", self.inner.name.clone()),
+            ("status", self.inner.status.clone()),
+            ("key", Some(redact_secret(&self.inner.key))),
+            ("expiry", self.inner.expiry.map(|expiry| expiry.to_rfc3339())),
+        ]
+    }
+}
+
+pub struct LicenseCheckoutOpts {}
+
+impl License {
+    pub(crate) fn with_verification(mut self, verification: ResponseVerification) -> Self {
+        self.verification = Some(verification);
+        self
+    }
+
+    /// Sets the usage counts of a metered license from its JSON:API resource, which `keygen_rs` drops.
+    pub(crate) fn with_usage(mut self, data: &Value) -> Self {
+        self.uses = data["attributes"]["uses"].as_u64();
+        self.max_uses = data["attributes"]["maxUses"].as_u64();
+        self
+    }
+
+    /// Builds a license from a JSON:API license resource.
+    pub(crate) fn from_resource(data: &Value) -> Result<KeygenRsLicense, Error> {
+        let mut data = data.clone();
+        // like `keygen_rs`, the scheme is only set for licenses built from a signed key
+        if let Some(attributes) = data["attributes"].as_object_mut() {
+            attributes.remove("scheme");
+        }
+        let mut license: KeygenRsLicense = from_resource(&data)?;
+        license.policy = data["relationships"]["policy"]["data"]["id"].as_str().map(str::to_string);
+        Ok(license)
+    }
+
+    /// Builds a license which only knows its id and key, to call the API on its behalf.
+    pub(crate) fn stub(id: String, key: String) -> KeygenRsLicense {
+        KeygenRsLicense {
+            id,
+            expiry: None,
+            name: None,
+            key,
+            scheme: None,
+            policy: None,
+            status: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Validates a license key, optionally scoped to fingerprints and entitlements.
+    pub(crate) async fn validate_key_with(key: String, fingerprints: &[String], entitlements: &[String]) -> Result<License, Error> {
+        let client = Client::default();
+        let params = json!({
+            "meta": {
+                "key": key,
+                "scope": Self::build_scope(fingerprints, entitlements)
+            }
+        });
+
+        let cache_key = format!("{}\n{}", key, params["meta"]["scope"]);
+        let response = client.post_cached("licenses/actions/validate-key", Some(&params), &cache_key).await?;
+        Self::from_validation(response, &key)
+    }
+
+    async fn validate_id_with(license: &KeygenRsLicense, fingerprints: &[String], entitlements: &[String]) -> Result<License, Error> {
+        let client = Client::default();
+        let params = json!({
+            "meta": {
+                "nonce": chrono::Utc::now().timestamp(),
+                "scope": Self::build_scope(fingerprints, entitlements)
+            }
+        });
+
+        // the nonce differs between calls, so only the scope identifies the cached response
+        let cache_key = params["meta"]["scope"].to_string();
+        let response = client
+            .post_cached(&format!("licenses/{}/actions/validate", license.id), Some(&params), &cache_key)
+            .await?;
+        Self::from_validation(response, &license.key)
+    }
+
+    fn from_validation(response: Response, key: &str) -> Result<License, Error> {
+        let license = Self::from_resource(&response.body["data"]);
+        let meta = &response.body["meta"];
+
+        if !meta["valid"].as_bool().unwrap_or(false) {
+            let license = license.unwrap_or_else(|_| Self::stub(String::new(), key.to_string()));
+            return Err(Self::handle_validation_code(meta, license));
+        }
+        Ok(License::from(license?)
+            .with_usage(&response.body["data"])
+            .with_verification(response.verification))
+    }
+
+    fn build_scope(fingerprints: &[String], entitlements: &[String]) -> Value {
+        let config = keygen_rs::config::get_config();
+        let mut scope = json!({
+            "product": config.product.to_string(),
+        });
+        if !fingerprints.is_empty() {
+            scope["fingerprint"] = json!(fingerprints[0]);
+            if fingerprints.len() > 1 {
+                scope["components"] = json!(fingerprints[1..].to_vec());
+            }
+        }
+        if !entitlements.is_empty() {
+            scope["entitlements"] = json!(entitlements);
+        }
+        if let Some(env) = config.environment.as_ref() {
+            scope["environment"] = json!(env);
+        }
+        scope
+    }
+
+    fn handle_validation_code(meta: &Value, license: KeygenRsLicense) -> Error {
+        let code = meta["code"].as_str().unwrap_or_default().to_string();
+        let detail = meta["detail"].as_str().unwrap_or_default().to_string();
+        match code.as_str() {
+            "FINGERPRINT_SCOPE_MISMATCH" | "NO_MACHINES" | "NO_MACHINE" => {
+                Error::LicenseNotActivated { code, detail, license }
+            }
+            "EXPIRED" => Error::LicenseExpired { code, detail },
+            "SUSPENDED" => Error::LicenseSuspended { code, detail },
+            "TOO_MANY_MACHINES" => Error::LicenseTooManyMachines { code, detail },
+            "TOO_MANY_CORES" => Error::LicenseTooManyCores { code, detail },
+            "TOO_MANY_PROCESSES" => Error::LicenseTooManyProcesses { code, detail },
+            // raised as a `LicenseTooManyUses` by `KeygenError::from_error`
+            c if USAGE_LIMIT_CODES.contains(&c) => Error::KeygenApiError { code, detail, body: meta.clone() },
+            "FINGERPRINT_SCOPE_REQUIRED" | "FINGERPRINT_SCOPE_EMPTY" => {
+                Error::ValidationFingerprintMissing { code, detail }
+            }
+            "COMPONENTS_SCOPE_REQUIRED" | "COMPONENTS_SCOPE_EMPTY" => {
+                Error::ValidationComponentsMissing { code, detail }
+            }
+            "COMPONENTS_SCOPE_MISMATCH" => Error::ComponentNotActivated { code, detail },
+            "HEARTBEAT_NOT_STARTED" => Error::HeartbeatRequired { code, detail },
+            "HEARTBEAT_DEAD" => Error::HeartbeatDead { code, detail },
+            "PRODUCT_SCOPE_REQUIRED" | "PRODUCT_SCOPE_EMPTY" => {
+                Error::ValidationProductMissing { code, detail }
+            }
+            _ => Error::LicenseKeyInvalid { code, detail },
+        }
+    }
+
+    /// Checks out an encrypted license file for the license with the given id.
+    pub(crate) async fn checkout_with(license_id: &str, ttl: Option<i64>, include: &str) -> Result<KeygenRsLicenseFile, Error> {
+        let query = json!({ "encrypt": 1, "include": include, "ttl": ttl });
+        let response = Client::default()
+            .post(&format!("licenses/{}/actions/check-out", license_id), None, Some(&query))
+            .await?;
+        from_resource(&response.body["data"])
+    }
+
+    /// Deactivates the machine of the license with the given id which has the given fingerprint.
+    pub(crate) async fn deactivate_fingerprint_with(license_id: &str, fingerprint: &str) -> Result<(), Error> {
+        match Machine::find_by_fingerprint(license_id, fingerprint).await? {
+            Some(machine) => Machine::deactivate_with(&machine.inner().id).await,
+            None => Err(Error::MachineNotFound),
+        }
+    }
+
+    /// Fetches a resource related to the license with the given id, e.g. its `owner`, if it has one.
+    async fn related_with<T: DeserializeOwned>(license_id: &str, relationship: &str) -> Result<Option<T>, Error> {
+        let response = Client::default()
+            .get(&format!("licenses/{}/{}", license_id, relationship), None)
+            .await?;
+        match &response.body["data"] {
+            Value::Null => Ok(None),
+            data => from_resource(data).map(Some),
+        }
+    }
+
+    /// Builds a license from the response of an admin operation.
+    fn from_response(response: Response) -> Result<License, Error> {
+        Ok(License::from(Self::from_resource(&response.body["data"])?)
+            .with_usage(&response.body["data"])
+            .with_verification(response.verification))
+    }
+
+    /// Creates a license for the policy with the given id, optionally owned by a user.
+    async fn create_with(client: &Client, policy_id: &str, attributes: Value, user: Option<&str>) -> Result<License, Error> {
+        let mut relationships = json!({ "policy": admin::relationship("policies", policy_id) });
+        if let Some(user) = user {
+            relationships["owner"] = admin::relationship("users", user);
+        }
+        Self::from_response(admin::create_response(client, "licenses", attributes, Some(relationships)).await?)
+    }
+
+    /// Updates the given attributes of the license with the given id.
+    async fn update_with(client: &Client, license_id: &str, attributes: Value) -> Result<License, Error> {
+        Self::from_response(admin::update_response(client, "licenses", license_id, attributes).await?)
+    }
+
+    /// Runs an action, e.g. `suspend`, on the license with the given id.
+    async fn action_with(client: &Client, license_id: &str, action: &str, meta: Option<Value>) -> Result<License, Error> {
+        let params = meta.map(|meta| json!({ "meta": meta }));
+        let response = client
+            .post(&format!("licenses/{}/actions/{}", license_id, action), params.as_ref(), None)
+            .await?;
+        Self::from_response(response)
+    }
+
+    /// Runs an action on this license, resolving to the updated license.
+    fn action<'a>(&self, py: Python<'a>, client: Client, operation: &'static str, action: &'static str, meta: Option<Value>) -> PyResult<Bound<'a, PyAny>> {
+        let license_id = self.inner.id.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument(operation, Some(license_id.clone()), None, License::action_with(&client, &license_id, action, meta)).await;
+            match result {
+                Ok(license) => Ok(license),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    /// Deletes `path` as an admin, for operations which leave nothing to return.
+    fn remove<'a>(&self, py: Python<'a>, operation: &'static str, path: String) -> PyResult<Bound<'a, PyAny>> {
+        let client = Client::admin()?;
+        let license_id = self.inner.id.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument(operation, Some(license_id), None, client.delete(&path)).await;
+            match result {
+                Ok(_) => Ok(()),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    /// The attributes of a license which are set, skipping the ones left out.
+    fn attributes(name: Option<String>, metadata: Option<JsonValue>, expiry: Option<Date>) -> Value {
+        admin::attributes(json!({
+            "name": name,
+            "metadata": metadata.map(|JsonValue(metadata)| metadata),
+            "expiry": expiry.map(|expiry| DateTime::<Utc>::from(expiry).to_rfc3339()),
+        }))
+    }
+
+    /// Activates a machine for the license with the given id.
+    pub(crate) async fn activate_with(license_id: &str, fingerprint: &str, components: &[keygen_rs::component::Component]) -> Result<Machine, Error> {
+        let config = keygen_rs::config::get_config();
+        let client = Client::default();
+        let hostname = hostname::get()
+            .map(|h| h.to_string_lossy().into_owned())
+            .unwrap_or_else(|_| String::from("unknown"));
+        let platform = config
+            .platform
+            .or_else(|| Some(format!("{}/{}", env::consts::OS, env::consts::ARCH)));
+
+        let mut params = json!({
+          "data": {
+            "type": "machines",
+            "attributes": {
+              "fingerprint": fingerprint,
+              "cores": num_cpus::get(),
+              "hostname": hostname,
+              "platform": platform,
+            },
+            "relationships": {
+              "license": {
+                "data": {
+                  "type": "licenses",
+                  "id": license_id
+                }
+              },
+            }
+          }
+        });
+        if !components.is_empty() {
+            params["data"]["relationships"]["components"] = json!(components
+                .iter()
+                .map(keygen_rs::component::Component::create_object)
+                .collect::<Vec<Value>>());
+        }
+
+        let response = client.post("machines", Some(&params), None).await?;
+        Machine::from_response(response)
+    }
+}
+
+interface_methods! {
 impl License {
     #[getter]
     fn id(&self) -> PyResult<String> {
@@ -58,8 +441,28 @@ impl License {
     }
 
     #[getter]
-    fn status(&self) -> PyResult<Option<String>> {
-        Ok(self.inner.status.clone())
+    fn status(&self) -> PyResult<Option<LicenseStatus>> {
+        Ok(self.inner.status.as_deref().and_then(|status| {
+            let parsed = LicenseStatus::parse(status);
+            if parsed.is_none() {
+                log::debug!("unknown license status {}", status);
+            }
+            parsed
+        }))
+    }
+
+    /// Whether the license has expired, which licenses without an expiry never do.
+    #[pyo3(signature = (now=None))]
+    fn is_expired(&self, now: Option<Date>) -> PyResult<bool> {
+        let now = now_or(now);
+        Ok(self.inner.expiry.is_some_and(|expiry| expiry <= now))
+    }
+
+    /// Time until the license expires, negative once it has, or `None` if it has no expiry.
+    #[pyo3(signature = (now=None))]
+    fn expires_in(&self, now: Option<Date>) -> PyResult<Option<Delta>> {
+        let now = now_or(now);
+        Ok(self.inner.expiry.map(|expiry| Delta::from(expiry - now)))
     }
 
     #[getter]
@@ -67,6 +470,73 @@ impl License {
         Ok(self.inner.policy.clone())
     }
 
+    /// Fetches the policy of this license, see `policy` for its id.
+    fn fetch_policy<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let license_id = self.inner.id.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("license.policy", Some(license_id.clone()), None, Policy::fetch_with(&license_id)).await;
+            match result {
+                Ok(policy) => Ok(policy),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    /// Fetches the user owning this license, if it has one.
+    fn user<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let license_id = self.inner.id.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("license.user", Some(license_id.clone()), None, License::related_with::<User>(&license_id, "owner")).await;
+            match result {
+                Ok(user) => Ok(user),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    /// Fetches the group of this license, if it has one.
+    fn group<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let license_id = self.inner.id.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("license.group", Some(license_id.clone()), None, License::related_with::<Group>(&license_id, "group")).await;
+            match result {
+                Ok(group) => Ok(group),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    /// Fetches the product of this license.
+    fn product<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let license_id = self.inner.id.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("license.product", Some(license_id.clone()), None, async {
+                License::related_with::<Product>(&license_id, "product")
+                    .await?
+                    .ok_or_else(|| Error::UnexpectedError(format!("License {} has no product", license_id)))
+            })
+            .await;
+            match result {
+                Ok(product) => Ok(product),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    #[getter]
+    fn uses(&self) -> PyResult<Option<u64>> {
+        Ok(self.uses)
+    }
+
+    #[getter]
+    fn max_uses(&self) -> PyResult<Option<u64>> {
+        Ok(self.max_uses)
+    }
+
     #[getter]
     fn scheme(&self) -> PyResult<Option<SchemeCode>> {
         Ok(match self.inner.scheme {
@@ -75,28 +545,22 @@ impl License {
         })
     }
 
+    #[getter]
+    fn verification(&self) -> PyResult<Option<ResponseVerification>> {
+        Ok(self.verification.clone())
+    }
+
     #[staticmethod]
     #[pyo3(signature = (license_id, fingerprint, components=None))]
     fn activate_machine(py: Python<'_>, license_id: String, fingerprint: String, components: Option<Vec<Component>>) -> PyResult<Bound<'_, PyAny>> {
-        let license = KeygenRsLicense {
-            id: license_id,
-            expiry: None,
-            name: None,
-            key: String::new(),
-            scheme: None,
-            policy: None,
-            status: None,
-            metadata: HashMap::new(),
-        };
-
         let components = components.unwrap_or_default().iter().map(|c| {
             c.clone().into()
         }).collect::<Vec<keygen_rs::component::Component>>();
 
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
-            let result = license.activate(&fingerprint, &components).await;
+            let result = instrument("license.activate", Some(license_id.clone()), None, License::activate_with(&license_id, &fingerprint, &components)).await;
             match result {
-                Ok(machine) => Ok(Machine::from(machine)),
+                Ok(machine) => Ok(machine),
                 Err(e) => Err(KeygenError::from_error(e)),
             }
         })
@@ -109,12 +573,19 @@ impl License {
 
         let fingerprints_vec = pylist_to_string_slice(fingerprints)?;
         let entitlements_vec = pylist_to_string_slice(entitlements)?;
+        let my_struct = self.clone();
 
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
-            let result = keygen_rs::validate(&fingerprints_vec, &entitlements_vec).await;
+            let result = instrument(
+                "license.validate",
+                Some(my_struct.inner.id.clone()),
+                None,
+                License::validate_id_with(&my_struct.inner, &fingerprints_vec, &entitlements_vec),
+            )
+            .await;
 
             match result {
-                Ok(license) => Ok(License::from(license)),
+                Ok(license) => Ok(license),
                 Err(e) => Err(KeygenError::from_error(e)),
             }
         })
@@ -127,12 +598,14 @@ impl License {
 
         let fingerprints_vec = pylist_to_string_slice(fingerprints)?;
         let entitlements_vec = pylist_to_string_slice(entitlements)?;
+        let key = self.inner.key.clone();
+        let id = self.inner.id.clone();
 
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
-            let result = keygen_rs::validate(&fingerprints_vec, &entitlements_vec).await;
+            let result = instrument("license.validate_key", Some(id), None, License::validate_key_with(key, &fingerprints_vec, &entitlements_vec)).await;
 
             match result {
-                Ok(license) => Ok(License::from(license)),
+                Ok(license) => Ok(license),
                 Err(e) => Err(KeygenError::from_error(e)),
             }
         })
@@ -152,83 +625,177 @@ impl License {
         }).collect::<Vec<keygen_rs::component::Component>>();
 
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
-            let result = my_struct.inner.activate(&fingerprint, &components).await;
+            let result = instrument(
+                "license.activate",
+                Some(my_struct.inner.id.clone()),
+                None,
+                License::activate_with(&my_struct.inner.id, &fingerprint, &components),
+            )
+            .await;
             match result {
-                Ok(machine) => Ok(Machine::from(machine)),
+                Ok(machine) => Ok(machine),
                 Err(e) => Err(KeygenError::from_error(e)),
             }
         })
     }
 
+    #[pyo3(signature = (fingerprint, components=None, heartbeat_interval=None, wait=false, poll_interval=30, timeout=None))]
+    fn lease(
+        &self,
+        fingerprint: String,
+        components: Option<Vec<Component>>,
+        heartbeat_interval: Option<u64>,
+        wait: bool,
+        poll_interval: u64,
+        timeout: Option<u64>,
+    ) -> PyResult<Lease> {
+        let components = components.unwrap_or_default().into_iter().map(Into::into).collect();
+        let options = LeaseOptions { heartbeat_interval, wait, poll_interval, timeout };
+        Ok(Lease::new(self.inner.id.clone(), fingerprint, components, options))
+    }
+
     fn deactivate<'a>(&'a self, py: Python<'a>, id: String) -> PyResult<Bound<'a, PyAny>> {
-        let my_struct = self.clone();
+        let license_id = self.inner.id.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("license.deactivate", Some(license_id), Some(id.clone()), Machine::deactivate_with(&id)).await;
+            match result {
+                Ok(_) => Ok(()),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    fn machine_by_fingerprint<'a>(&'a self, py: Python<'a>, fingerprint: String) -> PyResult<Bound<'a, PyAny>> {
+        let license_id = self.inner.id.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument(
+                "license.machine_by_fingerprint",
+                Some(license_id.clone()),
+                None,
+                Machine::find_by_fingerprint(&license_id, &fingerprint),
+            )
+            .await;
+            match result {
+                Ok(machine) => Ok(machine),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    fn deactivate_fingerprint<'a>(&'a self, py: Python<'a>, fingerprint: String) -> PyResult<Bound<'a, PyAny>> {
+        let license_id = self.inner.id.clone();
 
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
-            let result = my_struct.inner.deactivate(&id).await;
+            let result = instrument("license.deactivate_fingerprint", Some(license_id.clone()), None, License::deactivate_fingerprint_with(&license_id, &fingerprint)).await;
             match result {
-                Ok(()) => Ok(()),
+                Ok(_) => Ok(()),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    fn deactivate_current<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let license_id = self.inner.id.clone();
+        let fingerprint = current_fingerprint(py)?;
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("license.deactivate_current", Some(license_id.clone()), None, License::deactivate_fingerprint_with(&license_id, &fingerprint)).await;
+            match result {
+                Ok(_) => Ok(()),
                 Err(e) => Err(KeygenError::from_error(e)),
             }
         })
     }
 
     fn machine<'a>(&'a self, py: Python<'a>, id: String) -> PyResult<Bound<'a, PyAny>> {
-        let my_struct = self.clone();
+        let license_id = self.inner.id.clone();
 
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
-            let result = my_struct.inner.machine(&id).await;
+            let result = instrument("license.machine", Some(license_id), Some(id.clone()), async {
+                Machine::from_response(Client::default().get(&format!("machines/{}", id), None).await?)
+            })
+            .await;
             match result {
-                Ok(machine) => Ok(Machine::from(machine)),
+                Ok(machine) => Ok(machine),
                 Err(e) => Err(KeygenError::from_error(e)),
             }
         })
     }
 
     fn machines<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
-        let my_struct = self.clone();
+        let license_id = self.inner.id.clone();
+        let pager = Pager::<Machine>::new(format!("licenses/{}/machines", license_id), json!({}), MAX_PAGE_SIZE)?;
 
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
-            let result = my_struct.inner.machines().await;
+            let result = instrument("license.machines", Some(license_id), None, pager.collect()).await;
             match result {
-                Ok(machines) => {
-                    Ok(machines.iter().map(|m| {
-                        Machine::from(m.clone())
-                    }).collect::<Vec<Machine>>())
-                },
+                Ok(machines) => Ok(machines),
                 Err(e) => Err(KeygenError::from_error(e)),
             }
         })
     }
 
+    #[pyo3(signature = (page_size=MAX_PAGE_SIZE, fingerprint=None, platform=None, hostname=None, heartbeat_status=None))]
+    fn iter_machines(
+        &self,
+        page_size: u32,
+        fingerprint: Option<String>,
+        platform: Option<String>,
+        hostname: Option<String>,
+        heartbeat_status: Option<String>,
+    ) -> PyResult<PageIterator> {
+        let query = json!({
+            "license": self.inner.id,
+            "fingerprint": fingerprint,
+            "hostname": hostname,
+            "status": heartbeat_status.map(|status| status.to_uppercase()),
+        });
+        let mut pager = Pager::<Machine>::new("machines".to_string(), query, page_size)?;
+        // the API does not filter machines by platform
+        if let Some(platform) = platform {
+            pager = pager.with_local_filter(move |machine| machine["attributes"]["platform"].as_str() == Some(platform.as_str()));
+        }
+        Ok(PageIterator::machines("license.machines", Some(self.inner.id.clone()), pager))
+    }
+
     fn entitlements<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
-        let my_struct = self.clone();
+        let license_id = self.inner.id.clone();
+        let pager = Pager::<Entitlement>::new(format!("licenses/{}/entitlements", license_id), json!({}), MAX_PAGE_SIZE)?;
 
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
-            let result = my_struct.inner.entitlements().await;
+            let result = instrument("license.entitlements", Some(license_id), None, pager.collect()).await;
             match result {
-                Ok(entitlements) => {
-                    Ok(entitlements.iter().map(|entitlement| {
-                        let e = entitlement.clone();
-                        Entitlement::build(
-                            e.id,
-                            e.name,
-                            e.code,
-                            e.created,
-                            e.updated
-                        )
-                    }).collect::<Vec<Entitlement>>())
-                },
+                Ok(entitlements) => Ok(entitlements),
                 Err(e) => Err(KeygenError::from_error(e)),
             }
         })
     }
 
+    #[pyo3(signature = (page_size=MAX_PAGE_SIZE, code=None))]
+    fn iter_entitlements(&self, page_size: u32, code: Option<String>) -> PyResult<PageIterator> {
+        let mut pager = Pager::<Entitlement>::new(format!("licenses/{}/entitlements", self.inner.id), json!({}), page_size)?;
+        // the API does not filter the entitlements of a license
+        if let Some(code) = code {
+            pager = pager.with_local_filter(move |entitlement| entitlement["attributes"]["code"].as_str() == Some(code.as_str()));
+        }
+        Ok(PageIterator::entitlements("license.entitlements", Some(self.inner.id.clone()), pager))
+    }
+
     #[pyo3(signature = (ttl=None, include=None))]
     fn checkout<'a>(&'a self, py: Python<'a>, ttl: Option<i64>, include: Option<Vec<String>>) -> PyResult<Bound<'a, PyAny>> {
         let my_struct = self.clone();
+        let include = include.map(|i| i.join(",")).unwrap_or_else(|| "entitlements".to_string());
 
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
-            let result = my_struct.inner.checkout(&keygen_rs::license::LicenseCheckoutOpts { ttl, include }).await;
+            let result = instrument(
+                "license.checkout",
+                Some(my_struct.inner.id.clone()),
+                None,
+                License::checkout_with(&my_struct.inner.id, ttl, &include),
+            )
+            .await;
             match result {
                 Ok(lf) => {
                     Ok(LicenseFile::from(lf))
@@ -237,6 +804,94 @@ impl License {
             }
         })
     }
+
+    #[staticmethod]
+    #[pyo3(signature = (policy_id, name=None, metadata=None, expiry=None, user=None))]
+    fn create(
+        py: Python<'_>,
+        policy_id: String,
+        name: Option<String>,
+        metadata: Option<JsonValue>,
+        expiry: Option<Date>,
+        user: Option<String>,
+    ) -> PyResult<Bound<'_, PyAny>> {
+        let client = Client::admin()?;
+        let attributes = License::attributes(name, metadata, expiry);
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("license.create", None, None, License::create_with(&client, &policy_id, attributes, user.as_deref())).await;
+            match result {
+                Ok(license) => Ok(license),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    #[pyo3(signature = (name=None, metadata=None, expiry=None))]
+    fn update<'a>(&'a self, py: Python<'a>, name: Option<String>, metadata: Option<JsonValue>, expiry: Option<Date>) -> PyResult<Bound<'a, PyAny>> {
+        let client = Client::admin()?;
+        let license_id = self.inner.id.clone();
+        let attributes = License::attributes(name, metadata, expiry);
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("license.update", Some(license_id.clone()), None, License::update_with(&client, &license_id, attributes)).await;
+            match result {
+                Ok(license) => Ok(license),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    fn suspend<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.action(py, Client::admin()?, "license.suspend", "suspend", None)
+    }
+
+    fn reinstate<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.action(py, Client::admin()?, "license.reinstate", "reinstate", None)
+    }
+
+    fn renew<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.action(py, Client::admin()?, "license.renew", "renew", None)
+    }
+
+    #[pyo3(signature = (n=1))]
+    fn increment_usage<'a>(&'a self, py: Python<'a>, n: u64) -> PyResult<Bound<'a, PyAny>> {
+        // a license may record its own usage, so this does not require a token
+        self.action(py, Client::default(), "license.increment_usage", "increment-usage", Some(json!({ "increment": n })))
+    }
+
+    #[pyo3(signature = (n=1))]
+    fn decrement_usage<'a>(&'a self, py: Python<'a>, n: u64) -> PyResult<Bound<'a, PyAny>> {
+        self.action(py, Client::admin()?, "license.decrement_usage", "decrement-usage", Some(json!({ "decrement": n })))
+    }
+
+    fn reset_usage<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.action(py, Client::admin()?, "license.reset_usage", "reset-usage", None)
+    }
+
+    #[pyo3(signature = (expiry=None, max_activations=None, max_deactivations=None))]
+    fn generate_token<'a>(&'a self, py: Python<'a>, expiry: Option<Date>, max_activations: Option<i64>, max_deactivations: Option<i64>) -> PyResult<Bound<'a, PyAny>> {
+        let client = Client::admin()?;
+        let license_id = self.inner.id.clone();
+        let attributes = Token::attributes(expiry, max_activations, max_deactivations);
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("license.generate_token", Some(license_id.clone()), None, Token::generate_with(&client, &license_id, attributes)).await;
+            match result {
+                Ok(token) => Ok(token),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    fn revoke<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.remove(py, "license.revoke", format!("licenses/{}/actions/revoke", self.inner.id))
+    }
+
+    fn delete<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.remove(py, "license.delete", format!("licenses/{}", self.inner.id))
+    }
+}
 }
 
 impl From<SchemeCode> for keygen_rs::license::SchemeCode {
@@ -1,11 +1,27 @@
-use crate::date::Date;
-use crate::utils::{create_interface, create_interface_no_clone};
+use crate::date::{now_or, Date, Delta};
+use chrono::{DateTime, Duration, Utc};
+use crate::identity::Identity;
+use crate::state::InnerState;
+use crate::utils::{create_interface, create_interface_no_clone, interface_methods};
 use keygen_rs::license_file::LicenseFile as KeygenRsLicenseFile;
 use keygen_rs::license_file::LicenseFileDataset as KeygenRsLicenseFileDataset;
 use pyo3::prelude::*;
 use crate::certificate::Certificate;
+use crate::clock;
 use crate::errors::KeygenError;
 use crate::license::License;
+use crate::group::Group;
+use crate::policy::Policy;
+use crate::product::Product;
+use crate::user::User;
+use base64::engine::general_purpose;
+use base64::Engine;
+use keygen_rs::errors::Error;
+use openssl::symm::{decrypt_aead, Cipher};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use crate::verifier::{PublicKeys, Verifier};
 
 #[pymodule(name = "license_file")]
 pub fn license_file_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -21,10 +37,87 @@ pub fn license_file_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     Ok(())
 }
 
-create_interface!(LicenseFile, KeygenRsLicenseFile);
-create_interface_no_clone!(LicenseFileDataset, KeygenRsLicenseFileDataset);
+create_interface!(LicenseFile, KeygenRsLicenseFile, module = "keygen_sh.license_file");
 
-#[pymethods]
+impl InnerState for KeygenRsLicenseFile {}
+
+impl Identity for LicenseFile {
+    fn resource_id(&self) -> Option<&str> {
+        Some(&self.inner.id)
+    }
+
+    fn repr_fields(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("id", Some(self.inner.id.clone())),
+            ("issued", Some(self.inner.issued.to_rfc3339())),
+            ("expiry", Some(self.inner.expiry.to_rfc3339())),
+            ("ttl", Some(self.inner.ttl.to_string())),
+        ]
+    }
+}
+create_interface_no_clone!(
+    LicenseFileDataset,
+    KeygenRsLicenseFileDataset,
+    policy: Option<Policy>,
+    product: Option<Product>,
+    user: Option<User>,
+    group: Option<Group>
+);
+
+/// Decrypts the payload of a license or machine file, which `keygen_rs` only keeps the license
+/// and machine of.
+pub(crate) fn decrypt_payload(enc: &str, key: &str) -> Result<Value, Error> {
+    let parts = enc
+        .split('.')
+        .map(|part| general_purpose::STANDARD.decode(part))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| Error::DecryptionError("Failed to decode encrypted data".into()))?;
+    let [ciphertext, iv, tag] = parts.as_slice() else {
+        return Err(Error::DecryptionError("Invalid encrypted data format".into()));
+    };
+
+    let key = Sha256::digest(key.as_bytes());
+    let plaintext = decrypt_aead(Cipher::aes_256_gcm(), &key, Some(iv), &[], ciphertext, tag)
+        .map_err(|_| Error::DecryptionError("Decryption failed".into()))?;
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
+/// The `meta` of the payload of a license file.
+#[derive(Deserialize)]
+struct Meta {
+    issued: DateTime<Utc>,
+    expiry: DateTime<Utc>,
+    ttl: i32,
+}
+
+impl LicenseFile {
+    /// Decrypts the file like `keygen_rs`, but only once, returning its dataset along with the
+    /// resources it includes, which `keygen_rs` drops.
+    fn decrypt_dataset(&self, key: &str) -> Result<(KeygenRsLicenseFileDataset, Value), Error> {
+        let certificate = self.inner.certificate()?;
+        match certificate.alg.as_str() {
+            "aes-256-gcm+ed25519" => {}
+            "aes-256-gcm+rsa-pss-sha256" | "aes-256-gcm+rsa-sha256" => return Err(Error::LicenseFileNotSupported(certificate.alg)),
+            _ => return Err(Error::LicenseFileNotEncrypted),
+        }
+
+        let mut payload = decrypt_payload(&certificate.enc, key)?;
+        let invalid = |e: serde_json::Error| Error::LicenseFileInvalid(e.to_string());
+        let meta: Meta = serde_json::from_value(payload["meta"].take()).map_err(invalid)?;
+        let dataset = KeygenRsLicenseFileDataset {
+            license: License::from_resource(&payload["data"]).map_err(|e| Error::LicenseFileInvalid(e.to_string()))?,
+            issued: meta.issued,
+            expiry: meta.expiry,
+            ttl: meta.ttl,
+        };
+        if meta.ttl != 0 && Utc::now() > meta.expiry {
+            return Err(Error::LicenseFileExpired(dataset));
+        }
+        Ok((dataset, payload["included"].take()))
+    }
+}
+
+interface_methods! {
 impl LicenseFile {
     #[getter]
     fn id(&self) -> PyResult<String> {
@@ -51,24 +144,50 @@ impl LicenseFile {
         Ok(self.inner.ttl)
     }
 
+    /// Whether the file has expired, allowing for `max_clock_drift`.
+    #[pyo3(signature = (now=None))]
+    fn is_expired(&self, now: Option<Date>) -> PyResult<bool> {
+        Ok(self.inner.expiry + clock::max_drift() < now_or(now))
+    }
+
+    /// Time until the file expires, allowing for `max_clock_drift`, or zero once it has.
+    #[pyo3(signature = (now=None))]
+    fn time_remaining(&self, now: Option<Date>) -> PyResult<Delta> {
+        let remaining = self.inner.expiry + clock::max_drift() - now_or(now);
+        Ok(Delta::from(remaining.max(Duration::zero())))
+    }
+
     #[staticmethod]
     fn build_from_cert(key: String, content: String) -> PyResult<Self> {
         match KeygenRsLicenseFile::from_cert(&key, &content) {
-            Ok(lf) => Ok(Self { inner: lf }),
+            Ok(lf) => {
+                clock::check(&[lf.issued])?;
+                Ok(Self { inner: lf })
+            }
             Err(e) => Err(KeygenError::from_error(e))
         }
     }
 
-    fn verify(&self) -> PyResult<()> {
-        match self.inner.verify() {
-            Ok(_) => Ok(()),
+    #[pyo3(signature = (public_key=None))]
+    fn verify(&self, public_key: Option<PublicKeys>) -> PyResult<String> {
+        match Verifier::new(public_key).verify_license_file(&self.inner) {
+            Ok(public_key) => Ok(public_key),
             Err(e) => Err(KeygenError::from_error(e)),
         }
     }
 
     fn decrypt(&self, key: String) -> PyResult<LicenseFileDataset> {
-        match self.inner.decrypt(&key) {
-            Ok(lfd) => Ok(LicenseFileDataset::from(lfd)),
+        match self.decrypt_dataset(&key) {
+            Ok((lfd, included)) => {
+                clock::check(&[lfd.issued])?;
+                Ok(LicenseFileDataset {
+                    policy: Policy::from_included(&included),
+                    product: Product::from_included(&included),
+                    user: User::from_included(&included),
+                    group: Group::from_included(&included),
+                    ..LicenseFileDataset::from(lfd)
+                })
+            }
             Err(e) => Err(KeygenError::from_error(e)),
         }
     }
@@ -80,6 +199,7 @@ impl LicenseFile {
         }
     }
 }
+}
 
 #[pymethods]
 impl LicenseFileDataset {
@@ -102,4 +222,28 @@ impl LicenseFileDataset {
     fn ttl(&self) -> PyResult<i32> {
         Ok(self.inner.ttl)
     }
+
+    /// The policy of the license, if the file was checked out including it.
+    #[getter]
+    fn policy(&self) -> PyResult<Option<Policy>> {
+        Ok(self.policy.clone())
+    }
+
+    /// The product of the license, if the file was checked out including it.
+    #[getter]
+    fn product(&self) -> PyResult<Option<Product>> {
+        Ok(self.product.clone())
+    }
+
+    /// The user owning the license, if the file was checked out including the owner.
+    #[getter]
+    fn user(&self) -> PyResult<Option<User>> {
+        Ok(self.user.clone())
+    }
+
+    /// The group of the license, if the file was checked out including it.
+    #[getter]
+    fn group(&self) -> PyResult<Option<Group>> {
+        Ok(self.group.clone())
+    }
 }
\ No newline at end of file
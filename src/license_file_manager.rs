@@ -0,0 +1,434 @@
+use crate::clock;
+use crate::errors::KeygenError;
+use crate::json::JsonValue;
+use crate::license::License;
+use crate::license_file::LicenseFile;
+use crate::logging::Secrets;
+use crate::machine::Machine;
+use crate::machine_file::MachineFile;
+use crate::telemetry::instrument;
+use crate::verifier::Verifier;
+use chrono::{DateTime, Duration, Utc};
+use keygen_rs::errors::Error;
+use keygen_rs::license_file::LicenseFile as KeygenRsLicenseFile;
+use keygen_rs::machine_file::MachineFile as KeygenRsMachineFile;
+use pyo3::prelude::*;
+use pyo3::{pyclass, pymethods, pymodule, Bound, PyResult, Python};
+use serde_json::json;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+
+#[pymodule(name = "license_file_manager")]
+pub fn license_file_manager_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Hack: workaround for https://github.com/PyO3/pyo3/issues/759
+    Python::attach(|py| {
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("keygen_sh.license_file_manager", m)
+    })?;
+
+    m.add_class::<LicenseFileManager>()?;
+    Ok(())
+}
+
+/// The license or machine to keep a checked-out file of, as accepted from Python.
+#[derive(FromPyObject)]
+enum Target {
+    License(License),
+    Machine(Machine),
+}
+
+/// The license or machine a file is checked out for, with the key to decrypt the file.
+#[derive(Debug, Clone)]
+enum Subject {
+    License { id: String, key: String },
+    Machine { id: String, key: String },
+}
+
+#[derive(Debug, Clone)]
+enum CheckedOut {
+    License(KeygenRsLicenseFile),
+    Machine(KeygenRsMachineFile),
+}
+
+impl CheckedOut {
+    fn id(&self) -> &str {
+        match self {
+            CheckedOut::License(file) => &file.id,
+            CheckedOut::Machine(file) => &file.id,
+        }
+    }
+
+    fn certificate(&self) -> &str {
+        match self {
+            CheckedOut::License(file) => &file.certificate,
+            CheckedOut::Machine(file) => &file.certificate,
+        }
+    }
+
+    fn issued(&self) -> DateTime<Utc> {
+        match self {
+            CheckedOut::License(file) => file.issued,
+            CheckedOut::Machine(file) => file.issued,
+        }
+    }
+
+    fn expiry(&self) -> DateTime<Utc> {
+        match self {
+            CheckedOut::License(file) => file.expiry,
+            CheckedOut::Machine(file) => file.expiry,
+        }
+    }
+}
+
+impl<'py> IntoPyObject<'py> for CheckedOut {
+    type Target = PyAny;
+    type Output = Bound<'py, Self::Target>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        match self {
+            CheckedOut::License(file) => Ok(LicenseFile::from(file).into_pyobject(py)?.into_any()),
+            CheckedOut::Machine(file) => Ok(MachineFile::from(file).into_pyobject(py)?.into_any()),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Options {
+    path: PathBuf,
+    ttl: Option<i64>,
+    include: Option<String>,
+    refresh_ratio: f64,
+    expiring_within: Option<i64>,
+    retry_interval: u64,
+    on_event: Option<Arc<Py<PyAny>>>,
+}
+
+/// State shared between the manager and its background refresh task.
+#[derive(Debug)]
+struct Shared {
+    subject: Subject,
+    options: Options,
+    /// The license key the files are decrypted with, which may not be the configured one.
+    secrets: Secrets,
+    current: Mutex<Option<CheckedOut>>,
+}
+
+impl Shared {
+    fn current(&self) -> Option<CheckedOut> {
+        self.current.lock().unwrap().clone()
+    }
+
+    /// Loads the file on disk, provided it is genuine and not expired.
+    fn load(&self) -> Result<CheckedOut, Error> {
+        let content = fs::read_to_string(&self.options.path)
+            .map_err(|e| Error::UnexpectedError(format!("Failed to read {}: {}", self.options.path.display(), e)))?;
+
+        let file = match &self.subject {
+            Subject::License { key, .. } => CheckedOut::License(KeygenRsLicenseFile::from_cert(key, &content)?),
+            Subject::Machine { key, .. } => {
+                let file = KeygenRsMachineFile::from_cert(key, &content)?;
+                if file.expiry < Utc::now() {
+                    return Err(Error::MachineFileExpired(file.decrypt(key)?));
+                }
+                CheckedOut::Machine(file)
+            }
+        };
+        Self::verify(&file)?;
+        Ok(file)
+    }
+
+    /// Checks out a new file, stores it on disk and makes it the current file.
+    async fn refresh(&self) -> Result<CheckedOut, Error> {
+        let result = match &self.subject {
+            Subject::License { id, .. } => {
+                let include = self.options.include.as_deref().unwrap_or("entitlements");
+                instrument(
+                    "license_file_manager.refresh",
+                    Some(id.clone()),
+                    None,
+                    License::checkout_with(id, self.options.ttl, include),
+                )
+                .await
+                .map(CheckedOut::License)
+            }
+            Subject::Machine { id, .. } => {
+                let include = self.options.include.as_deref().unwrap_or("license.entitlements");
+                instrument(
+                    "license_file_manager.refresh",
+                    None,
+                    Some(id.clone()),
+                    Machine::checkout_with(id, self.options.ttl, include),
+                )
+                .await
+                .map(CheckedOut::Machine)
+            }
+        }
+        .and_then(|file| {
+            Self::verify(&file)?;
+            self.store(&file)?;
+            Ok(file)
+        });
+
+        match &result {
+            Ok(file) => {
+                *self.current.lock().unwrap() = Some(file.clone());
+                self.emit("refreshed", Some(file), None);
+            }
+            Err(e) => self.emit("refresh_failed", self.current().as_ref(), Some(e)),
+        }
+        result
+    }
+
+    fn verify(file: &CheckedOut) -> Result<String, Error> {
+        let verifier = Verifier::new(None);
+        match file {
+            CheckedOut::License(file) => verifier.verify_license_file(file),
+            CheckedOut::Machine(file) => verifier.verify_machine_file(file),
+        }
+    }
+
+    fn store(&self, file: &CheckedOut) -> Result<(), Error> {
+        let path = &self.options.path;
+        let write = || {
+            if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                fs::create_dir_all(dir)?;
+            }
+            let tmp = path.with_extension("tmp");
+            fs::write(&tmp, file.certificate())?;
+            fs::rename(&tmp, path)
+        };
+        write().map_err(|e: std::io::Error| Error::UnexpectedError(format!("Failed to write {}: {}", path.display(), e)))
+    }
+
+    /// When the file should be refreshed, i.e. once `refresh_ratio` of its TTL has passed.
+    fn refresh_at(&self, file: &CheckedOut) -> DateTime<Utc> {
+        let ttl = file.expiry() - file.issued();
+        file.issued() + Duration::milliseconds((ttl.num_milliseconds() as f64 * self.options.refresh_ratio) as i64)
+    }
+
+    /// When the expiry of the file is imminent, by default once 90% of its TTL has passed.
+    fn expiring_at(&self, file: &CheckedOut) -> DateTime<Utc> {
+        let within = match self.options.expiring_within {
+            Some(seconds) => Duration::seconds(seconds),
+            None => (file.expiry() - file.issued()) / 10,
+        };
+        file.expiry() - within
+    }
+
+    fn emit(&self, kind: &str, file: Option<&CheckedOut>, error: Option<&Error>) {
+        match error {
+            Some(e) => log::info!("license file {}: {}", kind, self.secrets.redact(&e.to_string())),
+            None => log::debug!("license file {}", kind),
+        }
+
+        let Some(on_event) = &self.options.on_event else {
+            return;
+        };
+        let event = json!({
+            "type": kind,
+            "path": self.options.path.display().to_string(),
+            "id": file.map(CheckedOut::id),
+            "issued": file.map(|f| f.issued().to_rfc3339()),
+            "expiry": file.map(|f| f.expiry().to_rfc3339()),
+            "error": error.map(|e| self.secrets.redact(&e.to_string())),
+        });
+
+        Python::attach(|py| {
+            if let Err(e) = on_event.call1(py, (JsonValue(event),)) {
+                log::warn!("license file event callback failed: {}", e);
+            }
+        });
+    }
+
+    /// Refreshes the current file whenever it is due, retrying every `retry_interval` seconds
+    /// while the API cannot be reached.
+    async fn run(self: Arc<Self>) {
+        let mut warned: Option<(DateTime<Utc>, &str)> = None;
+
+        loop {
+            let Some(file) = self.current() else {
+                return;
+            };
+            let now = Utc::now();
+            let refresh_at = self.refresh_at(&file);
+            if now < refresh_at {
+                tokio::time::sleep((refresh_at - now).to_std().unwrap_or_default()).await;
+                continue;
+            }
+
+            if self.refresh().await.is_err() {
+                let now = Utc::now();
+                let kind = if now >= file.expiry() {
+                    Some("expired")
+                } else if now >= self.expiring_at(&file) {
+                    Some("expiring")
+                } else {
+                    None
+                };
+                if let Some(kind) = kind {
+                    if warned != Some((file.expiry(), kind)) {
+                        warned = Some((file.expiry(), kind));
+                        self.emit(kind, Some(&file), None);
+                    }
+                }
+                tokio::time::sleep(std::time::Duration::from_secs(self.options.retry_interval)).await;
+            }
+        }
+    }
+}
+
+/// Keeps a current license or machine file checked out on disk, refreshing it in the background
+/// before it expires and falling back to the file on disk while the API cannot be reached.
+#[pyclass(frozen)]
+pub struct LicenseFileManager {
+    shared: Arc<Shared>,
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl LicenseFileManager {
+    fn spawn(&self) {
+        let mut task = self.task.lock().unwrap();
+        if task.as_ref().is_some_and(|task| !task.is_finished()) {
+            return;
+        }
+        *task = Some(pyo3_async_runtimes::tokio::get_runtime().spawn(self.shared.clone().run()));
+    }
+
+    fn abort(&self) {
+        if let Some(task) = self.task.lock().unwrap().take() {
+            task.abort();
+        }
+    }
+}
+
+impl Drop for LicenseFileManager {
+    fn drop(&mut self) {
+        self.abort();
+    }
+}
+
+#[pymethods]
+impl LicenseFileManager {
+    #[new]
+    #[pyo3(signature = (target, path, license_key=None, ttl=None, include=None, refresh_ratio=0.8, expiring_within=None, retry_interval=60, on_event=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        target: Target,
+        path: PathBuf,
+        license_key: Option<String>,
+        ttl: Option<i64>,
+        include: Option<Vec<String>>,
+        refresh_ratio: f64,
+        expiring_within: Option<i64>,
+        retry_interval: u64,
+        on_event: Option<Py<PyAny>>,
+    ) -> PyResult<Self> {
+        let (subject, license_key) = match target {
+            Target::License(license) => {
+                let key = license.inner().key.clone();
+                (Subject::License { id: license.inner().id.clone(), key: key.clone() }, key)
+            }
+            Target::Machine(machine) => {
+                // machine files are encrypted with the license key and the machine fingerprint
+                let license_key = license_key
+                    .or_else(|| keygen_rs::config::get_config().license_key)
+                    .ok_or_else(|| KeygenError::from_error(Error::LicenseKeyMissing))?;
+                let subject = Subject::Machine {
+                    id: machine.inner().id.clone(),
+                    key: format!("{}{}", license_key, machine.inner().fingerprint),
+                };
+                (subject, license_key)
+            }
+        };
+
+        Ok(Self {
+            shared: Arc::new(Shared {
+                subject,
+                options: Options {
+                    path,
+                    ttl,
+                    include: include.map(|i| i.join(",")),
+                    refresh_ratio: refresh_ratio.clamp(0.0, 1.0),
+                    expiring_within,
+                    retry_interval,
+                    on_event: on_event.map(Arc::new),
+                },
+                secrets: Secrets::new([Some(license_key)]),
+                current: Mutex::new(None),
+            }),
+            task: Mutex::new(None),
+        })
+    }
+
+    #[getter]
+    fn path(&self) -> PyResult<PathBuf> {
+        Ok(self.shared.options.path.clone())
+    }
+
+    #[getter]
+    fn current(&self) -> PyResult<Option<CheckedOut>> {
+        Ok(self.shared.current())
+    }
+
+    #[getter]
+    fn running(&self) -> PyResult<bool> {
+        Ok(self.task.lock().unwrap().as_ref().is_some_and(|task| !task.is_finished()))
+    }
+
+    /// Loads the file on disk, or checks out a new one when it is missing, invalid or due for a
+    /// refresh, then starts refreshing it in the background.
+    fn start<'a>(slf: Bound<'a, Self>, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let manager = slf.unbind();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let shared = manager.get().shared.clone();
+            clock::check(&clock::modified(&shared.options.path).into_iter().collect::<Vec<_>>())?;
+
+            let loaded = shared.load();
+            match &loaded {
+                Ok(file) => clock::check(&[file.issued()])?,
+                Err(e) => log::debug!("not using license file on disk: {}", e),
+            }
+
+            let file = match loaded {
+                Ok(file) if Utc::now() < shared.refresh_at(&file) => {
+                    *shared.current.lock().unwrap() = Some(file.clone());
+                    file
+                }
+                loaded => match (shared.refresh().await, loaded) {
+                    (Ok(file), _) => file,
+                    (Err(_), Ok(file)) => {
+                        // offline, but the file on disk is still valid
+                        *shared.current.lock().unwrap() = Some(file.clone());
+                        file
+                    }
+                    (Err(e), Err(_)) => return Err(KeygenError::from_error(e)),
+                },
+            };
+
+            manager.get().spawn();
+            Ok(file)
+        })
+    }
+
+    /// Checks out a new file right away.
+    fn refresh<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let shared = self.shared.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            match shared.refresh().await {
+                Ok(file) => Ok(file),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    /// Stops refreshing the file in the background.
+    fn stop(&self) {
+        self.abort();
+    }
+}
@@ -0,0 +1,131 @@
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const REDACTED: &str = "[REDACTED]";
+
+static LOGGER: PythonLogger = PythonLogger;
+
+/// Whether `LOGGER` is the installed logger, so that the max level is ours to set.
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Forwards the records of this crate to Python's `logging`, e.g. a record of the
+/// `keygen_sh::client` target to the `keygen_sh.client` logger.
+struct PythonLogger;
+
+impl Log for PythonLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target().starts_with("keygen_sh")
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let name = record.target().replace("::", ".");
+        let level = match record.level() {
+            Level::Error => 40,
+            Level::Warn => 30,
+            Level::Info => 20,
+            Level::Debug => 10,
+            Level::Trace => 5,
+        };
+
+        Python::attach(|py| {
+            let logger = py.import("logging")?.call_method1("getLogger", (name,))?;
+            if logger.call_method1("isEnabledFor", (level,))?.extract::<bool>()? {
+                logger.call_method1("log", (level, redact(&record.args().to_string())))?;
+            }
+            Ok::<_, PyErr>(())
+        })
+        .ok();
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs the bridge to Python's `logging`, unless another logger is already installed.
+pub(crate) fn init(py: Python<'_>) {
+    if log::set_logger(&LOGGER).is_ok() {
+        INSTALLED.store(true, Ordering::Relaxed);
+        refresh_level(py);
+    }
+}
+
+/// Reads the lowest level enabled for the `keygen_sh` loggers of Python and filters out the records
+/// below it, so that disabled records do not take the GIL. Called on `init` and `set_config`, so a
+/// level set on a logger afterwards only applies from the next `set_config`.
+pub(crate) fn refresh_level(py: Python<'_>) {
+    if !INSTALLED.load(Ordering::Relaxed) {
+        return;
+    }
+    match python_level(py) {
+        Ok(level) => log::set_max_level(level),
+        Err(e) => {
+            log::set_max_level(LevelFilter::Trace);
+            log::warn!("failed to read the level of the keygen_sh logger: {}", e);
+        }
+    }
+}
+
+fn python_level(py: Python<'_>) -> PyResult<LevelFilter> {
+    let logging = py.import("logging")?;
+    let mut level: i64 = logging
+        .call_method1("getLogger", ("keygen_sh",))?
+        .call_method0("getEffectiveLevel")?
+        .extract()?;
+    // a child logger, e.g. `keygen_sh.client`, may enable a lower level than `keygen_sh`
+    let loggers = logging.getattr("root")?.getattr("manager")?.getattr("loggerDict")?;
+    for (name, logger) in loggers.cast::<PyDict>()?.iter() {
+        // placeholders of loggers which were not created yet have no level
+        if name.extract::<String>()?.starts_with("keygen_sh.") && logger.hasattr("getEffectiveLevel")? {
+            level = level.min(logger.call_method0("getEffectiveLevel")?.extract()?);
+        }
+    }
+
+    Ok(match level {
+        ..=5 => LevelFilter::Trace,
+        6..=10 => LevelFilter::Debug,
+        11..=20 => LevelFilter::Info,
+        21..=30 => LevelFilter::Warn,
+        31..=40 => LevelFilter::Error,
+        _ => LevelFilter::Off,
+    })
+}
+
+/// Replaces the license key and token of the global config in `text`.
+pub(crate) fn redact(text: &str) -> String {
+    let config = keygen_rs::config::get_config();
+    Secrets::new([config.license_key, config.token]).replace(text)
+}
+
+/// The credentials a request is made with, which may differ from those of the global config, e.g.
+/// the key of another license or a token which was refreshed since.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Secrets(Vec<String>);
+
+impl Secrets {
+    pub(crate) fn new(secrets: impl IntoIterator<Item = Option<String>>) -> Self {
+        Self(secrets.into_iter().flatten().filter(|secret| !secret.is_empty()).collect())
+    }
+
+    /// Replaces these credentials and those of the global config in `text`.
+    pub(crate) fn redact(&self, text: &str) -> String {
+        self.replace(&redact(text))
+    }
+
+    /// Redacts the value of a header which carries credentials.
+    pub(crate) fn redact_header(&self, name: &str, value: &str) -> String {
+        if name.eq_ignore_ascii_case("authorization") {
+            REDACTED.to_string()
+        } else {
+            self.redact(value)
+        }
+    }
+
+    fn replace(&self, text: &str) -> String {
+        self.0.iter().fold(text.to_string(), |text, secret| text.replace(secret, REDACTED))
+    }
+}
@@ -1,10 +1,18 @@
+use crate::client::{from_resource, Client, Response};
 use crate::date::Date;
 use crate::errors::KeygenError;
 use crate::machine_file::MachineFile;
-use crate::utils::create_interface;
+use crate::telemetry::{instrument, record_license, record_machine};
+use crate::identity::Identity;
+use crate::state::InnerState;
+use crate::utils::{create_interface, interface_methods};
+use crate::verification::ResponseVerification;
+use keygen_rs::errors::Error;
 use keygen_rs::machine::Machine as KeygenRsMachine;
+use keygen_rs::machine_file::MachineFile as KeygenRsMachineFile;
 use pyo3::prelude::{PyAnyMethods, PyModule, PyModuleMethods};
-use pyo3::{pyclass, pymethods, pymodule, Bound, PyAny, PyResult, Python};
+use pyo3::{pyclass, pymodule, Bound, PyAny, PyResult, Python};
+use serde_json::json;
 
 #[pymodule(name = "machine")]
 pub fn machine_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -19,9 +27,87 @@ pub fn machine_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     Ok(())
 }
 
-create_interface!(Machine, KeygenRsMachine);
+create_interface!(Machine, KeygenRsMachine, module = "keygen_sh.machine", verification: Option<ResponseVerification>);
 
-#[pymethods]
+impl InnerState for KeygenRsMachine {}
+
+impl Identity for Machine {
+    fn resource_id(&self) -> Option<&str> {
+        Some(&self.inner.id)
+    }
+
+    fn repr_fields(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("id", Some(self.inner.id.clone())),
+            ("fingerprint", Some(self.inner.fingerprint.clone())),
+            ("name", self.inner.name.clone()),
+            ("platform", self.inner.platform.clone()),
+            ("heartbeat_status", Some(self.inner.heartbeat_status.clone())),
+        ]
+    }
+}
+
+impl Machine {
+    pub(crate) fn with_verification(mut self, verification: ResponseVerification) -> Self {
+        self.verification = Some(verification);
+        self
+    }
+
+    /// Checks out an encrypted machine file for the machine with the given id.
+    pub(crate) async fn checkout_with(machine_id: &str, ttl: Option<i64>, include: &str) -> Result<KeygenRsMachineFile, Error> {
+        let query = json!({ "encrypt": 1, "include": include, "ttl": ttl });
+        let response = Client::default()
+            .post(&format!("machines/{}/actions/check-out", machine_id), None, Some(&query))
+            .await?;
+        from_resource(&response.body["data"])
+    }
+
+    /// Finds the machine of the license with the given id which has the given fingerprint.
+    pub(crate) async fn find_by_fingerprint(license_id: &str, fingerprint: &str) -> Result<Option<Machine>, Error> {
+        let query = json!({ "license": license_id, "fingerprint": fingerprint, "limit": 1 });
+        let response = Client::default().get("machines", Some(&query)).await?;
+        Ok(Self::from_list_response(response)?.into_iter().next())
+    }
+
+    /// Pings the heartbeat of the machine with the given id.
+    pub(crate) async fn ping_with(machine_id: &str) -> Result<Machine, Error> {
+        let response = Client::default()
+            .post(&format!("machines/{}/actions/ping", machine_id), None, None)
+            .await?;
+        Machine::from_response(response)
+    }
+
+    /// Deactivates the machine with the given id.
+    pub(crate) async fn deactivate_with(machine_id: &str) -> Result<(), Error> {
+        Client::default().delete(&format!("machines/{}", machine_id)).await?;
+        Ok(())
+    }
+
+    /// Builds a machine from an API response whose `data` is a machine resource.
+    pub(crate) fn from_response(response: Response) -> Result<Machine, Error> {
+        let machine: KeygenRsMachine = from_resource(&response.body["data"])?;
+        record_machine(&machine.id);
+        if let Some(license_id) = response.body["data"]["relationships"]["license"]["data"]["id"].as_str() {
+            record_license(license_id);
+        }
+        Ok(Machine::from(machine).with_verification(response.verification))
+    }
+
+    /// Builds machines from an API response whose `data` is a list of machine resources.
+    pub(crate) fn from_list_response(response: Response) -> Result<Vec<Machine>, Error> {
+        response.body["data"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|data| {
+                let machine = from_resource(data)?;
+                Ok(Machine::from(machine).with_verification(response.verification.clone()))
+            })
+            .collect()
+    }
+}
+
+interface_methods! {
 impl Machine {
     #[getter]
     fn id(&self) -> PyResult<String> {
@@ -78,11 +164,22 @@ impl Machine {
         Ok(Date::from(self.inner.updated))
     }
 
+    #[getter]
+    fn verification(&self) -> PyResult<Option<ResponseVerification>> {
+        Ok(self.verification.clone())
+    }
+
     fn deactivate<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
         let my_struct = self.clone();
 
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
-            let result = my_struct.inner.deactivate().await;
+            let result = instrument(
+                "machine.deactivate",
+                None,
+                Some(my_struct.inner.id.clone()),
+                Machine::deactivate_with(&my_struct.inner.id),
+            )
+            .await;
             match result {
                 Ok(_) => Ok(()),
                 Err(e) => Err(KeygenError::from_error(e)),
@@ -98,12 +195,16 @@ impl Machine {
         include: Option<Vec<String>>,
     ) -> PyResult<Bound<'a, PyAny>> {
         let my_struct = self.clone();
+        let include = include.map(|i| i.join(",")).unwrap_or_else(|| "license.entitlements".to_string());
 
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
-            let result = my_struct
-                .inner
-                .checkout(&keygen_rs::machine::MachineCheckoutOpts { ttl, include })
-                .await;
+            let result = instrument(
+                "machine.checkout",
+                None,
+                Some(my_struct.inner.id.clone()),
+                Machine::checkout_with(&my_struct.inner.id, ttl, &include),
+            )
+            .await;
             match result {
                 Ok(mf) => Ok(MachineFile::from(mf)),
                 Err(e) => Err(KeygenError::from_error(e)),
@@ -115,11 +216,12 @@ impl Machine {
         let my_struct = self.clone();
 
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
-            let result = my_struct.inner.ping().await;
+            let result = instrument("machine.ping", None, Some(my_struct.inner.id.clone()), Machine::ping_with(&my_struct.inner.id)).await;
             match result {
-                Ok(m) => Ok(Machine::from(m)),
+                Ok(m) => Ok(m),
                 Err(e) => Err(KeygenError::from_error(e)),
             }
         })
     }
 }
+}
@@ -1,13 +1,18 @@
 use pyo3::prelude::*;
 use pyo3::{pymethods, pymodule, Bound, PyResult, Python};
-use crate::utils::{create_interface, create_interface_no_clone};
+use crate::identity::Identity;
+use crate::state::InnerState;
+use crate::utils::{create_interface, create_interface_no_clone, interface_methods};
 use keygen_rs::machine_file::MachineFile as KeygenRsMachineFile;
 use keygen_rs::machine_file::MachineFileDataset as KeygenRsMachineFileDataset;
 use crate::certificate::Certificate;
-use crate::date::Date;
+use crate::clock;
+use crate::date::{now_or, Date, Delta};
+use chrono::Duration;
 use crate::errors::KeygenError;
 use crate::license::License;
 use crate::machine::Machine;
+use crate::verifier::{PublicKeys, Verifier};
 
 #[pymodule(name = "machine_file")]
 pub fn machine_file_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -23,10 +28,27 @@ pub fn machine_file_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     Ok(())
 }
 
-create_interface!(MachineFile, KeygenRsMachineFile);
+create_interface!(MachineFile, KeygenRsMachineFile, module = "keygen_sh.machine_file");
+
+impl InnerState for KeygenRsMachineFile {}
+
+impl Identity for MachineFile {
+    fn resource_id(&self) -> Option<&str> {
+        Some(&self.inner.id)
+    }
+
+    fn repr_fields(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("id", Some(self.inner.id.clone())),
+            ("issued", Some(self.inner.issued.to_rfc3339())),
+            ("expiry", Some(self.inner.expiry.to_rfc3339())),
+            ("ttl", Some(self.inner.ttl.to_string())),
+        ]
+    }
+}
 create_interface_no_clone!(MachineFileDataset, KeygenRsMachineFileDataset);
 
-#[pymethods]
+interface_methods! {
 impl MachineFile {
     #[getter]
     fn id(&self) -> PyResult<String> {
@@ -52,25 +74,45 @@ impl MachineFile {
     fn ttl(&self) -> PyResult<i32> {
         Ok(self.inner.ttl)
     }
+
+    /// Whether the file has expired, allowing for `max_clock_drift`.
+    #[pyo3(signature = (now=None))]
+    fn is_expired(&self, now: Option<Date>) -> PyResult<bool> {
+        Ok(self.inner.expiry + clock::max_drift() < now_or(now))
+    }
+
+    /// Time until the file expires, allowing for `max_clock_drift`, or zero once it has.
+    #[pyo3(signature = (now=None))]
+    fn time_remaining(&self, now: Option<Date>) -> PyResult<Delta> {
+        let remaining = self.inner.expiry + clock::max_drift() - now_or(now);
+        Ok(Delta::from(remaining.max(Duration::zero())))
+    }
     
     #[staticmethod]
     fn from_cert(key: String, content: String) -> PyResult<Self> {
         match KeygenRsMachineFile::from_cert(&key, &content) {
-            Ok(mf) => Ok(MachineFile::from(mf)),
+            Ok(mf) => {
+                clock::check(&[mf.issued])?;
+                Ok(MachineFile::from(mf))
+            }
             Err(e) => Err(KeygenError::from_error(e)),
         }
     }
 
-    fn verify(&self) -> PyResult<()> {
-        match self.inner.verify() {
-            Ok(_) => Ok(()),
+    #[pyo3(signature = (public_key=None))]
+    fn verify(&self, public_key: Option<PublicKeys>) -> PyResult<String> {
+        match Verifier::new(public_key).verify_machine_file(&self.inner) {
+            Ok(public_key) => Ok(public_key),
             Err(e) => Err(KeygenError::from_error(e)),
         }
     }
 
     fn decrypt(&self, key: String) -> PyResult<MachineFileDataset> {
         match self.inner.decrypt(&key) {
-            Ok(mfd) => Ok(MachineFileDataset::from(mfd)),
+            Ok(mfd) => {
+                clock::check(&[mfd.issued])?;
+                Ok(MachineFileDataset::from(mfd))
+            }
             Err(e) => Err(KeygenError::from_error(e)),
         }
     }
@@ -82,6 +124,7 @@ impl MachineFile {
         }
     }
 }
+}
 
 #[pymethods]
 impl MachineFileDataset {
@@ -0,0 +1,1221 @@
+use crate::config::KeygenConfig;
+use crate::date::Date;
+use crate::json::JsonValue;
+use crate::testing::{random_id, Signer, Validity};
+use base64::{engine::general_purpose, Engine};
+use chrono::{DateTime, Utc};
+use keygen_rs::errors::Error;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+use pyo3::{pyclass, pymethods, Bound, PyAny, PyResult, Python};
+use semver::{Version, VersionReq};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// The API version the mock server answers as.
+const API_VERSION: &str = "1.7";
+
+#[derive(Debug, Clone)]
+struct MockPolicy {
+    id: String,
+    name: Option<String>,
+    max_machines: Option<i64>,
+    floating: bool,
+    require_heartbeat: bool,
+    heartbeat_duration: Option<i64>,
+    /// Seconds a renewal extends the expiry of its licenses by.
+    duration: Option<i64>,
+    created: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+struct MockLicense {
+    id: String,
+    key: String,
+    name: Option<String>,
+    status: String,
+    expiry: Option<DateTime<Utc>>,
+    policy: Option<String>,
+    owner: Option<String>,
+    metadata: Value,
+    entitlements: Vec<String>,
+    created: DateTime<Utc>,
+    updated: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+struct MockMachine {
+    id: String,
+    license: String,
+    fingerprint: String,
+    name: Option<String>,
+    platform: Option<String>,
+    hostname: Option<String>,
+    cores: Option<i64>,
+    heartbeat_status: String,
+    created: DateTime<Utc>,
+    updated: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+struct MockRelease {
+    id: String,
+    version: Version,
+    channel: String,
+    created: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+struct MockArtifact {
+    id: String,
+    release: String,
+    filename: String,
+    platform: Option<String>,
+    arch: Option<String>,
+    checksum: String,
+    signature: String,
+    /// What its download serves, which differs from what was signed for a corrupted download.
+    content: Vec<u8>,
+    created: DateTime<Utc>,
+}
+
+/// An error to answer the next matching requests with instead of handling them.
+#[derive(Debug, Clone)]
+struct InjectedError {
+    status: u16,
+    code: String,
+    detail: String,
+    path: Option<String>,
+    remaining: u32,
+}
+
+/// How the signature of the next matching responses is broken, to test their verification.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Tamper {
+    /// No `Keygen-Signature` header.
+    Unsigned,
+    /// A signature by another key.
+    Signature,
+    /// A body which differs from the signed digest.
+    Body,
+}
+
+#[derive(Debug, Clone)]
+struct Tampering {
+    tamper: Tamper,
+    path: Option<String>,
+    remaining: u32,
+}
+
+/// What the server knows, shared between the Python object and the connections it serves.
+struct State {
+    signer: Signer,
+    policies: Vec<MockPolicy>,
+    licenses: Vec<MockLicense>,
+    machines: Vec<MockMachine>,
+    releases: Vec<MockRelease>,
+    artifacts: Vec<MockArtifact>,
+    errors: Vec<InjectedError>,
+    tamperings: Vec<Tampering>,
+    requests: Vec<(String, String)>,
+}
+
+/// A request to the server, with the path relative to the account, e.g. `machines/m1`.
+struct Request {
+    method: String,
+    target: String,
+    path: Vec<String>,
+    query: HashMap<String, String>,
+    headers: HashMap<String, String>,
+    body: Value,
+}
+
+/// A response of the server, before it is signed.
+struct Reply {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: Option<Value>,
+    /// The content of a download, which is served unsigned instead of a body.
+    download: Option<Vec<u8>>,
+}
+
+impl Reply {
+    fn ok(body: Value) -> Self {
+        Self { status: 200, headers: vec![], body: Some(body), download: None }
+    }
+
+    fn created(body: Value) -> Self {
+        Self { status: 201, headers: vec![], body: Some(body), download: None }
+    }
+
+    fn redirect(location: String) -> Self {
+        Self { status: 303, headers: vec![("Location", location)], body: None, download: None }
+    }
+
+    fn download(content: Vec<u8>) -> Self {
+        Self { status: 200, headers: vec![], body: None, download: Some(content) }
+    }
+
+    /// The page of `resources` asked for by the `page[number]` and `page[size]` of the request,
+    /// linking the next page unless it is the last one. Without a page size, all of them.
+    fn page(resources: Vec<Value>, request: &Request) -> Self {
+        let param = |name: &str| request.query.get(name).and_then(|value| value.parse::<usize>().ok()).filter(|value| *value > 0);
+        let size = param("page[size]").unwrap_or(resources.len().max(1));
+        let number = param("page[number]").unwrap_or(1);
+        let link = |number: usize| format!("/{}?page[number]={}&page[size]={}", request.path.join("/"), number, size);
+
+        let next = (number * size < resources.len()).then(|| link(number + 1));
+        let page: Vec<Value> = resources.into_iter().skip((number - 1) * size).take(size).collect();
+        Self::ok(json!({ "data": page, "links": { "self": link(number), "next": next } }))
+    }
+
+    fn no_content() -> Self {
+        Self { status: 204, headers: vec![], body: None, download: None }
+    }
+
+    fn error(status: u16, code: &str, detail: &str) -> Self {
+        let body = json!({ "errors": [{ "title": reason(status), "detail": detail, "code": code }] });
+        let headers = match status {
+            429 => vec![
+                ("X-RateLimit-Window", "30s".to_string()),
+                ("X-RateLimit-Count", "61".to_string()),
+                ("X-RateLimit-Limit", "60".to_string()),
+                ("X-RateLimit-Remaining", "0".to_string()),
+                ("X-RateLimit-Reset", (Utc::now().timestamp() + 30).to_string()),
+                ("Retry-After", "30".to_string()),
+            ],
+            _ => vec![],
+        };
+        Self { status, headers, body: Some(body), download: None }
+    }
+
+    fn not_found() -> Self {
+        Self::error(404, "NOT_FOUND", "The requested resource was not found")
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        303 => "See Other",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        422 => "Unprocessable Entity",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        _ => "Error",
+    }
+}
+
+impl State {
+    fn policy(&self, id: &str) -> Option<&MockPolicy> {
+        self.policies.iter().find(|policy| policy.id == id)
+    }
+
+    fn license(&self, id: &str) -> Option<&MockLicense> {
+        self.licenses.iter().find(|license| license.id == id || license.key == id)
+    }
+
+    fn machines_of(&self, license_id: &str) -> Vec<&MockMachine> {
+        self.machines.iter().filter(|machine| machine.license == license_id).collect()
+    }
+
+    fn policy_resource(policy: &MockPolicy) -> Value {
+        json!({
+            "id": policy.id,
+            "type": "policies",
+            "attributes": {
+                "name": policy.name,
+                "duration": policy.duration,
+                "floating": policy.floating,
+                "requireHeartbeat": policy.require_heartbeat,
+                "heartbeatDuration": policy.heartbeat_duration,
+                "maxMachines": policy.max_machines,
+                "metadata": {},
+                "created": policy.created,
+                "updated": policy.created,
+            },
+        })
+    }
+
+    fn license_resource(&self, license: &MockLicense) -> Value {
+        let policy = license.policy.as_ref().map(|id| json!({ "data": { "type": "policies", "id": id } }));
+        let owner = license.owner.as_ref().map(|id| json!({ "data": { "type": "users", "id": id } }));
+        json!({
+            "id": license.id,
+            "type": "licenses",
+            "attributes": {
+                "key": license.key,
+                "name": license.name,
+                "expiry": license.expiry,
+                "status": license.status,
+                "metadata": license.metadata,
+                "created": license.created,
+                "updated": license.updated,
+            },
+            "relationships": { "policy": policy, "owner": owner },
+        })
+    }
+
+    fn machine_resource(&self, machine: &MockMachine) -> Value {
+        let policy = self.license(&machine.license).and_then(|license| license.policy.as_deref()).and_then(|id| self.policy(id));
+        json!({
+            "id": machine.id,
+            "type": "machines",
+            "attributes": {
+                "fingerprint": machine.fingerprint,
+                "name": machine.name,
+                "platform": machine.platform,
+                "hostname": machine.hostname,
+                "cores": machine.cores,
+                "requireHeartbeat": policy.is_some_and(|policy| policy.require_heartbeat),
+                "heartbeatStatus": machine.heartbeat_status,
+                "heartbeatDuration": policy.and_then(|policy| policy.heartbeat_duration),
+                "created": machine.created,
+                "updated": machine.updated,
+            },
+            "relationships": {
+                "license": { "data": { "type": "licenses", "id": machine.license } },
+            },
+        })
+    }
+
+    fn entitlement_resources(license: &MockLicense) -> Vec<Value> {
+        license
+            .entitlements
+            .iter()
+            .map(|code| {
+                json!({
+                    "id": format!("{}-{}", license.id, code.to_lowercase()),
+                    "type": "entitlements",
+                    "attributes": { "name": code, "code": code, "created": license.created, "updated": license.created },
+                })
+            })
+            .collect()
+    }
+
+    fn release_resource(release: &MockRelease) -> Value {
+        json!({
+            "id": release.id,
+            "type": "releases",
+            "attributes": {
+                "name": null,
+                "description": null,
+                "channel": release.channel,
+                "status": "PUBLISHED",
+                "tag": null,
+                "version": release.version.to_string(),
+                "metadata": {},
+                "yanked": null,
+                "created": release.created,
+                "updated": release.created,
+            },
+        })
+    }
+
+    fn artifact_resource(artifact: &MockArtifact) -> Value {
+        json!({
+            "id": artifact.id,
+            "type": "artifacts",
+            "attributes": {
+                "filename": artifact.filename,
+                "filetype": artifact.filename.rsplit_once('.').map(|(_, extension)| extension),
+                "filesize": artifact.content.len(),
+                "platform": artifact.platform,
+                "arch": artifact.arch,
+                "signature": artifact.signature,
+                "checksum": artifact.checksum,
+                "status": "UPLOADED",
+                "metadata": {},
+                "created": artifact.created,
+                "updated": artifact.created,
+            },
+            "relationships": {
+                "release": { "data": { "type": "releases", "id": artifact.release } },
+            },
+        })
+    }
+
+    /// The injected error for a request, if any is left.
+    fn injected_error(&mut self, path: &str) -> Option<Reply> {
+        let error = self
+            .errors
+            .iter_mut()
+            .find(|error| error.remaining > 0 && error.path.as_deref().is_none_or(|filter| path.contains(filter)))?;
+        error.remaining -= 1;
+        Some(Reply::error(error.status, &error.code, &error.detail))
+    }
+
+    /// The tampering of the response to a request, if any is left.
+    fn tampering(&mut self, path: &str) -> Option<Tamper> {
+        let tampering = self
+            .tamperings
+            .iter_mut()
+            .find(|tampering| tampering.remaining > 0 && tampering.path.as_deref().is_none_or(|filter| path.contains(filter)))?;
+        tampering.remaining -= 1;
+        Some(tampering.tamper)
+    }
+
+    fn handle(&mut self, request: &Request) -> Reply {
+        self.requests.push((request.method.clone(), request.target.clone()));
+        if let Some(reply) = self.injected_error(&request.path.join("/")) {
+            return reply;
+        }
+
+        let path: Vec<&str> = request.path.iter().map(String::as_str).collect();
+        match (request.method.as_str(), path.as_slice()) {
+            ("GET", ["me"]) => self.me(request),
+            ("POST", ["licenses", "actions", "validate-key"]) => {
+                let key = request.body["meta"]["key"].as_str().unwrap_or_default();
+                self.validate(key, &request.body["meta"]["scope"])
+            }
+            ("POST", ["licenses", id, "actions", "validate"]) => self.validate(id, &request.body["meta"]["scope"]),
+            ("POST", ["licenses", id, "actions", "check-out"]) => self.checkout_license(id, request),
+            ("POST", ["licenses"]) => self.admin(request, |state| state.create_license(&request.body["data"])),
+            ("PATCH", ["licenses", id]) => self.admin(request, |state| state.update_license(id, |license| {
+                let attributes = &request.body["data"]["attributes"];
+                if let Some(name) = attributes.get("name") {
+                    license.name = name.as_str().map(str::to_string);
+                }
+                if let Some(metadata) = attributes.get("metadata") {
+                    license.metadata = metadata.clone();
+                }
+                if let Some(expiry) = attributes.get("expiry") {
+                    license.expiry = parse_date(expiry);
+                }
+            })),
+            ("POST", ["licenses", id, "actions", "suspend"]) => {
+                self.admin(request, |state| state.update_license(id, |license| license.status = "SUSPENDED".to_string()))
+            }
+            ("POST", ["licenses", id, "actions", "reinstate"]) => {
+                self.admin(request, |state| state.update_license(id, |license| license.status = "ACTIVE".to_string()))
+            }
+            ("POST", ["licenses", id, "actions", "renew"]) => {
+                let duration = self.license(id).and_then(|license| license.policy.as_deref()).and_then(|id| self.policy(id)).and_then(|policy| policy.duration);
+                self.admin(request, |state| state.update_license(id, |license| {
+                    if let Some(duration) = duration {
+                        license.expiry = Some(license.expiry.unwrap_or_else(Utc::now) + chrono::Duration::seconds(duration));
+                    }
+                    license.status = "ACTIVE".to_string();
+                }))
+            }
+            ("DELETE", ["licenses", id]) | ("DELETE", ["licenses", id, "actions", "revoke"]) => self.admin(request, |state| state.delete_license(id)),
+            ("GET", ["licenses", id, "machines"]) => self.list_machines(Some(id), request),
+            ("GET", ["licenses", id, "entitlements"]) => match self.license(id) {
+                Some(license) => Reply::page(Self::entitlement_resources(license), request),
+                None => Reply::not_found(),
+            },
+            ("GET", ["licenses", id, "policy"]) => {
+                match self.license(id).and_then(|license| license.policy.as_deref()).and_then(|id| self.policy(id)) {
+                    Some(policy) => Reply::ok(json!({ "data": Self::policy_resource(policy) })),
+                    None => Reply::not_found(),
+                }
+            }
+            ("GET", ["machines"]) => self.list_machines(request.query.get("license").map(String::as_str), request),
+            ("POST", ["machines"]) => self.activate(&request.body["data"]),
+            ("GET", ["machines", id]) => match self.machines.iter().find(|machine| machine.id == *id) {
+                Some(machine) => Reply::ok(json!({ "data": self.machine_resource(machine) })),
+                None => Reply::not_found(),
+            },
+            ("DELETE", ["machines", id]) => {
+                let count = self.machines.len();
+                self.machines.retain(|machine| machine.id != *id);
+                if self.machines.len() < count {
+                    Reply::no_content()
+                } else {
+                    Reply::not_found()
+                }
+            }
+            ("POST", ["machines", id, "actions", "ping"]) => {
+                let Some(index) = self.machines.iter().position(|machine| machine.id == *id) else {
+                    return Reply::not_found();
+                };
+                self.machines[index].heartbeat_status = "ALIVE".to_string();
+                self.machines[index].updated = Utc::now();
+                Reply::ok(json!({ "data": self.machine_resource(&self.machines[index]) }))
+            }
+            ("POST", ["machines", id, "actions", "check-out"]) => self.checkout_machine(id, request),
+            ("GET", ["releases"]) => {
+                let channel = request.query.get("channel");
+                let releases = self
+                    .releases
+                    .iter()
+                    .filter(|release| channel.is_none_or(|channel| release.channel == *channel))
+                    .map(Self::release_resource)
+                    .collect();
+                Reply::page(releases, request)
+            }
+            ("GET", ["releases", version, "upgrade"]) => self.upgrade(version, request),
+            ("GET", ["releases", id, "artifacts"]) => {
+                let matches = |name: &str, value: Option<&str>| request.query.get(name).is_none_or(|filter| Some(filter.as_str()) == value);
+                let artifacts = self
+                    .artifacts
+                    .iter()
+                    .filter(|artifact| artifact.release == *id)
+                    .filter(|artifact| matches("platform", artifact.platform.as_deref()))
+                    .filter(|artifact| matches("arch", artifact.arch.as_deref()))
+                    .map(Self::artifact_resource)
+                    .collect();
+                Reply::page(artifacts, request)
+            }
+            ("GET", ["artifacts", id]) => {
+                let Some(artifact) = self.artifacts.iter().find(|artifact| artifact.id == *id) else {
+                    return Reply::not_found();
+                };
+                // like the API, the download link is somewhere else and needs no credentials
+                let host = request.headers.get("host").cloned().unwrap_or_default();
+                let link = format!("http://{}/downloads/{}", host, artifact.id);
+                if request.headers.get("prefer").is_some_and(|prefer| prefer == "no-redirect") {
+                    let mut resource = Self::artifact_resource(artifact);
+                    resource["links"] = json!({ "redirect": link });
+                    Reply::ok(json!({ "data": resource }))
+                } else {
+                    Reply::redirect(link)
+                }
+            }
+            ("GET", ["downloads", id]) => match self.artifacts.iter().find(|artifact| artifact.id == *id) {
+                Some(artifact) => Reply::download(artifact.content.clone()),
+                None => Reply::not_found(),
+            },
+            _ => Reply::not_found(),
+        }
+    }
+
+    /// Handles a request which requires a token, like the admin operations of the API.
+    fn admin(&mut self, request: &Request, handle: impl FnOnce(&mut Self) -> Reply) -> Reply {
+        let token = request.headers.get("authorization").and_then(|value| value.strip_prefix("Bearer "));
+        match token {
+            Some(_) => handle(self),
+            None => Reply::error(403, "ACCESS_DENIED", "You do not have permission to complete the request"),
+        }
+    }
+
+    fn create_license(&mut self, data: &Value) -> Reply {
+        let attributes = &data["attributes"];
+        let policy = data["relationships"]["policy"]["data"]["id"].as_str().unwrap_or_default();
+        if self.policy(policy).is_none() {
+            return Reply::error(422, "POLICY_NOT_FOUND", "must exist");
+        }
+
+        let now = Utc::now();
+        let license = MockLicense {
+            id: random_id(),
+            key: random_id().to_uppercase(),
+            name: attributes["name"].as_str().map(str::to_string),
+            status: "ACTIVE".to_string(),
+            expiry: parse_date(&attributes["expiry"]),
+            policy: Some(policy.to_string()),
+            owner: data["relationships"]["owner"]["data"]["id"].as_str().map(str::to_string),
+            metadata: attributes.get("metadata").cloned().unwrap_or_else(|| json!({})),
+            entitlements: vec![],
+            created: now,
+            updated: now,
+        };
+        let resource = self.license_resource(&license);
+        self.licenses.push(license);
+        Reply::created(json!({ "data": resource }))
+    }
+
+    fn update_license(&mut self, id: &str, update: impl FnOnce(&mut MockLicense)) -> Reply {
+        let Some(index) = self.licenses.iter().position(|license| license.id == id) else {
+            return Reply::not_found();
+        };
+        update(&mut self.licenses[index]);
+        self.licenses[index].updated = Utc::now();
+        Reply::ok(json!({ "data": self.license_resource(&self.licenses[index]) }))
+    }
+
+    fn delete_license(&mut self, id: &str) -> Reply {
+        let Some(index) = self.licenses.iter().position(|license| license.id == id) else {
+            return Reply::not_found();
+        };
+        let license = self.licenses.remove(index);
+        self.machines.retain(|machine| machine.license != license.id);
+        Reply::no_content()
+    }
+
+    fn me(&self, request: &Request) -> Reply {
+        let key = request.headers.get("authorization").and_then(|value| value.strip_prefix("License "));
+        match key.and_then(|key| self.licenses.iter().find(|license| license.key == key)) {
+            Some(license) => Reply::ok(json!({ "data": self.license_resource(license) })),
+            None => Reply::error(401, "LICENSE_INVALID", "License key is invalid"),
+        }
+    }
+
+    /// Validates a license by id or key, like the API does for the given scope.
+    fn validate(&self, id: &str, scope: &Value) -> Reply {
+        let Some(license) = self.license(id) else {
+            let meta = json!({ "valid": false, "code": "NOT_FOUND", "detail": "does not exist", "scope": scope });
+            return Reply::ok(json!({ "data": null, "meta": meta }));
+        };
+        let (code, detail) = self.validation_code(license, scope);
+        let meta = json!({
+            "ts": Utc::now(),
+            "valid": code == "VALID",
+            "code": code,
+            "detail": detail,
+            "scope": scope,
+        });
+        Reply::ok(json!({ "data": self.license_resource(license), "meta": meta }))
+    }
+
+    fn validation_code(&self, license: &MockLicense, scope: &Value) -> (&'static str, &'static str) {
+        let policy = license.policy.as_deref().and_then(|id| self.policy(id));
+        let machines = self.machines_of(&license.id);
+
+        if license.status == "SUSPENDED" {
+            return ("SUSPENDED", "is suspended");
+        }
+        if license.status == "EXPIRED" || license.expiry.is_some_and(|expiry| expiry < Utc::now()) {
+            return ("EXPIRED", "is expired");
+        }
+        if let Some(max_machines) = policy.and_then(|policy| policy.max_machines) {
+            if machines.len() as i64 > max_machines {
+                return ("TOO_MANY_MACHINES", "has too many associated machines");
+            }
+        }
+        if let Some(fingerprint) = scope["fingerprint"].as_str() {
+            if machines.is_empty() {
+                return ("NO_MACHINES", "fingerprint is not activated (has no associated machines)");
+            }
+            match machines.iter().find(|machine| machine.fingerprint == fingerprint) {
+                None => return ("FINGERPRINT_SCOPE_MISMATCH", "fingerprint is not activated (does not match any associated machines)"),
+                Some(machine) if policy.is_some_and(|policy| policy.require_heartbeat) && machine.heartbeat_status == "NOT_STARTED" => {
+                    return ("HEARTBEAT_NOT_STARTED", "machine heartbeat is required");
+                }
+                Some(_) => {}
+            }
+        }
+        let entitlements = scope["entitlements"].as_array().into_iter().flatten().filter_map(Value::as_str);
+        if entitlements.into_iter().any(|code| !license.entitlements.iter().any(|entitlement| entitlement == code)) {
+            return ("ENTITLEMENTS_MISSING", "is missing one or more required entitlements");
+        }
+        ("VALID", "is valid")
+    }
+
+    /// The machines of a license, filtered like the API by the `fingerprint`, `hostname` and
+    /// `status` of the query, which does not filter by platform.
+    fn list_machines(&self, license: Option<&str>, request: &Request) -> Reply {
+        let license = license.map(|id| self.license(id).map(|license| license.id.as_str()).unwrap_or(id));
+        let matches = |name: &str, value: Option<&str>| request.query.get(name).is_none_or(|filter| Some(filter.as_str()) == value);
+        let machines: Vec<Value> = self
+            .machines
+            .iter()
+            .filter(|machine| license.is_none_or(|license| machine.license == license))
+            .filter(|machine| matches("fingerprint", Some(&machine.fingerprint)))
+            .filter(|machine| matches("hostname", machine.hostname.as_deref()))
+            .filter(|machine| matches("status", Some(&machine.heartbeat_status)))
+            .map(|machine| self.machine_resource(machine))
+            .collect();
+        Reply::page(machines, request)
+    }
+
+    fn activate(&mut self, data: &Value) -> Reply {
+        let attributes = &data["attributes"];
+        let license_id = data["relationships"]["license"]["data"]["id"].as_str().unwrap_or_default();
+        let Some(license) = self.license(license_id).cloned() else {
+            return Reply::error(404, "NOT_FOUND", "The requested license was not found");
+        };
+        let Some(fingerprint) = attributes["fingerprint"].as_str() else {
+            return Reply::error(422, "FINGERPRINT_BLANK", "fingerprint cannot be blank");
+        };
+
+        let machines = self.machines_of(&license.id);
+        if machines.iter().any(|machine| machine.fingerprint == fingerprint) {
+            return Reply::error(422, "FINGERPRINT_TAKEN", "fingerprint has already been taken");
+        }
+        // like the API, a license of a node-locked policy can only be activated on one machine
+        if let Some(policy) = license.policy.as_deref().and_then(|id| self.policy(id)) {
+            let max_machines = if policy.floating { policy.max_machines } else { Some(1) };
+            if max_machines.is_some_and(|max_machines| machines.len() as i64 >= max_machines) {
+                return Reply::error(422, "MACHINE_LIMIT_EXCEEDED", "machine count has exceeded maximum allowed for license");
+            }
+        }
+
+        let now = Utc::now();
+        let machine = MockMachine {
+            id: random_id(),
+            license: license.id,
+            fingerprint: fingerprint.to_string(),
+            name: attributes["name"].as_str().map(str::to_string),
+            platform: attributes["platform"].as_str().map(str::to_string),
+            hostname: attributes["hostname"].as_str().map(str::to_string),
+            cores: attributes["cores"].as_i64(),
+            heartbeat_status: "NOT_STARTED".to_string(),
+            created: now,
+            updated: now,
+        };
+        let resource = self.machine_resource(&machine);
+        self.machines.push(machine);
+        Reply::created(json!({ "data": resource }))
+    }
+
+    /// The resources a checkout includes, e.g. for `include=license.entitlements,license.policy`.
+    fn checkout_included(&self, license: &MockLicense, request: &Request) -> Vec<Value> {
+        let include = request.query.get("include").map(String::as_str).unwrap_or_default();
+        let mut included = vec![];
+        if include.split(',').any(|name| name.ends_with("policy")) {
+            if let Some(policy) = license.policy.as_deref().and_then(|id| self.policy(id)) {
+                included.push(Self::policy_resource(policy));
+            }
+        }
+        if include.split(',').any(|name| name.ends_with("entitlements")) {
+            included.extend(Self::entitlement_resources(license));
+        }
+        included
+    }
+
+    fn checkout_license(&self, id: &str, request: &Request) -> Reply {
+        let Some(license) = self.license(id) else {
+            return Reply::not_found();
+        };
+        let validity = checkout_validity(request);
+        let included = self.checkout_included(license, request);
+        file_reply(
+            "license-files",
+            &validity,
+            self.signer.license_file_with(&license.key, self.license_resource(license), included, &validity),
+        )
+    }
+
+    fn checkout_machine(&self, id: &str, request: &Request) -> Reply {
+        let Some(machine) = self.machines.iter().find(|machine| machine.id == *id) else {
+            return Reply::not_found();
+        };
+        let Some(license) = self.license(&machine.license) else {
+            return Reply::not_found();
+        };
+        let validity = checkout_validity(request);
+        let included = self.checkout_included(license, request);
+        file_reply(
+            "machine-files",
+            &validity,
+            self.signer.machine_file_with(&license.key, self.machine_resource(machine), self.license_resource(license), included, &validity),
+        )
+    }
+
+    /// The newest release of the channel, `stable` by default, which is newer than `current`
+    /// and, like the API, within the major version of the `constraint`, e.g. `1.0` for `1.x`.
+    fn upgrade(&self, current: &str, request: &Request) -> Reply {
+        let Ok(current) = Version::parse(current.trim_start_matches('v')) else {
+            return Reply::error(400, "VERSION_INVALID", "version must be a valid semver");
+        };
+        let constraint = match request.query.get("constraint").map(|constraint| VersionReq::parse(&format!("^{}", constraint))) {
+            Some(Ok(constraint)) => Some(constraint),
+            Some(Err(_)) => return Reply::error(400, "CONSTRAINT_INVALID", "constraint must be a valid semver constraint"),
+            None => None,
+        };
+        let channel = request.query.get("channel").map(String::as_str).unwrap_or("stable");
+        let upgrade = self
+            .releases
+            .iter()
+            .filter(|release| release.channel == channel && release.version > current)
+            .filter(|release| constraint.as_ref().is_none_or(|constraint| constraint.matches(&release.version)))
+            .max_by(|a, b| a.version.cmp(&b.version));
+        match upgrade {
+            Some(release) => Reply::ok(json!({ "data": Self::release_resource(release) })),
+            None => Reply::no_content(),
+        }
+    }
+}
+
+fn parse_date(value: &Value) -> Option<DateTime<Utc>> {
+    value.as_str().and_then(|date| DateTime::parse_from_rfc3339(date).ok()).map(|date| date.with_timezone(&Utc))
+}
+
+/// The validity of a checked out file, for the `ttl` of the checkout.
+fn checkout_validity(request: &Request) -> Validity {
+    Validity::new(None, None, request.query.get("ttl").and_then(|ttl| ttl.parse().ok()))
+}
+
+fn file_reply(kind: &str, validity: &Validity, certificate: Result<String, Error>) -> Reply {
+    match certificate {
+        Ok(certificate) => Reply::ok(json!({
+            "data": {
+                "id": random_id(),
+                "type": kind,
+                "attributes": {
+                    "certificate": certificate,
+                    "issued": validity.issued,
+                    "expiry": validity.expiry,
+                    "ttl": validity.ttl,
+                },
+            },
+        })),
+        Err(e) => Reply::error(500, "INTERNAL_SERVER_ERROR", &e.to_string()),
+    }
+}
+
+/// Reads a request, with the path relative to the account; the API prefix and account are
+/// optional, like in the URLs the client builds for the official API and for other hosts.
+async fn read_request(stream: &mut BufReader<TcpStream>) -> std::io::Result<Option<Request>> {
+    let mut line = String::new();
+    if stream.read_line(&mut line).await? == 0 {
+        return Ok(None);
+    }
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Ok(None);
+    };
+    let (method, target) = (method.to_string(), target.to_string());
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        if stream.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let length = headers.get("content-length").and_then(|length| length.parse().ok()).unwrap_or(0);
+    let mut body = vec![0; length];
+    stream.read_exact(&mut body).await?;
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    let mut path: Vec<String> = path.split('/').filter(|segment| !segment.is_empty()).map(str::to_string).collect();
+    if path.first().is_some_and(|segment| segment.starts_with('v')) {
+        path.remove(0);
+    }
+    if path.first().is_some_and(|segment| segment == "accounts") && path.len() >= 2 {
+        path.drain(..2);
+    }
+    let query = url::form_urlencoded::parse(query.as_bytes()).into_owned().collect();
+
+    Ok(Some(Request {
+        method,
+        target: target.clone(),
+        path,
+        query,
+        headers,
+        body: serde_json::from_slice(&body).unwrap_or(Value::Null),
+    }))
+}
+
+/// Writes a reply, signed like the API signs its responses unless it is tampered with.
+async fn write_reply(stream: &mut BufReader<TcpStream>, request: &Request, reply: Reply, signer: &Signer, tamper: Option<Tamper>) -> std::io::Result<()> {
+    if let Some(content) = reply.download {
+        return write_download(stream, content).await;
+    }
+    let body = reply.body.map(|body| body.to_string()).unwrap_or_default();
+    let date = Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+    let digest = format!("sha-256={}", general_purpose::STANDARD.encode(Sha256::digest(body.as_bytes())));
+    let host = request.headers.get("host").cloned().unwrap_or_default();
+    let signing_data = format!(
+        "(request-target): {} {}\nhost: {}\ndate: {}\ndigest: {}",
+        request.method.to_lowercase(),
+        request.target,
+        host,
+        date,
+        digest
+    );
+    let signature = match tamper {
+        Some(Tamper::Signature) => Signer::generate().sign_bytes(signing_data.as_bytes()),
+        _ => signer.sign_bytes(signing_data.as_bytes()),
+    };
+    let signature = general_purpose::STANDARD.encode(signature);
+    let body = match tamper {
+        Some(Tamper::Body) => body.replacen('{', "{ ", 1),
+        _ => body,
+    };
+
+    let mut head = format!("HTTP/1.1 {} {}\r\n", reply.status, reason(reply.status));
+    let mut headers = vec![
+        ("Content-Type", "application/vnd.api+json".to_string()),
+        ("Content-Length", body.len().to_string()),
+        ("Connection", "close".to_string()),
+        ("Date", date),
+        ("Digest", digest),
+        ("Keygen-Version", API_VERSION.to_string()),
+    ];
+    if tamper != Some(Tamper::Unsigned) {
+        headers.push((
+            "Keygen-Signature",
+            format!(
+                "keyid=\"mock\", algorithm=\"ed25519\", signature=\"{}\", headers=\"(request-target) host date digest\"",
+                signature
+            ),
+        ));
+    }
+    headers.extend(reply.headers);
+    for (name, value) in headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+
+    let stream = stream.get_mut();
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Writes the content of a download, which like the storage the API links to is not signed.
+async fn write_download(stream: &mut BufReader<TcpStream>, content: Vec<u8>) -> std::io::Result<()> {
+    let head = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        content.len()
+    );
+    let stream = stream.get_mut();
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&content).await?;
+    stream.shutdown().await
+}
+
+async fn serve_connection(stream: TcpStream, state: Arc<Mutex<State>>) -> std::io::Result<()> {
+    let mut stream = BufReader::new(stream);
+    let Some(request) = read_request(&mut stream).await? else {
+        return Ok(());
+    };
+    let (reply, signer, tamper) = {
+        let mut state = state.lock().unwrap();
+        let tamper = state.tampering(&request.path.join("/"));
+        (state.handle(&request), state.signer.clone(), tamper)
+    };
+    write_reply(&mut stream, &request, reply, &signer, tamper).await
+}
+
+async fn serve(listener: TcpListener, state: Arc<Mutex<State>>) {
+    while let Ok((stream, _)) = listener.accept().await {
+        let state = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = serve_connection(stream, state).await {
+                log::debug!("mock server connection failed: {}", e);
+            }
+        });
+    }
+}
+
+/// A fake Keygen API on localhost, for tests which exercise the bindings without the network.
+///
+/// It serves validation, machines, activation, deactivation, checkouts, heartbeat pings and
+/// entitlements of the licenses and policies it is seeded with, and signs its responses. With a
+/// token, licenses can also be created, updated, suspended, reinstated, renewed and deleted. It
+/// also serves the releases, upgrades and artifact downloads it is seeded with.
+#[pyclass(frozen, module = "keygen_sh.testing")]
+pub struct MockKeygenServer {
+    state: Arc<Mutex<State>>,
+    address: Mutex<Option<SocketAddr>>,
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl MockKeygenServer {
+    fn abort(&self) {
+        if let Some(task) = self.task.lock().unwrap().take() {
+            task.abort();
+        }
+        *self.address.lock().unwrap() = None;
+    }
+
+    fn url_of(&self) -> PyResult<String> {
+        match *self.address.lock().unwrap() {
+            Some(address) => Ok(format!("http://{}", address)),
+            None => Err(PyRuntimeError::new_err("the mock server is not running")),
+        }
+    }
+}
+
+impl Drop for MockKeygenServer {
+    fn drop(&mut self) {
+        self.abort();
+    }
+}
+
+#[pymethods]
+impl MockKeygenServer {
+    /// Signs responses and files with the given signer, or a new one.
+    #[new]
+    #[pyo3(signature = (signer=None))]
+    fn new(signer: Option<Signer>) -> Self {
+        let state = State {
+            signer: signer.unwrap_or_else(Signer::generate),
+            policies: vec![],
+            licenses: vec![],
+            machines: vec![],
+            releases: vec![],
+            artifacts: vec![],
+            errors: vec![],
+            tamperings: vec![],
+            requests: vec![],
+        };
+        Self {
+            state: Arc::new(Mutex::new(state)),
+            address: Mutex::new(None),
+            task: Mutex::new(None),
+        }
+    }
+
+    /// Starts serving on a free port of localhost, returning the URL of the server.
+    fn start(&self) -> PyResult<String> {
+        if self.address.lock().unwrap().is_some() {
+            return self.url_of();
+        }
+        let runtime = pyo3_async_runtimes::tokio::get_runtime();
+        let listener = std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+            .map_err(|e| PyRuntimeError::new_err(format!("the mock server cannot listen: {}", e)))?;
+        let address = listener.local_addr().map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let listener = {
+            let _guard = runtime.enter();
+            TcpListener::from_std(listener).map_err(|e| PyRuntimeError::new_err(e.to_string()))?
+        };
+
+        *self.task.lock().unwrap() = Some(runtime.spawn(serve(listener, self.state.clone())));
+        *self.address.lock().unwrap() = Some(address);
+        self.url_of()
+    }
+
+    fn stop(&self) {
+        self.abort();
+    }
+
+    fn __enter__(slf: Bound<'_, Self>) -> PyResult<Bound<'_, Self>> {
+        slf.get().start()?;
+        Ok(slf)
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&self, _args: &Bound<'_, PyTuple>) {
+        self.abort();
+    }
+
+    #[getter]
+    fn url(&self) -> PyResult<String> {
+        self.url_of()
+    }
+
+    #[getter]
+    fn signer(&self) -> PyResult<Signer> {
+        Ok(self.state.lock().unwrap().signer.clone())
+    }
+
+    #[getter]
+    fn public_key(&self) -> PyResult<String> {
+        Ok(self.state.lock().unwrap().signer.public_key_hex())
+    }
+
+    /// The method and path of every request the server received, in order.
+    #[getter]
+    fn requests(&self) -> PyResult<Vec<(String, String)>> {
+        Ok(self.state.lock().unwrap().requests.clone())
+    }
+
+    /// A config which talks to the server and trusts its public key.
+    #[pyo3(signature = (license_key=None, product="mock-product"))]
+    fn config<'py>(&self, py: Python<'py>, license_key: Option<String>, product: &str) -> PyResult<Bound<'py, PyAny>> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("api_url", self.url_of()?)?;
+        kwargs.set_item("api_version", API_VERSION)?;
+        kwargs.set_item("api_prefix", "v1")?;
+        kwargs.set_item("account", "mock")?;
+        kwargs.set_item("product", product)?;
+        kwargs.set_item("license_key", license_key)?;
+        kwargs.set_item("public_key", self.public_key()?)?;
+        py.get_type::<KeygenConfig>().call((), Some(&kwargs))
+    }
+
+    /// Adds a policy, returning its id.
+    #[pyo3(signature = (*, id=None, name=None, max_machines=None, floating=false, require_heartbeat=false, heartbeat_duration=None, duration=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn add_policy(
+        &self,
+        id: Option<String>,
+        name: Option<String>,
+        max_machines: Option<i64>,
+        floating: bool,
+        require_heartbeat: bool,
+        heartbeat_duration: Option<i64>,
+        duration: Option<i64>,
+    ) -> PyResult<String> {
+        let policy = MockPolicy {
+            id: id.unwrap_or_else(random_id),
+            name,
+            max_machines,
+            floating,
+            require_heartbeat,
+            heartbeat_duration,
+            duration,
+            created: Utc::now(),
+        };
+        let id = policy.id.clone();
+        let mut state = self.state.lock().unwrap();
+        state.policies.retain(|existing| existing.id != id);
+        state.policies.push(policy);
+        Ok(id)
+    }
+
+    /// Adds a license, or replaces the license with the same id, returning its id.
+    #[pyo3(signature = (key, *, id=None, policy=None, name=None, status="ACTIVE", expiry=None, metadata=None, entitlements=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn add_license(
+        &self,
+        key: String,
+        id: Option<String>,
+        policy: Option<String>,
+        name: Option<String>,
+        status: &str,
+        expiry: Option<Date>,
+        metadata: Option<JsonValue>,
+        entitlements: Option<Vec<String>>,
+    ) -> PyResult<String> {
+        let license = MockLicense {
+            id: id.unwrap_or_else(random_id),
+            key,
+            name,
+            status: status.to_uppercase(),
+            expiry: expiry.map(DateTime::<Utc>::from),
+            policy,
+            owner: None,
+            metadata: metadata.map(|JsonValue(metadata)| metadata).unwrap_or_else(|| json!({})),
+            entitlements: entitlements.unwrap_or_default(),
+            created: Utc::now(),
+            updated: Utc::now(),
+        };
+        let id = license.id.clone();
+        let mut state = self.state.lock().unwrap();
+        state.licenses.retain(|existing| existing.id != id);
+        state.licenses.push(license);
+        Ok(id)
+    }
+
+    /// Adds a machine to the license with the given id, returning the id of the machine.
+    #[pyo3(signature = (license, fingerprint, *, platform=None, hostname=None, heartbeat_status="NOT_STARTED"))]
+    fn add_machine(
+        &self,
+        license: String,
+        fingerprint: String,
+        platform: Option<String>,
+        hostname: Option<String>,
+        heartbeat_status: &str,
+    ) -> PyResult<String> {
+        let mut state = self.state.lock().unwrap();
+        let Some(license) = state.license(&license).map(|license| license.id.clone()) else {
+            return Err(PyValueError::new_err(format!("unknown license: {}", license)));
+        };
+        let now = Utc::now();
+        let machine = MockMachine {
+            id: random_id(),
+            license,
+            fingerprint,
+            name: None,
+            platform,
+            hostname,
+            cores: None,
+            heartbeat_status: heartbeat_status.to_uppercase(),
+            created: now,
+            updated: now,
+        };
+        let id = machine.id.clone();
+        state.machines.push(machine);
+        Ok(id)
+    }
+
+    /// Adds a release, returning its id.
+    #[pyo3(signature = (version, *, id=None, channel="stable"))]
+    fn add_release(&self, version: &str, id: Option<String>, channel: &str) -> PyResult<String> {
+        let version = Version::parse(version.trim_start_matches('v'))
+            .map_err(|e| PyValueError::new_err(format!("invalid version {:?}: {}", version, e)))?;
+        let release = MockRelease { id: id.unwrap_or_else(random_id), version, channel: channel.to_string(), created: Utc::now() };
+        let id = release.id.clone();
+        let mut state = self.state.lock().unwrap();
+        state.releases.retain(|existing| existing.id != id);
+        state.releases.push(release);
+        Ok(id)
+    }
+
+    /// Adds an artifact to the release with the given id, returning the id of the artifact. It
+    /// defaults to the hex-encoded SHA-256 of `content` as checksum and to an Ed25519ph signature by
+    /// the signer of the server, and downloads as `served` instead of `content` when given.
+    #[pyo3(signature = (release, filename, content, *, id=None, platform=None, arch=None, checksum=None, signature=None, served=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn add_artifact(
+        &self,
+        release: String,
+        filename: String,
+        content: Vec<u8>,
+        id: Option<String>,
+        platform: Option<String>,
+        arch: Option<String>,
+        checksum: Option<String>,
+        signature: Option<String>,
+        served: Option<Vec<u8>>,
+    ) -> PyResult<String> {
+        let mut state = self.state.lock().unwrap();
+        if !state.releases.iter().any(|existing| existing.id == release) {
+            return Err(PyValueError::new_err(format!("unknown release: {}", release)));
+        }
+        let artifact = MockArtifact {
+            id: id.unwrap_or_else(random_id),
+            release,
+            filename,
+            platform,
+            arch,
+            checksum: checksum.unwrap_or_else(|| hex::encode(Sha256::digest(&content))),
+            signature: signature.unwrap_or_else(|| general_purpose::STANDARD.encode(state.signer.sign_prehashed(&content))),
+            content: served.unwrap_or(content),
+            created: Utc::now(),
+        };
+        let id = artifact.id.clone();
+        state.artifacts.retain(|existing| existing.id != id);
+        state.artifacts.push(artifact);
+        Ok(id)
+    }
+
+    /// Answers the next `times` requests whose path contains `path` with an API error, e.g.
+    /// `RATE_LIMIT_EXCEEDED`, `LICENSE_EXPIRED` or `LICENSE_SUSPENDED`.
+    #[pyo3(signature = (code, *, status=None, detail=None, path=None, times=1))]
+    fn inject_error(&self, code: String, status: Option<u16>, detail: Option<String>, path: Option<String>, times: u32) {
+        let status = status.unwrap_or(match code.as_str() {
+            "RATE_LIMIT_EXCEEDED" => 429,
+            "NOT_FOUND" => 404,
+            "LICENSE_EXPIRED" | "LICENSE_SUSPENDED" | "LICENSE_NOT_ALLOWED" | "TOKEN_INVALID" | "TOKEN_EXPIRED" => 403,
+            _ => 422,
+        });
+        let detail = detail.unwrap_or_else(|| code.to_lowercase().replace('_', " "));
+        self.state.lock().unwrap().errors.push(InjectedError { status, code, detail, path, remaining: times });
+    }
+
+    /// Breaks the signature of the next `times` responses whose path contains `path`: "unsigned"
+    /// leaves out the `Keygen-Signature` header, "signature" signs with another key and "body"
+    /// alters the body after it was signed.
+    #[pyo3(signature = (tamper, *, path=None, times=1))]
+    fn tamper(&self, tamper: &str, path: Option<String>, times: u32) -> PyResult<()> {
+        let tamper = match tamper {
+            "unsigned" => Tamper::Unsigned,
+            "signature" => Tamper::Signature,
+            "body" => Tamper::Body,
+            _ => return Err(PyValueError::new_err(format!("unknown tampering: {}", tamper))),
+        };
+        self.state.lock().unwrap().tamperings.push(Tampering { tamper, path, remaining: times });
+        Ok(())
+    }
+
+    /// Forgets the machines, injected errors, tamperings and requests, keeping the licenses and
+    /// policies.
+    fn reset(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.machines.clear();
+        state.errors.clear();
+        state.tamperings.clear();
+        state.requests.clear();
+    }
+
+    fn __repr__(&self) -> String {
+        match *self.address.lock().unwrap() {
+            Some(address) => format!("MockKeygenServer(url='http://{}')", address),
+            None => "MockKeygenServer(url=None)".to_string(),
+        }
+    }
+}
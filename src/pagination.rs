@@ -0,0 +1,249 @@
+use crate::artifact::Artifact;
+use crate::client::{Client, Response};
+use crate::entitlement::Entitlement;
+use crate::errors::KeygenError;
+use crate::machine::Machine;
+use crate::group::Group;
+use crate::policy::Policy;
+use crate::product::Product;
+use crate::release::Release;
+use crate::user::User;
+use crate::telemetry::instrument;
+use keygen_rs::errors::Error;
+use pyo3::exceptions::{PyStopAsyncIteration, PyValueError};
+use pyo3::prelude::*;
+use pyo3::{pyclass, pymethods, pymodule, Bound, IntoPyObject, PyResult, Python};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[pymodule(name = "pagination")]
+pub fn pagination_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Hack: workaround for https://github.com/PyO3/pyo3/issues/759
+    Python::attach(|py| {
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("keygen_sh.pagination", m)
+    })?;
+
+    m.add_class::<PageIterator>()?;
+    Ok(())
+}
+
+/// The largest page size the API allows.
+pub(crate) const MAX_PAGE_SIZE: u32 = 100;
+
+/// A resource which can be listed.
+pub(crate) trait ListResource: Sized {
+    /// Builds the resources from an API response whose `data` is a list of resources.
+    fn from_list_response(response: Response) -> Result<Vec<Self>, Error>;
+}
+
+impl ListResource for Machine {
+    fn from_list_response(response: Response) -> Result<Vec<Self>, Error> {
+        Machine::from_list_response(response)
+    }
+}
+
+impl ListResource for Entitlement {
+    fn from_list_response(response: Response) -> Result<Vec<Self>, Error> {
+        Entitlement::from_list_response(response)
+    }
+}
+
+impl ListResource for Policy {
+    fn from_list_response(response: Response) -> Result<Vec<Self>, Error> {
+        Policy::from_list_response(response)
+    }
+}
+
+impl ListResource for Product {
+    fn from_list_response(response: Response) -> Result<Vec<Self>, Error> {
+        Product::from_list_response(response)
+    }
+}
+
+impl ListResource for User {
+    fn from_list_response(response: Response) -> Result<Vec<Self>, Error> {
+        User::from_list_response(response)
+    }
+}
+
+impl ListResource for Group {
+    fn from_list_response(response: Response) -> Result<Vec<Self>, Error> {
+        Group::from_list_response(response)
+    }
+}
+
+impl ListResource for Release {
+    fn from_list_response(response: Response) -> Result<Vec<Self>, Error> {
+        Release::from_list_response(response)
+    }
+}
+
+impl ListResource for Artifact {
+    fn from_list_response(response: Response) -> Result<Vec<Self>, Error> {
+        Artifact::from_list_response(response)
+    }
+}
+
+/// Filters resources on attributes the API cannot filter by, given the raw resource.
+type LocalFilter = Box<dyn Fn(&Value) -> bool + Send + Sync>;
+
+/// Walks the pages of a list endpoint of the API, one request per page.
+pub(crate) struct Pager<T> {
+    path: String,
+    query: Value,
+    page_size: u32,
+    next_page: Option<u32>,
+    local_filter: Option<LocalFilter>,
+    client: Client,
+    resource: PhantomData<T>,
+}
+
+impl<T: ListResource> Pager<T> {
+    pub(crate) fn new(path: String, query: Value, page_size: u32) -> PyResult<Self> {
+        if !(1..=MAX_PAGE_SIZE).contains(&page_size) {
+            return Err(PyValueError::new_err(format!("page_size must be between 1 and {}", MAX_PAGE_SIZE)));
+        }
+        Ok(Self {
+            path,
+            query,
+            page_size,
+            next_page: Some(1),
+            local_filter: None,
+            client: Client::default(),
+            resource: PhantomData,
+        })
+    }
+
+    pub(crate) fn with_local_filter(mut self, filter: impl Fn(&Value) -> bool + Send + Sync + 'static) -> Self {
+        self.local_filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Fetches the pages with the given client, e.g. one for admin operations.
+    pub(crate) fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Fetches the next page, or returns `None` once all pages have been fetched.
+    pub(crate) async fn next(&mut self) -> Result<Option<Vec<T>>, Error> {
+        let Some(page) = self.next_page else {
+            return Ok(None);
+        };
+
+        let mut query = self.query.clone();
+        query["page[number]"] = page.into();
+        query["page[size]"] = self.page_size.into();
+        let mut response = self.client.get(&self.path, Some(&query)).await?;
+
+        // the API links the next page unless this is the last one, without links a short page
+        // is the last one
+        let count = response.body["data"].as_array().map_or(0, Vec::len);
+        let has_next = match response.body["links"].get("next") {
+            Some(next) => !next.is_null(),
+            None => count == self.page_size as usize,
+        };
+        self.next_page = (has_next && count > 0).then_some(page + 1);
+
+        if let (Some(filter), Some(data)) = (&self.local_filter, response.body["data"].as_array_mut()) {
+            data.retain(|resource| filter(resource));
+        }
+        T::from_list_response(response).map(Some)
+    }
+
+    /// Fetches all remaining pages.
+    pub(crate) async fn collect(mut self) -> Result<Vec<T>, Error> {
+        let mut resources = Vec::new();
+        while let Some(page) = self.next().await? {
+            resources.extend(page);
+        }
+        Ok(resources)
+    }
+}
+
+#[derive(IntoPyObject)]
+#[allow(clippy::large_enum_variant)]
+enum Item {
+    Machine(Machine),
+    Entitlement(Entitlement),
+}
+
+enum AnyPager {
+    Machines(Pager<Machine>),
+    Entitlements(Pager<Entitlement>),
+}
+
+impl AnyPager {
+    async fn next(&mut self) -> Result<Option<Vec<Item>>, Error> {
+        Ok(match self {
+            AnyPager::Machines(pager) => pager.next().await?.map(|page| page.into_iter().map(Item::Machine).collect()),
+            AnyPager::Entitlements(pager) => pager.next().await?.map(|page| page.into_iter().map(Item::Entitlement).collect()),
+        })
+    }
+}
+
+#[derive(Default)]
+struct PageState {
+    pager: Option<AnyPager>,
+    buffer: VecDeque<Item>,
+}
+
+/// Iterates over the resources of a list endpoint with `async for`, fetching pages as needed.
+#[pyclass(frozen)]
+pub struct PageIterator {
+    operation: &'static str,
+    license_id: Option<String>,
+    state: Arc<Mutex<PageState>>,
+}
+
+impl PageIterator {
+    fn new(operation: &'static str, license_id: Option<String>, pager: AnyPager) -> Self {
+        Self {
+            operation,
+            license_id,
+            state: Arc::new(Mutex::new(PageState { pager: Some(pager), buffer: VecDeque::new() })),
+        }
+    }
+
+    pub(crate) fn machines(operation: &'static str, license_id: Option<String>, pager: Pager<Machine>) -> Self {
+        Self::new(operation, license_id, AnyPager::Machines(pager))
+    }
+
+    pub(crate) fn entitlements(operation: &'static str, license_id: Option<String>, pager: Pager<Entitlement>) -> Self {
+        Self::new(operation, license_id, AnyPager::Entitlements(pager))
+    }
+}
+
+#[pymethods]
+impl PageIterator {
+    fn __aiter__(slf: Bound<'_, Self>) -> Bound<'_, Self> {
+        slf
+    }
+
+    fn __anext__<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let (operation, license_id) = (self.operation, self.license_id.clone());
+        let state = self.state.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let mut state = state.lock().await;
+            loop {
+                if let Some(item) = state.buffer.pop_front() {
+                    return Ok(item);
+                }
+                let Some(pager) = state.pager.as_mut() else {
+                    return Err(PyStopAsyncIteration::new_err(()));
+                };
+                match instrument(operation, license_id.clone(), None, pager.next()).await {
+                    Ok(Some(items)) => state.buffer.extend(items),
+                    Ok(None) => state.pager = None,
+                    Err(e) => return Err(KeygenError::from_error(e)),
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,414 @@
+use crate::admin;
+use crate::client::{find_included, from_resource, from_resources, Client, Response};
+use crate::date::Date;
+use crate::errors::KeygenError;
+use crate::identity::Identity;
+use crate::json::JsonValue;
+use crate::pagination::{Pager, MAX_PAGE_SIZE};
+use crate::state::JsonState;
+use crate::telemetry::instrument;
+use crate::utils::interface_methods;
+use chrono::{DateTime, Utc};
+use keygen_rs::errors::Error;
+use pyo3::prelude::*;
+use pyo3::{pyclass, pymodule, Bound, PyResult, Python};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+#[pymodule(name = "policy")]
+pub fn policy_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Hack: workaround for https://github.com/PyO3/pyo3/issues/759
+    Python::attach(|py| {
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("keygen_sh.policy", m)
+    })?;
+
+    m.add_class::<Policy>()?;
+    Ok(())
+}
+
+#[pyclass(frozen, module = "keygen_sh.policy")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Policy {
+    pub id: String,
+    pub name: Option<String>,
+    /// Seconds a license of the policy is valid for, or `None` if it does not expire.
+    pub duration: Option<i64>,
+    #[serde(default)]
+    pub strict: bool,
+    #[serde(default)]
+    pub floating: bool,
+    pub scheme: Option<String>,
+    #[serde(default)]
+    pub require_heartbeat: bool,
+    pub heartbeat_duration: Option<i64>,
+    pub max_machines: Option<i64>,
+    pub max_processes: Option<i64>,
+    pub max_cores: Option<i64>,
+    pub max_uses: Option<i64>,
+    pub max_users: Option<i64>,
+    pub fingerprint_uniqueness_strategy: Option<String>,
+    pub fingerprint_matching_strategy: Option<String>,
+    pub expiration_strategy: Option<String>,
+    pub authentication_strategy: Option<String>,
+    #[serde(default)]
+    pub require_check_in: bool,
+    pub check_in_interval: Option<String>,
+    pub check_in_interval_count: Option<i64>,
+    #[serde(default)]
+    pub metadata: HashMap<String, Value>,
+    pub created: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+}
+
+impl Policy {
+    pub(crate) fn from_response(response: Response) -> Result<Policy, Error> {
+        from_resource(&response.body["data"])
+    }
+
+    /// Builds policies from an API response whose `data` is a list of policy resources.
+    pub(crate) fn from_list_response(response: Response) -> Result<Vec<Policy>, Error> {
+        from_resources(&response.body["data"])
+    }
+
+    /// Finds the policy among the resources included in e.g. a license file.
+    pub(crate) fn from_included(included: &Value) -> Option<Policy> {
+        find_included(included, "policies")
+    }
+
+    /// Fetches the policy of the license with the given id.
+    pub(crate) async fn fetch_with(license_id: &str) -> Result<Policy, Error> {
+        Self::from_response(Client::default().get(&format!("licenses/{}/policy", license_id), None).await?)
+    }
+
+    /// The attributes of a policy which are set, skipping the ones left out.
+    #[allow(clippy::too_many_arguments)]
+    fn attributes(
+        name: Option<String>,
+        duration: Option<i64>,
+        strict: Option<bool>,
+        floating: Option<bool>,
+        scheme: Option<String>,
+        require_heartbeat: Option<bool>,
+        heartbeat_duration: Option<i64>,
+        max_machines: Option<i64>,
+        max_processes: Option<i64>,
+        max_cores: Option<i64>,
+        max_uses: Option<i64>,
+        max_users: Option<i64>,
+        fingerprint_uniqueness_strategy: Option<String>,
+        fingerprint_matching_strategy: Option<String>,
+        expiration_strategy: Option<String>,
+        authentication_strategy: Option<String>,
+        require_check_in: Option<bool>,
+        check_in_interval: Option<String>,
+        check_in_interval_count: Option<i64>,
+        metadata: Option<JsonValue>,
+    ) -> Value {
+        admin::attributes(json!({
+            "name": name,
+            "duration": duration,
+            "strict": strict,
+            "floating": floating,
+            "scheme": scheme,
+            "requireHeartbeat": require_heartbeat,
+            "heartbeatDuration": heartbeat_duration,
+            "maxMachines": max_machines,
+            "maxProcesses": max_processes,
+            "maxCores": max_cores,
+            "maxUses": max_uses,
+            "maxUsers": max_users,
+            "fingerprintUniquenessStrategy": fingerprint_uniqueness_strategy,
+            "fingerprintMatchingStrategy": fingerprint_matching_strategy,
+            "expirationStrategy": expiration_strategy,
+            "authenticationStrategy": authentication_strategy,
+            "requireCheckIn": require_check_in,
+            "checkInInterval": check_in_interval,
+            "checkInIntervalCount": check_in_interval_count,
+            "metadata": metadata.map(|JsonValue(metadata)| metadata),
+        }))
+    }
+}
+
+impl JsonState for Policy {
+    fn to_state(&self) -> Result<Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
+
+    fn from_state(state: Value) -> Result<Self, serde_json::Error> {
+        serde_json::from_value(state)
+    }
+}
+
+impl Identity for Policy {
+    fn resource_id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+
+    fn repr_fields(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("id", Some(self.id.clone())),
+            ("name", self.name.clone()),
+            ("duration", self.duration.map(|duration| duration.to_string())),
+            ("max_machines", self.max_machines.map(|max| max.to_string())),
+            ("floating", Some(self.floating.to_string())),
+        ]
+    }
+}
+
+interface_methods! {
+impl Policy {
+    #[getter]
+    fn id(&self) -> PyResult<String> {
+        Ok(self.id.clone())
+    }
+
+    #[getter]
+    fn name(&self) -> PyResult<Option<String>> {
+        Ok(self.name.clone())
+    }
+
+    #[getter]
+    fn duration(&self) -> PyResult<Option<i64>> {
+        Ok(self.duration)
+    }
+
+    #[getter]
+    fn strict(&self) -> PyResult<bool> {
+        Ok(self.strict)
+    }
+
+    #[getter]
+    fn floating(&self) -> PyResult<bool> {
+        Ok(self.floating)
+    }
+
+    #[getter]
+    fn scheme(&self) -> PyResult<Option<String>> {
+        Ok(self.scheme.clone())
+    }
+
+    #[getter]
+    fn require_heartbeat(&self) -> PyResult<bool> {
+        Ok(self.require_heartbeat)
+    }
+
+    #[getter]
+    fn heartbeat_duration(&self) -> PyResult<Option<i64>> {
+        Ok(self.heartbeat_duration)
+    }
+
+    #[getter]
+    fn max_machines(&self) -> PyResult<Option<i64>> {
+        Ok(self.max_machines)
+    }
+
+    #[getter]
+    fn max_processes(&self) -> PyResult<Option<i64>> {
+        Ok(self.max_processes)
+    }
+
+    #[getter]
+    fn max_cores(&self) -> PyResult<Option<i64>> {
+        Ok(self.max_cores)
+    }
+
+    #[getter]
+    fn max_uses(&self) -> PyResult<Option<i64>> {
+        Ok(self.max_uses)
+    }
+
+    #[getter]
+    fn max_users(&self) -> PyResult<Option<i64>> {
+        Ok(self.max_users)
+    }
+
+    #[getter]
+    fn fingerprint_uniqueness_strategy(&self) -> PyResult<Option<String>> {
+        Ok(self.fingerprint_uniqueness_strategy.clone())
+    }
+
+    #[getter]
+    fn fingerprint_matching_strategy(&self) -> PyResult<Option<String>> {
+        Ok(self.fingerprint_matching_strategy.clone())
+    }
+
+    #[getter]
+    fn expiration_strategy(&self) -> PyResult<Option<String>> {
+        Ok(self.expiration_strategy.clone())
+    }
+
+    #[getter]
+    fn authentication_strategy(&self) -> PyResult<Option<String>> {
+        Ok(self.authentication_strategy.clone())
+    }
+
+    #[getter]
+    fn require_check_in(&self) -> PyResult<bool> {
+        Ok(self.require_check_in)
+    }
+
+    #[getter]
+    fn check_in_interval(&self) -> PyResult<Option<String>> {
+        Ok(self.check_in_interval.clone())
+    }
+
+    #[getter]
+    fn check_in_interval_count(&self) -> PyResult<Option<i64>> {
+        Ok(self.check_in_interval_count)
+    }
+
+    #[getter]
+    fn metadata(&self) -> PyResult<JsonValue> {
+        Ok(JsonValue(json!(self.metadata)))
+    }
+
+    #[getter]
+    fn created(&self) -> PyResult<Date> {
+        Ok(Date::from(self.created))
+    }
+
+    #[getter]
+    fn updated(&self) -> PyResult<Date> {
+        Ok(Date::from(self.updated))
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (page_size=MAX_PAGE_SIZE))]
+    fn list(py: Python<'_>, page_size: u32) -> PyResult<Bound<'_, PyAny>> {
+        let pager = Pager::<Policy>::new("policies".to_string(), json!({}), page_size)?.with_client(Client::admin()?);
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("policy.list", None, None, pager.collect()).await;
+            match result {
+                Ok(policies) => Ok(policies),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (name, product_id=None, *, duration=None, strict=None, floating=None, scheme=None, require_heartbeat=None, heartbeat_duration=None, max_machines=None, max_processes=None, max_cores=None, max_uses=None, max_users=None, fingerprint_uniqueness_strategy=None, fingerprint_matching_strategy=None, expiration_strategy=None, authentication_strategy=None, require_check_in=None, check_in_interval=None, check_in_interval_count=None, metadata=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn create(
+        py: Python<'_>,
+        name: String,
+        product_id: Option<String>,
+        duration: Option<i64>,
+        strict: Option<bool>,
+        floating: Option<bool>,
+        scheme: Option<String>,
+        require_heartbeat: Option<bool>,
+        heartbeat_duration: Option<i64>,
+        max_machines: Option<i64>,
+        max_processes: Option<i64>,
+        max_cores: Option<i64>,
+        max_uses: Option<i64>,
+        max_users: Option<i64>,
+        fingerprint_uniqueness_strategy: Option<String>,
+        fingerprint_matching_strategy: Option<String>,
+        expiration_strategy: Option<String>,
+        authentication_strategy: Option<String>,
+        require_check_in: Option<bool>,
+        check_in_interval: Option<String>,
+        check_in_interval_count: Option<i64>,
+        metadata: Option<JsonValue>,
+    ) -> PyResult<Bound<'_, PyAny>> {
+        let client = Client::admin()?;
+        let product_id = product_id.unwrap_or_else(|| keygen_rs::config::get_config().product);
+        let attributes = Policy::attributes(
+            Some(name),
+            duration,
+            strict,
+            floating,
+            scheme,
+            require_heartbeat,
+            heartbeat_duration,
+            max_machines,
+            max_processes,
+            max_cores,
+            max_uses,
+            max_users,
+            fingerprint_uniqueness_strategy,
+            fingerprint_matching_strategy,
+            expiration_strategy,
+            authentication_strategy,
+            require_check_in,
+            check_in_interval,
+            check_in_interval_count,
+            metadata,
+        );
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("policy.create", None, None, admin::create::<Policy>(&client, "policies", attributes, Some(json!({ "product": admin::relationship("products", &product_id) })))).await;
+            match result {
+                Ok(policy) => Ok(policy),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    #[pyo3(signature = (*, name=None, duration=None, strict=None, floating=None, scheme=None, require_heartbeat=None, heartbeat_duration=None, max_machines=None, max_processes=None, max_cores=None, max_uses=None, max_users=None, fingerprint_uniqueness_strategy=None, fingerprint_matching_strategy=None, expiration_strategy=None, authentication_strategy=None, require_check_in=None, check_in_interval=None, check_in_interval_count=None, metadata=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn update<'a>(
+        &'a self,
+        py: Python<'a>,
+        name: Option<String>,
+        duration: Option<i64>,
+        strict: Option<bool>,
+        floating: Option<bool>,
+        scheme: Option<String>,
+        require_heartbeat: Option<bool>,
+        heartbeat_duration: Option<i64>,
+        max_machines: Option<i64>,
+        max_processes: Option<i64>,
+        max_cores: Option<i64>,
+        max_uses: Option<i64>,
+        max_users: Option<i64>,
+        fingerprint_uniqueness_strategy: Option<String>,
+        fingerprint_matching_strategy: Option<String>,
+        expiration_strategy: Option<String>,
+        authentication_strategy: Option<String>,
+        require_check_in: Option<bool>,
+        check_in_interval: Option<String>,
+        check_in_interval_count: Option<i64>,
+        metadata: Option<JsonValue>,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let client = Client::admin()?;
+        let policy_id = self.id.clone();
+        let attributes = Policy::attributes(
+            name,
+            duration,
+            strict,
+            floating,
+            scheme,
+            require_heartbeat,
+            heartbeat_duration,
+            max_machines,
+            max_processes,
+            max_cores,
+            max_uses,
+            max_users,
+            fingerprint_uniqueness_strategy,
+            fingerprint_matching_strategy,
+            expiration_strategy,
+            authentication_strategy,
+            require_check_in,
+            check_in_interval,
+            check_in_interval_count,
+            metadata,
+        );
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("policy.update", None, None, admin::update::<Policy>(&client, "policies", &policy_id, attributes)).await;
+            match result {
+                Ok(policy) => Ok(policy),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+}
+}
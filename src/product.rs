@@ -0,0 +1,212 @@
+use crate::admin;
+use crate::client::{find_included, from_resources, Client, Response};
+use crate::date::Date;
+use crate::errors::KeygenError;
+use crate::identity::Identity;
+use crate::json::JsonValue;
+use crate::pagination::{Pager, MAX_PAGE_SIZE};
+use crate::state::JsonState;
+use crate::telemetry::instrument;
+use crate::utils::interface_methods;
+use chrono::{DateTime, Utc};
+use keygen_rs::errors::Error;
+use pyo3::prelude::*;
+use pyo3::{pyclass, pymodule, Bound, PyResult, Python};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+#[pymodule(name = "product")]
+pub fn product_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Hack: workaround for https://github.com/PyO3/pyo3/issues/759
+    Python::attach(|py| {
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("keygen_sh.product", m)
+    })?;
+
+    m.add_class::<Product>()?;
+    Ok(())
+}
+
+#[pyclass(frozen, module = "keygen_sh.product")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Product {
+    pub id: String,
+    pub name: String,
+    pub code: Option<String>,
+    pub url: Option<String>,
+    pub distribution_strategy: Option<String>,
+    pub platforms: Option<Vec<String>>,
+    #[serde(default)]
+    pub metadata: HashMap<String, Value>,
+    pub created: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+}
+
+impl Product {
+    /// Builds products from an API response whose `data` is a list of product resources.
+    pub(crate) fn from_list_response(response: Response) -> Result<Vec<Product>, Error> {
+        from_resources(&response.body["data"])
+    }
+
+    /// Finds the product among the resources included in e.g. a license file.
+    pub(crate) fn from_included(included: &Value) -> Option<Product> {
+        find_included(included, "products")
+    }
+
+    /// The attributes of a product which are set, skipping the ones left out.
+    fn attributes(
+        name: Option<String>,
+        code: Option<String>,
+        url: Option<String>,
+        distribution_strategy: Option<String>,
+        platforms: Option<Vec<String>>,
+        metadata: Option<JsonValue>,
+    ) -> Value {
+        admin::attributes(json!({
+            "name": name,
+            "code": code,
+            "url": url,
+            "distributionStrategy": distribution_strategy,
+            "platforms": platforms,
+            "metadata": metadata.map(|JsonValue(metadata)| metadata),
+        }))
+    }
+}
+
+impl JsonState for Product {
+    fn to_state(&self) -> Result<Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
+
+    fn from_state(state: Value) -> Result<Self, serde_json::Error> {
+        serde_json::from_value(state)
+    }
+}
+
+impl Identity for Product {
+    fn resource_id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+
+    fn repr_fields(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("id", Some(self.id.clone())),
+            ("name", Some(self.name.clone())),
+            ("code", self.code.clone()),
+        ]
+    }
+}
+
+interface_methods! {
+impl Product {
+    #[getter]
+    fn id(&self) -> PyResult<String> {
+        Ok(self.id.clone())
+    }
+
+    #[getter]
+    fn name(&self) -> PyResult<String> {
+        Ok(self.name.clone())
+    }
+
+    #[getter]
+    fn code(&self) -> PyResult<Option<String>> {
+        Ok(self.code.clone())
+    }
+
+    #[getter]
+    fn url(&self) -> PyResult<Option<String>> {
+        Ok(self.url.clone())
+    }
+
+    #[getter]
+    fn distribution_strategy(&self) -> PyResult<Option<String>> {
+        Ok(self.distribution_strategy.clone())
+    }
+
+    #[getter]
+    fn platforms(&self) -> PyResult<Option<Vec<String>>> {
+        Ok(self.platforms.clone())
+    }
+
+    #[getter]
+    fn metadata(&self) -> PyResult<JsonValue> {
+        Ok(JsonValue(json!(self.metadata)))
+    }
+
+    #[getter]
+    fn created(&self) -> PyResult<Date> {
+        Ok(Date::from(self.created))
+    }
+
+    #[getter]
+    fn updated(&self) -> PyResult<Date> {
+        Ok(Date::from(self.updated))
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (page_size=MAX_PAGE_SIZE))]
+    fn list(py: Python<'_>, page_size: u32) -> PyResult<Bound<'_, PyAny>> {
+        let pager = Pager::<Product>::new("products".to_string(), json!({}), page_size)?.with_client(Client::admin()?);
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("product.list", None, None, pager.collect()).await;
+            match result {
+                Ok(products) => Ok(products),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (name, *, code=None, url=None, distribution_strategy=None, platforms=None, metadata=None))]
+    fn create(
+        py: Python<'_>,
+        name: String,
+        code: Option<String>,
+        url: Option<String>,
+        distribution_strategy: Option<String>,
+        platforms: Option<Vec<String>>,
+        metadata: Option<JsonValue>,
+    ) -> PyResult<Bound<'_, PyAny>> {
+        let client = Client::admin()?;
+        let attributes = Product::attributes(Some(name), code, url, distribution_strategy, platforms, metadata);
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("product.create", None, None, admin::create::<Product>(&client, "products", attributes, None)).await;
+            match result {
+                Ok(product) => Ok(product),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    #[pyo3(signature = (*, name=None, code=None, url=None, distribution_strategy=None, platforms=None, metadata=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn update<'a>(
+        &'a self,
+        py: Python<'a>,
+        name: Option<String>,
+        code: Option<String>,
+        url: Option<String>,
+        distribution_strategy: Option<String>,
+        platforms: Option<Vec<String>>,
+        metadata: Option<JsonValue>,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let client = Client::admin()?;
+        let product_id = self.id.clone();
+        let attributes = Product::attributes(name, code, url, distribution_strategy, platforms, metadata);
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("product.update", None, None, admin::update::<Product>(&client, "products", &product_id, attributes)).await;
+            match result {
+                Ok(product) => Ok(product),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+}
+}
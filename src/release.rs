@@ -0,0 +1,208 @@
+use crate::artifact::Artifact;
+use crate::client::{from_resource, from_resources, Client, Response};
+use crate::date::Date;
+use crate::errors::KeygenError;
+use crate::identity::Identity;
+use crate::json::JsonValue;
+use crate::pagination::{Pager, MAX_PAGE_SIZE};
+use crate::state::JsonState;
+use crate::telemetry::instrument;
+use crate::utils::interface_methods;
+use chrono::{DateTime, Utc};
+use keygen_rs::config::get_config;
+use keygen_rs::errors::Error;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::{pyclass, pymodule, Bound, PyResult, Python};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+#[pymodule(name = "release")]
+pub fn release_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Hack: workaround for https://github.com/PyO3/pyo3/issues/759
+    Python::attach(|py| {
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("keygen_sh.release", m)
+    })?;
+
+    m.add_class::<Release>()?;
+    Ok(())
+}
+
+#[pyclass(frozen, module = "keygen_sh.release")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Release {
+    pub id: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub channel: String,
+    pub status: Option<String>,
+    pub tag: Option<String>,
+    pub version: String,
+    #[serde(default)]
+    pub metadata: HashMap<String, Value>,
+    pub yanked: Option<DateTime<Utc>>,
+    pub created: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+}
+
+impl Release {
+    /// Builds releases from an API response whose `data` is a list of release resources.
+    pub(crate) fn from_list_response(response: Response) -> Result<Vec<Release>, Error> {
+        from_resources(&response.body["data"])
+    }
+
+    /// Fetches the release the product can be upgraded to from `current`, if there is a newer one.
+    async fn upgrade_with(current: Version, constraint: Option<String>, channel: Option<String>) -> Result<Option<Release>, Error> {
+        let query = json!({
+            "product": get_config().product,
+            "constraint": constraint,
+            "channel": channel,
+        });
+        let response = Client::default()
+            .get(&format!("releases/{}/upgrade", current), Some(&query))
+            .await?;
+
+        // the API answers 204 No Content when there is no upgrade
+        let release: Release = match &response.body["data"] {
+            Value::Null => return Ok(None),
+            data => from_resource(data)?,
+        };
+        match Version::parse(&release.version) {
+            Ok(version) if version > current => Ok(Some(release)),
+            Ok(_) => Ok(None),
+            Err(e) => Err(Error::UnexpectedError(format!("Release version {:?} is invalid: {}", release.version, e))),
+        }
+    }
+}
+
+impl JsonState for Release {
+    fn to_state(&self) -> Result<Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
+
+    fn from_state(state: Value) -> Result<Self, serde_json::Error> {
+        serde_json::from_value(state)
+    }
+}
+
+impl Identity for Release {
+    fn resource_id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+
+    fn repr_fields(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("id", Some(self.id.clone())),
+            ("version", Some(self.version.clone())),
+            ("channel", Some(self.channel.clone())),
+        ]
+    }
+}
+
+interface_methods! {
+impl Release {
+    #[getter]
+    fn id(&self) -> PyResult<String> {
+        Ok(self.id.clone())
+    }
+
+    #[getter]
+    fn name(&self) -> PyResult<Option<String>> {
+        Ok(self.name.clone())
+    }
+
+    #[getter]
+    fn description(&self) -> PyResult<Option<String>> {
+        Ok(self.description.clone())
+    }
+
+    #[getter]
+    fn channel(&self) -> PyResult<String> {
+        Ok(self.channel.clone())
+    }
+
+    #[getter]
+    fn status(&self) -> PyResult<Option<String>> {
+        Ok(self.status.clone())
+    }
+
+    #[getter]
+    fn tag(&self) -> PyResult<Option<String>> {
+        Ok(self.tag.clone())
+    }
+
+    #[getter]
+    fn version(&self) -> PyResult<String> {
+        Ok(self.version.clone())
+    }
+
+    #[getter]
+    fn metadata(&self) -> PyResult<JsonValue> {
+        Ok(JsonValue(json!(self.metadata)))
+    }
+
+    #[getter]
+    fn yanked(&self) -> PyResult<Option<Date>> {
+        Ok(self.yanked.map(Date::from))
+    }
+
+    #[getter]
+    fn created(&self) -> PyResult<Date> {
+        Ok(Date::from(self.created))
+    }
+
+    #[getter]
+    fn updated(&self) -> PyResult<Date> {
+        Ok(Date::from(self.updated))
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (channel=None, page_size=MAX_PAGE_SIZE))]
+    fn list(py: Python<'_>, channel: Option<String>, page_size: u32) -> PyResult<Bound<'_, PyAny>> {
+        let query = json!({ "product": get_config().product, "channel": channel });
+        let pager = Pager::<Release>::new("releases".to_string(), query, page_size)?;
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("release.list", None, None, pager.collect()).await;
+            match result {
+                Ok(releases) => Ok(releases),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (current_version, constraint=None, channel=None))]
+    fn upgrade(py: Python<'_>, current_version: String, constraint: Option<String>, channel: Option<String>) -> PyResult<Bound<'_, PyAny>> {
+        let current = Version::parse(current_version.trim_start_matches('v'))
+            .map_err(|e| PyValueError::new_err(format!("invalid version {:?}: {}", current_version, e)))?;
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("release.upgrade", None, None, Release::upgrade_with(current, constraint, channel)).await;
+            match result {
+                Ok(release) => Ok(release),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    #[pyo3(signature = (platform=None, arch=None))]
+    fn artifacts<'a>(&'a self, py: Python<'a>, platform: Option<String>, arch: Option<String>) -> PyResult<Bound<'a, PyAny>> {
+        let query = json!({ "platform": platform, "arch": arch });
+        let pager = Pager::<Artifact>::new(format!("releases/{}/artifacts", self.id), query, MAX_PAGE_SIZE)?;
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("release.artifacts", None, None, pager.collect()).await;
+            match result {
+                Ok(artifacts) => Ok(artifacts),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+}
+}
@@ -0,0 +1,92 @@
+use crate::errors::KeygenError;
+use crate::json::JsonValue;
+use crate::license::SchemeCode;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use serde_json::json;
+
+#[pymodule(name = "signed_key")]
+pub fn signed_key_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Hack: workaround for https://github.com/PyO3/pyo3/issues/759
+    Python::attach(|py| {
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("keygen_sh.signed_key", m)
+    })?;
+
+    m.add_class::<SignedKey>()?;
+    Ok(())
+}
+
+#[pyclass(frozen)]
+#[derive(Debug, Clone)]
+pub struct SignedKey {
+    scheme: SchemeCode,
+    prefix: String,
+    signature: String,
+    public_key: String,
+    data: Vec<u8>,
+}
+
+impl SignedKey {
+    /// Builds a signed key out of an already verified `signed_key`, its decoded dataset
+    /// and the public key its signature matched.
+    pub(crate) fn build(scheme: SchemeCode, signed_key: &str, data: Vec<u8>, public_key: String) -> Self {
+        let (signing_data, signature) = signed_key.split_once('.').unwrap_or((signed_key, ""));
+        let prefix = signing_data.split_once('/').map(|(p, _)| p).unwrap_or_default();
+
+        Self {
+            scheme,
+            prefix: prefix.to_string(),
+            signature: signature.to_string(),
+            public_key,
+            data,
+        }
+    }
+
+    pub(crate) fn utf8(&self) -> PyResult<String> {
+        String::from_utf8(self.data.clone()).map_err(|e| {
+            KeygenError::from_kind("LicenseKeyDataInvalid", json!({ "details": format!("License key data is not valid UTF-8: {}", e) }))
+        })
+    }
+}
+
+#[pymethods]
+impl SignedKey {
+    #[getter]
+    fn scheme(&self) -> PyResult<SchemeCode> {
+        Ok(self.scheme.clone())
+    }
+
+    #[getter]
+    fn prefix(&self) -> PyResult<String> {
+        Ok(self.prefix.clone())
+    }
+
+    #[getter]
+    fn signature(&self) -> PyResult<String> {
+        Ok(self.signature.clone())
+    }
+
+    #[getter]
+    fn public_key(&self) -> PyResult<String> {
+        Ok(self.public_key.clone())
+    }
+
+    #[getter]
+    fn bytes<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyBytes>> {
+        Ok(PyBytes::new(py, &self.data))
+    }
+
+    #[getter]
+    fn data(&self) -> PyResult<String> {
+        self.utf8()
+    }
+
+    fn json(&self) -> PyResult<JsonValue> {
+        match serde_json::from_slice(&self.data) {
+            Ok(value) => Ok(JsonValue(value)),
+            Err(e) => Err(KeygenError::from_kind("LicenseKeyDataInvalid", json!({ "details": format!("License key data is not valid JSON: {}", e) }))),
+        }
+    }
+}
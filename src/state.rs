@@ -0,0 +1,46 @@
+use crate::errors::KeygenError;
+use crate::json::JsonValue;
+use keygen_rs::errors::Error;
+use pyo3::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+/// The state of a pyclass as JSON, which `to_dict`, `to_json` and pickling are built on.
+pub(crate) trait JsonState: Sized {
+    fn to_state(&self) -> Result<Value, serde_json::Error>;
+    fn from_state(state: Value) -> Result<Self, serde_json::Error>;
+}
+
+/// A `keygen_rs` type wrapped by `create_interface!`, whose state is its serde representation
+/// unless it skips fields which are needed to restore it.
+pub(crate) trait InnerState: Serialize + DeserializeOwned {
+    fn to_state(&self) -> Result<Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
+
+    fn from_state(state: Value) -> Result<Self, serde_json::Error> {
+        serde_json::from_value(state)
+    }
+}
+
+/// The state of a pyclass as a Python dict, for state which is not JSON, e.g. callables.
+pub(crate) trait PyState: Sized {
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>>;
+    fn from_dict(dict: &Bound<'_, PyAny>) -> PyResult<Self>;
+}
+
+impl<T: JsonState> PyState for T {
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        JsonValue(self.to_state().map_err(json_error)?).into_pyobject(py)
+    }
+
+    fn from_dict(dict: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let JsonValue(state) = dict.extract()?;
+        Self::from_state(state).map_err(json_error)
+    }
+}
+
+fn json_error(error: serde_json::Error) -> PyErr {
+    KeygenError::from_error(Error::from(error))
+}
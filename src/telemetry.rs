@@ -0,0 +1,385 @@
+use crate::date::Date;
+use crate::errors::error_type;
+use crate::json::JsonValue;
+use crate::logging::redact;
+use chrono::{DateTime, Utc};
+use keygen_rs::errors::Error;
+use pyo3::prelude::*;
+use pyo3::{pyclass, pyfunction, pymethods, pymodule, wrap_pyfunction, Bound, PyResult, Python};
+use serde_json::json;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
+
+#[pymodule(name = "telemetry")]
+pub fn telemetry_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Hack: workaround for https://github.com/PyO3/pyo3/issues/759
+    Python::attach(|py| {
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("keygen_sh.telemetry", m)
+    })?;
+
+    m.add_function(wrap_pyfunction!(set_exporter, m)?)?;
+    m.add_function(wrap_pyfunction!(metrics, m)?)?;
+    m.add_function(wrap_pyfunction!(reset_metrics, m)?)?;
+    m.add_class::<Span>()?;
+    m.add_class::<Histogram>()?;
+    m.add_class::<Metrics>()?;
+    m.add_class::<InMemoryExporter>()?;
+    Ok(())
+}
+
+/// Upper bounds, in seconds, of the buckets of the duration histograms.
+const BOUNDS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+static EXPORTER: RwLock<Option<Arc<Py<PyAny>>>> = RwLock::new(None);
+static COUNTERS: Mutex<BTreeMap<(String, String), u64>> = Mutex::new(BTreeMap::new());
+static HISTOGRAMS: Mutex<BTreeMap<String, Histogram>> = Mutex::new(BTreeMap::new());
+
+tokio::task_local! {
+    static CURRENT: RefCell<SpanState>;
+}
+
+/// What is learned about an operation while it runs.
+#[derive(Debug, Default)]
+struct SpanState {
+    license_id: Option<String>,
+    machine_id: Option<String>,
+    requests: u32,
+    retries: u32,
+}
+
+/// Runs `future` as the operation `operation`, recording a span and metrics once it completes.
+pub(crate) async fn instrument<T, F>(operation: &'static str, license_id: Option<String>, machine_id: Option<String>, future: F) -> Result<T, Error>
+where
+    F: Future<Output = Result<T, Error>>,
+{
+    let start_time = Utc::now();
+    let started = Instant::now();
+    let state = RefCell::new(SpanState { license_id, machine_id, ..Default::default() });
+
+    let (result, state) = CURRENT
+        .scope(state, async {
+            let result = future.await;
+            (result, CURRENT.with(RefCell::take))
+        })
+        .await;
+
+    let span = Span {
+        operation: operation.to_string(),
+        license_id: state.license_id,
+        machine_id: state.machine_id,
+        outcome: match &result {
+            Ok(_) => "OK".to_string(),
+            Err(e) => error_type(e).to_string(),
+        },
+        error: result.as_ref().err().map(|e| redact(&e.to_string())),
+        start_time,
+        duration: started.elapsed().as_secs_f64(),
+        requests: state.requests,
+        retry_count: state.retries,
+    };
+    record(&span);
+    export(span);
+
+    result
+}
+
+/// Records the license the current operation acts on, once it is known.
+pub(crate) fn record_license(id: &str) {
+    let _ = CURRENT.try_with(|state| state.borrow_mut().license_id = Some(id.to_string()));
+}
+
+/// Records the machine the current operation acts on, once it is known.
+pub(crate) fn record_machine(id: &str) {
+    let _ = CURRENT.try_with(|state| state.borrow_mut().machine_id = Some(id.to_string()));
+}
+
+/// Records an HTTP request made by the current operation.
+pub(crate) fn record_request() {
+    let _ = CURRENT.try_with(|state| state.borrow_mut().requests += 1);
+}
+
+/// Records a retry of the current operation, e.g. after refreshing an expired token.
+pub(crate) fn record_retry() {
+    let _ = CURRENT.try_with(|state| state.borrow_mut().retries += 1);
+}
+
+fn record(span: &Span) {
+    *COUNTERS
+        .lock()
+        .unwrap()
+        .entry((span.operation.clone(), span.outcome.clone()))
+        .or_default() += 1;
+
+    HISTOGRAMS
+        .lock()
+        .unwrap()
+        .entry(span.operation.clone())
+        .or_insert_with(Histogram::empty)
+        .observe(span.duration);
+}
+
+fn export(span: Span) {
+    let Some(exporter) = EXPORTER.read().unwrap().clone() else {
+        return;
+    };
+
+    Python::attach(|py| {
+        if let Err(e) = exporter.call1(py, (span,)) {
+            log::warn!("telemetry exporter failed: {}", e);
+        }
+    });
+}
+
+/// Sets a callable which receives every finished `Span`, or removes it with `None`.
+#[pyfunction]
+#[pyo3(signature = (exporter=None))]
+fn set_exporter(exporter: Option<Py<PyAny>>) -> PyResult<()> {
+    *EXPORTER.write().unwrap() = exporter.map(Arc::new);
+    Ok(())
+}
+
+/// Returns a snapshot of the metrics collected in-process.
+#[pyfunction]
+fn metrics() -> PyResult<Metrics> {
+    Ok(Metrics {
+        counters: COUNTERS.lock().unwrap().clone(),
+        histograms: HISTOGRAMS.lock().unwrap().clone(),
+    })
+}
+
+#[pyfunction]
+fn reset_metrics() -> PyResult<()> {
+    COUNTERS.lock().unwrap().clear();
+    HISTOGRAMS.lock().unwrap().clear();
+    Ok(())
+}
+
+/// A finished licensing operation, e.g. `license.validate`.
+#[pyclass(frozen)]
+#[derive(Debug, Clone)]
+pub struct Span {
+    operation: String,
+    license_id: Option<String>,
+    machine_id: Option<String>,
+    outcome: String,
+    error: Option<String>,
+    start_time: DateTime<Utc>,
+    duration: f64,
+    requests: u32,
+    retry_count: u32,
+}
+
+#[pymethods]
+impl Span {
+    #[getter]
+    fn operation(&self) -> PyResult<String> {
+        Ok(self.operation.clone())
+    }
+
+    #[getter]
+    fn license_id(&self) -> PyResult<Option<String>> {
+        Ok(self.license_id.clone())
+    }
+
+    #[getter]
+    fn machine_id(&self) -> PyResult<Option<String>> {
+        Ok(self.machine_id.clone())
+    }
+
+    /// `OK`, or the name of the error the operation failed with, e.g. `LicenseExpired`.
+    #[getter]
+    fn outcome(&self) -> PyResult<String> {
+        Ok(self.outcome.clone())
+    }
+
+    #[getter]
+    fn error(&self) -> PyResult<Option<String>> {
+        Ok(self.error.clone())
+    }
+
+    #[getter]
+    fn start_time(&self) -> PyResult<Date> {
+        Ok(Date::from(self.start_time))
+    }
+
+    /// Duration in seconds.
+    #[getter]
+    fn duration(&self) -> PyResult<f64> {
+        Ok(self.duration)
+    }
+
+    #[getter]
+    fn requests(&self) -> PyResult<u32> {
+        Ok(self.requests)
+    }
+
+    #[getter]
+    fn retry_count(&self) -> PyResult<u32> {
+        Ok(self.retry_count)
+    }
+
+    /// The span as OpenTelemetry attributes.
+    fn attributes(&self) -> PyResult<JsonValue> {
+        let mut attributes = json!({
+            "keygen.operation": self.operation,
+            "keygen.outcome": self.outcome,
+            "keygen.duration": self.duration,
+            "keygen.requests": self.requests,
+            "keygen.retry_count": self.retry_count,
+        });
+        if let Some(license_id) = &self.license_id {
+            attributes["keygen.license.id"] = json!(license_id);
+        }
+        if let Some(machine_id) = &self.machine_id {
+            attributes["keygen.machine.id"] = json!(machine_id);
+        }
+        if let Some(error) = &self.error {
+            attributes["keygen.error"] = json!(error);
+        }
+        Ok(JsonValue(attributes))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Span(operation={:?}, outcome={:?}, duration={:.3})",
+            self.operation, self.outcome, self.duration
+        )
+    }
+}
+
+/// Distribution of the durations, in seconds, of an operation.
+#[pyclass(frozen)]
+#[derive(Debug, Clone)]
+pub struct Histogram {
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+    bucket_counts: Vec<u64>,
+}
+
+impl Histogram {
+    fn empty() -> Self {
+        Self {
+            count: 0,
+            sum: 0.0,
+            min: f64::INFINITY,
+            max: 0.0,
+            bucket_counts: vec![0; BOUNDS.len() + 1],
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        let bucket = BOUNDS.iter().position(|bound| value <= *bound).unwrap_or(BOUNDS.len());
+        self.bucket_counts[bucket] += 1;
+    }
+}
+
+#[pymethods]
+impl Histogram {
+    #[getter]
+    fn count(&self) -> PyResult<u64> {
+        Ok(self.count)
+    }
+
+    #[getter]
+    fn sum(&self) -> PyResult<f64> {
+        Ok(self.sum)
+    }
+
+    #[getter]
+    fn min(&self) -> PyResult<f64> {
+        Ok(self.min)
+    }
+
+    #[getter]
+    fn max(&self) -> PyResult<f64> {
+        Ok(self.max)
+    }
+
+    #[getter]
+    fn bounds(&self) -> PyResult<Vec<f64>> {
+        Ok(BOUNDS.to_vec())
+    }
+
+    /// Counts per bucket; the last bucket counts the values above the last bound.
+    #[getter]
+    fn bucket_counts(&self) -> PyResult<Vec<u64>> {
+        Ok(self.bucket_counts.clone())
+    }
+}
+
+/// Snapshot of the `keygen.calls` counters and `keygen.call.duration` histograms.
+#[pyclass(frozen)]
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    counters: BTreeMap<(String, String), u64>,
+    histograms: BTreeMap<String, Histogram>,
+}
+
+#[pymethods]
+impl Metrics {
+    /// Number of calls by `(operation, outcome)`.
+    #[getter]
+    fn counters(&self) -> PyResult<BTreeMap<(String, String), u64>> {
+        Ok(self.counters.clone())
+    }
+
+    /// Duration histograms by operation.
+    #[getter]
+    fn histograms(&self) -> PyResult<BTreeMap<String, Histogram>> {
+        Ok(self.histograms.clone())
+    }
+
+    /// Number of calls of `operation`, optionally only those with the given outcome.
+    #[pyo3(signature = (operation, outcome=None))]
+    fn count(&self, operation: &str, outcome: Option<&str>) -> PyResult<u64> {
+        Ok(self
+            .counters
+            .iter()
+            .filter(|((op, out), _)| op == operation && outcome.is_none_or(|o| o == out))
+            .map(|(_, count)| count)
+            .sum())
+    }
+}
+
+/// An exporter which keeps the spans in memory, for tests.
+#[pyclass(frozen)]
+#[derive(Debug, Default)]
+pub struct InMemoryExporter {
+    spans: Mutex<Vec<Span>>,
+}
+
+#[pymethods]
+impl InMemoryExporter {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn __call__(&self, span: Span) {
+        self.spans.lock().unwrap().push(span);
+    }
+
+    fn __len__(&self) -> usize {
+        self.spans.lock().unwrap().len()
+    }
+
+    #[getter]
+    fn spans(&self) -> PyResult<Vec<Span>> {
+        Ok(self.spans.lock().unwrap().clone())
+    }
+
+    fn clear(&self) {
+        self.spans.lock().unwrap().clear();
+    }
+}
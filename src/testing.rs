@@ -0,0 +1,341 @@
+use crate::date::Date;
+use crate::errors::KeygenError;
+use crate::json::JsonValue;
+use crate::mock_server::MockKeygenServer;
+use base64::{engine::general_purpose, Engine};
+use chrono::{DateTime, Duration, Utc};
+use ed25519_dalek::{ExpandedSecretKey, PublicKey, SecretKey};
+use keygen_rs::errors::Error;
+use openssl::rand::rand_bytes;
+use openssl::symm::{encrypt_aead, Cipher};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::{pyclass, pymethods, pymodule, Bound, FromPyObject, PyResult, Python};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+#[pymodule(name = "testing")]
+pub fn testing_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Hack: workaround for https://github.com/PyO3/pyo3/issues/759
+    Python::attach(|py| {
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("keygen_sh.testing", m)
+    })?;
+
+    m.add_class::<Signer>()?;
+    m.add_class::<MockKeygenServer>()?;
+    Ok(())
+}
+
+/// The ttl of license and machine files when neither a ttl nor an expiry is given, as for a
+/// checkout without a ttl.
+const DEFAULT_TTL: i64 = 30 * 24 * 60 * 60;
+
+/// Signs keys, license files and machine files like Keygen does for a product, with a key pair
+/// of its own, so that the offline path can be tested without the API.
+#[pyclass(frozen, module = "keygen_sh.testing")]
+pub struct Signer {
+    secret: SecretKey,
+    public: PublicKey,
+}
+
+/// A message to sign, as text or bytes.
+#[derive(FromPyObject)]
+enum Message {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl Message {
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Message::Text(text) => text.as_bytes(),
+            Message::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+impl Signer {
+    /// A signer with a new key pair.
+    pub(crate) fn generate() -> Self {
+        let secret = SecretKey::from_bytes(&random_bytes::<32>()).expect("a secret key is 32 bytes");
+        let public = PublicKey::from(&secret);
+        Self { secret, public }
+    }
+
+    pub(crate) fn public_key_hex(&self) -> String {
+        hex::encode(self.public.as_bytes())
+    }
+
+    pub(crate) fn sign_bytes(&self, message: &[u8]) -> Vec<u8> {
+        ExpandedSecretKey::from(&self.secret)
+            .sign(message, &self.public)
+            .to_bytes()
+            .to_vec()
+    }
+
+    /// Signs the SHA-512 of `content` with Ed25519ph, like `keygen dist` signs artifacts.
+    pub(crate) fn sign_prehashed(&self, content: &[u8]) -> Vec<u8> {
+        let mut prehash: sha2_09::Sha512 = sha2_09::Digest::new();
+        sha2_09::Digest::update(&mut prehash, content);
+        ExpandedSecretKey::from(&self.secret)
+            .sign_prehashed(prehash, &self.public, None)
+            .expect("an Ed25519ph signature without context does not fail")
+            .to_bytes()
+            .to_vec()
+    }
+
+    /// Encrypts and signs a dataset as a certificate of the given kind, e.g. `license`.
+    fn certificate(&self, kind: &str, secret: &str, dataset: &Value) -> Result<String, Error> {
+        let key = Sha256::digest(secret.as_bytes());
+        let iv = random_bytes::<12>();
+        let mut tag = [0u8; 16];
+        let plaintext = serde_json::to_vec(dataset)?;
+        let ciphertext = encrypt_aead(Cipher::aes_256_gcm(), &key, Some(&iv), &[], &plaintext, &mut tag)
+            .map_err(|e| Error::UnexpectedError(format!("Encryption failed: {}", e)))?;
+
+        let enc = [&ciphertext[..], &iv, &tag]
+            .map(|part| general_purpose::STANDARD.encode(part))
+            .join(".");
+        let sig = general_purpose::STANDARD.encode(self.sign_bytes(format!("{}/{}", kind, enc).as_bytes()));
+        let certificate = json!({ "enc": enc, "sig": sig, "alg": "aes-256-gcm+ed25519" });
+        let encoded = general_purpose::STANDARD.encode(serde_json::to_vec(&certificate)?);
+
+        let banner = format!("{} FILE", kind.to_uppercase());
+        let lines: Vec<&str> = encoded
+            .as_bytes()
+            .chunks(76)
+            .map(|line| std::str::from_utf8(line).unwrap_or_default())
+            .collect();
+        Ok(format!("-----BEGIN {}-----\n{}\n-----END {}-----\n", banner, lines.join("\n"), banner))
+    }
+
+    /// Builds a license file for the given license resource.
+    pub(crate) fn license_file_with(&self, license_key: &str, license: Value, included: Vec<Value>, validity: &Validity) -> Result<String, Error> {
+        let dataset = json!({ "meta": validity.meta(), "data": license, "included": included });
+        self.certificate("license", license_key, &dataset)
+    }
+
+    /// Builds a machine file for the given machine resource, which includes the license resource.
+    pub(crate) fn machine_file_with(
+        &self,
+        license_key: &str,
+        machine: Value,
+        license: Value,
+        mut included: Vec<Value>,
+        validity: &Validity,
+    ) -> Result<String, Error> {
+        let secret = format!("{}{}", license_key, machine["attributes"]["fingerprint"].as_str().unwrap_or_default());
+        included.insert(0, license);
+        let dataset = json!({ "meta": validity.meta(), "data": machine, "included": included });
+        self.certificate("machine", &secret, &dataset)
+    }
+}
+
+impl Clone for Signer {
+    fn clone(&self) -> Self {
+        let secret = SecretKey::from_bytes(self.secret.as_bytes()).expect("a secret key is 32 bytes");
+        Self { secret, public: self.public }
+    }
+}
+
+/// Random bytes from the operating system.
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    rand_bytes(&mut bytes).expect("the operating system provides random bytes");
+    bytes
+}
+
+/// A random id in the format of the ids of Keygen resources, a UUID v4.
+pub(crate) fn random_id() -> String {
+    let mut bytes = random_bytes::<16>();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+/// The attributes of a resource, `overrides` replacing the defaults.
+fn merge(mut defaults: Value, overrides: Option<JsonValue>) -> PyResult<Value> {
+    match overrides.map(|JsonValue(overrides)| overrides) {
+        None => Ok(defaults),
+        Some(Value::Object(overrides)) => {
+            if let Value::Object(defaults) = &mut defaults {
+                defaults.extend(overrides);
+            }
+            Ok(defaults)
+        }
+        Some(_) => Err(PyValueError::new_err("attributes must be a dict")),
+    }
+}
+
+/// When a license or machine file is issued and expires, and its ttl in seconds.
+pub(crate) struct Validity {
+    pub issued: DateTime<Utc>,
+    pub expiry: DateTime<Utc>,
+    pub ttl: i64,
+}
+
+impl Validity {
+    /// Defaults to a file issued now, and a ttl of the time between the two dates.
+    pub(crate) fn new(issued: Option<Date>, expiry: Option<Date>, ttl: Option<i64>) -> Self {
+        let issued = issued.map(DateTime::<Utc>::from).unwrap_or_else(Utc::now);
+        let (expiry, ttl) = match (expiry.map(DateTime::<Utc>::from), ttl) {
+            (Some(expiry), Some(ttl)) => (expiry, ttl),
+            (Some(expiry), None) => (expiry, (expiry - issued).num_seconds()),
+            (None, ttl) => {
+                let ttl = ttl.unwrap_or(DEFAULT_TTL);
+                (issued + Duration::seconds(ttl), ttl)
+            }
+        };
+        Self { issued, expiry, ttl }
+    }
+
+    fn meta(&self) -> Value {
+        json!({ "issued": self.issued, "expiry": self.expiry, "ttl": self.ttl })
+    }
+}
+
+/// A license resource with the given key, as included in license and machine files.
+fn license_resource(license_key: &str, license_id: Option<String>, attributes: Option<JsonValue>, now: DateTime<Utc>) -> PyResult<Value> {
+    let attributes = merge(
+        json!({
+            "key": license_key,
+            "name": null,
+            "expiry": null,
+            "status": "ACTIVE",
+            "metadata": {},
+            "created": now,
+            "updated": now,
+        }),
+        attributes,
+    )?;
+    Ok(json!({
+        "id": license_id.unwrap_or_else(random_id),
+        "type": "licenses",
+        "attributes": attributes,
+        "relationships": {},
+    }))
+}
+
+fn included_resources(included: Option<JsonValue>) -> PyResult<Vec<Value>> {
+    match included.map(|JsonValue(included)| included) {
+        None => Ok(vec![]),
+        Some(Value::Array(included)) => Ok(included),
+        Some(_) => Err(PyValueError::new_err("included must be a list")),
+    }
+}
+
+#[pymethods]
+impl Signer {
+    /// Uses the given hex-encoded Ed25519 private key, or generates a new key pair.
+    #[new]
+    #[pyo3(signature = (private_key=None))]
+    fn new(private_key: Option<String>) -> PyResult<Self> {
+        let Some(private_key) = private_key else {
+            return Ok(Self::generate());
+        };
+        let bytes = hex::decode(&private_key).map_err(|_| PyValueError::new_err("private key must be hex-encoded"))?;
+        let secret = SecretKey::from_bytes(&bytes).map_err(|_| PyValueError::new_err("private key must be 32 bytes"))?;
+        let public = PublicKey::from(&secret);
+        Ok(Self { secret, public })
+    }
+
+    /// The hex-encoded public key, to use as the `public_key` of the config.
+    #[getter]
+    fn public_key(&self) -> PyResult<String> {
+        Ok(self.public_key_hex())
+    }
+
+    #[getter]
+    fn private_key(&self) -> PyResult<String> {
+        Ok(hex::encode(self.secret.as_bytes()))
+    }
+
+    /// Signs a message, returning the base64-encoded Ed25519 signature.
+    fn sign(&self, message: Message) -> PyResult<String> {
+        Ok(general_purpose::STANDARD.encode(self.sign_bytes(message.as_bytes())))
+    }
+
+    /// Signs an artifact with Ed25519ph, returning the base64-encoded signature.
+    fn sign_artifact(&self, content: Message) -> PyResult<String> {
+        Ok(general_purpose::STANDARD.encode(self.sign_prehashed(content.as_bytes())))
+    }
+
+    /// Signs a license key with the ED25519_SIGN scheme, embedding `data`.
+    fn sign_key(&self, data: Message) -> PyResult<String> {
+        let signing_data = format!("key/{}", general_purpose::URL_SAFE.encode(data.as_bytes()));
+        let signature = general_purpose::URL_SAFE.encode(self.sign_bytes(signing_data.as_bytes()));
+        Ok(format!("{}.{}", signing_data, signature))
+    }
+
+    /// Builds a license file for the license with the given key, encrypted with the key.
+    #[pyo3(signature = (license_key, *, license_id=None, attributes=None, included=None, issued=None, expiry=None, ttl=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn license_file(
+        &self,
+        license_key: String,
+        license_id: Option<String>,
+        attributes: Option<JsonValue>,
+        included: Option<JsonValue>,
+        issued: Option<Date>,
+        expiry: Option<Date>,
+        ttl: Option<i64>,
+    ) -> PyResult<String> {
+        let validity = Validity::new(issued, expiry, ttl);
+        let license = license_resource(&license_key, license_id, attributes, validity.issued)?;
+        self.license_file_with(&license_key, license, included_resources(included)?, &validity)
+            .map_err(KeygenError::from_error)
+    }
+
+    /// Builds a machine file for the machine with the given fingerprint, encrypted like Keygen
+    /// does with the license key followed by the fingerprint.
+    #[pyo3(signature = (license_key, fingerprint, *, machine_id=None, attributes=None, license_id=None, license_attributes=None, included=None, issued=None, expiry=None, ttl=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn machine_file(
+        &self,
+        license_key: String,
+        fingerprint: String,
+        machine_id: Option<String>,
+        attributes: Option<JsonValue>,
+        license_id: Option<String>,
+        license_attributes: Option<JsonValue>,
+        included: Option<JsonValue>,
+        issued: Option<Date>,
+        expiry: Option<Date>,
+        ttl: Option<i64>,
+    ) -> PyResult<String> {
+        let validity = Validity::new(issued, expiry, ttl);
+        let license = license_resource(&license_key, license_id, license_attributes, validity.issued)?;
+        let attributes = merge(
+            json!({
+                "fingerprint": fingerprint,
+                "name": null,
+                "platform": null,
+                "hostname": null,
+                "cores": null,
+                "requireHeartbeat": false,
+                "heartbeatStatus": "NOT_STARTED",
+                "heartbeatDuration": null,
+                "created": validity.issued,
+                "updated": validity.issued,
+            }),
+            attributes,
+        )?;
+        let machine = json!({
+            "id": machine_id.unwrap_or_else(random_id),
+            "type": "machines",
+            "attributes": attributes,
+            "relationships": {
+                "license": { "data": { "type": "licenses", "id": license["id"] } },
+            },
+        });
+        self.machine_file_with(&license_key, machine, license, included_resources(included)?, &validity)
+            .map_err(KeygenError::from_error)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Signer(public_key='{}')", self.public_key_hex())
+    }
+}
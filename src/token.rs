@@ -0,0 +1,204 @@
+use crate::admin;
+use crate::client::{from_resource, Client};
+use crate::date::Date;
+use crate::errors::KeygenError;
+use crate::identity::{redact_secret, Identity};
+use crate::state::JsonState;
+use crate::telemetry::instrument;
+use crate::utils::interface_methods;
+use chrono::{DateTime, Utc};
+use keygen_rs::errors::Error;
+use pyo3::prelude::*;
+use pyo3::{pyclass, pymodule, Bound, PyResult, Python};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+#[pymodule(name = "token")]
+pub fn token_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Hack: workaround for https://github.com/PyO3/pyo3/issues/759
+    Python::attach(|py| {
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("keygen_sh.token", m)
+    })?;
+
+    m.add_class::<Token>()?;
+    Ok(())
+}
+
+#[pyclass(frozen, module = "keygen_sh.token")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Token {
+    pub id: String,
+    pub kind: String,
+    /// The secret itself, which the API only returns when the token is generated or regenerated.
+    pub token: Option<String>,
+    pub name: Option<String>,
+    pub expiry: Option<DateTime<Utc>>,
+    pub permissions: Option<Vec<String>>,
+    pub max_activations: Option<i64>,
+    pub activations: Option<i64>,
+    pub max_deactivations: Option<i64>,
+    pub deactivations: Option<i64>,
+    pub created: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+}
+
+impl Token {
+    /// Generates an activation token for the license with the given id.
+    pub(crate) async fn generate_with(client: &Client, license_id: &str, attributes: Value) -> Result<Token, Error> {
+        let params = json!({
+          "data": {
+            "type": "tokens",
+            "attributes": attributes,
+          }
+        });
+        let response = client.post(&format!("licenses/{}/tokens", license_id), Some(&params), None).await?;
+        from_resource(&response.body["data"])
+    }
+
+    /// The attributes of a license token which are set, skipping the ones left out.
+    pub(crate) fn attributes(expiry: Option<Date>, max_activations: Option<i64>, max_deactivations: Option<i64>) -> Value {
+        admin::attributes(json!({
+            "expiry": expiry.map(|expiry| DateTime::<Utc>::from(expiry).to_rfc3339()),
+            "maxActivations": max_activations,
+            "maxDeactivations": max_deactivations,
+        }))
+    }
+}
+
+impl JsonState for Token {
+    fn to_state(&self) -> Result<Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
+
+    fn from_state(state: Value) -> Result<Self, serde_json::Error> {
+        serde_json::from_value(state)
+    }
+}
+
+impl Identity for Token {
+    fn resource_id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+
+    fn repr_fields(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("id", Some(self.id.clone())),
+            ("kind", Some(self.kind.clone())),
+            ("token", self.token.as_deref().map(redact_secret)),
+        ]
+    }
+}
+
+interface_methods! {
+impl Token {
+    #[getter]
+    fn id(&self) -> PyResult<String> {
+        Ok(self.id.clone())
+    }
+
+    #[getter]
+    fn kind(&self) -> PyResult<String> {
+        Ok(self.kind.clone())
+    }
+
+    #[getter]
+    fn token(&self) -> PyResult<Option<String>> {
+        Ok(self.token.clone())
+    }
+
+    #[getter]
+    fn name(&self) -> PyResult<Option<String>> {
+        Ok(self.name.clone())
+    }
+
+    #[getter]
+    fn expiry(&self) -> PyResult<Option<Date>> {
+        Ok(self.expiry.map(Date::from))
+    }
+
+    #[getter]
+    fn permissions(&self) -> PyResult<Option<Vec<String>>> {
+        Ok(self.permissions.clone())
+    }
+
+    #[getter]
+    fn max_activations(&self) -> PyResult<Option<i64>> {
+        Ok(self.max_activations)
+    }
+
+    #[getter]
+    fn activations(&self) -> PyResult<Option<i64>> {
+        Ok(self.activations)
+    }
+
+    #[getter]
+    fn max_deactivations(&self) -> PyResult<Option<i64>> {
+        Ok(self.max_deactivations)
+    }
+
+    #[getter]
+    fn deactivations(&self) -> PyResult<Option<i64>> {
+        Ok(self.deactivations)
+    }
+
+    #[getter]
+    fn created(&self) -> PyResult<Date> {
+        Ok(Date::from(self.created))
+    }
+
+    #[getter]
+    fn updated(&self) -> PyResult<Date> {
+        Ok(Date::from(self.updated))
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (email, password, expiry=None))]
+    fn login(py: Python<'_>, email: String, password: String, expiry: Option<Date>) -> PyResult<Bound<'_, PyAny>> {
+        let client = Client::login(email, password);
+        let attributes = admin::attributes(json!({
+            "expiry": expiry.map(|expiry| DateTime::<Utc>::from(expiry).to_rfc3339()),
+        }));
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("token.login", None, None, admin::create::<Token>(&client, "tokens", attributes, None)).await;
+            match result {
+                Ok(token) => Ok(token),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    fn regenerate<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let client = Client::admin()?;
+        let token_id = self.id.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("token.regenerate", None, None, async {
+                let response = client.put(&format!("tokens/{}", token_id), None).await?;
+                from_resource::<Token>(&response.body["data"])
+            })
+            .await;
+            match result {
+                Ok(token) => Ok(token),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    fn revoke<'a>(&'a self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let client = Client::admin()?;
+        let token_id = self.id.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("token.revoke", None, None, client.delete(&format!("tokens/{}", token_id))).await;
+            match result {
+                Ok(_) => Ok(()),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+}
+}
@@ -0,0 +1,218 @@
+use crate::admin;
+use crate::client::{find_included, from_resources, Client, Response};
+use crate::date::Date;
+use crate::errors::KeygenError;
+use crate::identity::Identity;
+use crate::json::JsonValue;
+use crate::pagination::{Pager, MAX_PAGE_SIZE};
+use crate::state::JsonState;
+use crate::telemetry::instrument;
+use crate::utils::interface_methods;
+use chrono::{DateTime, Utc};
+use keygen_rs::errors::Error;
+use pyo3::prelude::*;
+use pyo3::{pyclass, pymodule, Bound, PyResult, Python};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+#[pymodule(name = "user")]
+pub fn user_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Hack: workaround for https://github.com/PyO3/pyo3/issues/759
+    Python::attach(|py| {
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("keygen_sh.user", m)
+    })?;
+
+    m.add_class::<User>()?;
+    Ok(())
+}
+
+#[pyclass(frozen, module = "keygen_sh.user")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    pub id: String,
+    pub email: String,
+    pub full_name: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub status: Option<String>,
+    pub role: Option<String>,
+    #[serde(default)]
+    pub metadata: HashMap<String, Value>,
+    pub created: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+}
+
+impl User {
+    /// Builds users from an API response whose `data` is a list of user resources.
+    pub(crate) fn from_list_response(response: Response) -> Result<Vec<User>, Error> {
+        from_resources(&response.body["data"])
+    }
+
+    /// Finds the user among the resources included in e.g. a license file.
+    pub(crate) fn from_included(included: &Value) -> Option<User> {
+        find_included(included, "users")
+    }
+
+    /// The attributes of a user which are set, skipping the ones left out.
+    fn attributes(
+        email: Option<String>,
+        first_name: Option<String>,
+        last_name: Option<String>,
+        password: Option<String>,
+        role: Option<String>,
+        metadata: Option<JsonValue>,
+    ) -> Value {
+        admin::attributes(json!({
+            "email": email,
+            "firstName": first_name,
+            "lastName": last_name,
+            "password": password,
+            "role": role,
+            "metadata": metadata.map(|JsonValue(metadata)| metadata),
+        }))
+    }
+}
+
+impl JsonState for User {
+    fn to_state(&self) -> Result<Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
+
+    fn from_state(state: Value) -> Result<Self, serde_json::Error> {
+        serde_json::from_value(state)
+    }
+}
+
+impl Identity for User {
+    fn resource_id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+
+    fn repr_fields(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("id", Some(self.id.clone())),
+            ("email", Some(self.email.clone())),
+            ("status", self.status.clone()),
+        ]
+    }
+}
+
+interface_methods! {
+impl User {
+    #[getter]
+    fn id(&self) -> PyResult<String> {
+        Ok(self.id.clone())
+    }
+
+    #[getter]
+    fn email(&self) -> PyResult<String> {
+        Ok(self.email.clone())
+    }
+
+    #[getter]
+    fn full_name(&self) -> PyResult<Option<String>> {
+        Ok(self.full_name.clone())
+    }
+
+    #[getter]
+    fn first_name(&self) -> PyResult<Option<String>> {
+        Ok(self.first_name.clone())
+    }
+
+    #[getter]
+    fn last_name(&self) -> PyResult<Option<String>> {
+        Ok(self.last_name.clone())
+    }
+
+    #[getter]
+    fn status(&self) -> PyResult<Option<String>> {
+        Ok(self.status.clone())
+    }
+
+    #[getter]
+    fn role(&self) -> PyResult<Option<String>> {
+        Ok(self.role.clone())
+    }
+
+    #[getter]
+    fn metadata(&self) -> PyResult<JsonValue> {
+        Ok(JsonValue(json!(self.metadata)))
+    }
+
+    #[getter]
+    fn created(&self) -> PyResult<Date> {
+        Ok(Date::from(self.created))
+    }
+
+    #[getter]
+    fn updated(&self) -> PyResult<Date> {
+        Ok(Date::from(self.updated))
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (page_size=MAX_PAGE_SIZE))]
+    fn list(py: Python<'_>, page_size: u32) -> PyResult<Bound<'_, PyAny>> {
+        let pager = Pager::<User>::new("users".to_string(), json!({}), page_size)?.with_client(Client::admin()?);
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("user.list", None, None, pager.collect()).await;
+            match result {
+                Ok(users) => Ok(users),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (email, *, first_name=None, last_name=None, password=None, role=None, metadata=None))]
+    fn create(
+        py: Python<'_>,
+        email: String,
+        first_name: Option<String>,
+        last_name: Option<String>,
+        password: Option<String>,
+        role: Option<String>,
+        metadata: Option<JsonValue>,
+    ) -> PyResult<Bound<'_, PyAny>> {
+        let client = Client::admin()?;
+        let attributes = User::attributes(Some(email), first_name, last_name, password, role, metadata);
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("user.create", None, None, admin::create::<User>(&client, "users", attributes, None)).await;
+            match result {
+                Ok(user) => Ok(user),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+
+    #[pyo3(signature = (*, email=None, first_name=None, last_name=None, password=None, role=None, metadata=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn update<'a>(
+        &'a self,
+        py: Python<'a>,
+        email: Option<String>,
+        first_name: Option<String>,
+        last_name: Option<String>,
+        password: Option<String>,
+        role: Option<String>,
+        metadata: Option<JsonValue>,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let client = Client::admin()?;
+        let user_id = self.id.clone();
+        let attributes = User::attributes(email, first_name, last_name, password, role, metadata);
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = instrument("user.update", None, None, admin::update::<User>(&client, "users", &user_id, attributes)).await;
+            match result {
+                Ok(user) => Ok(user),
+                Err(e) => Err(KeygenError::from_error(e)),
+            }
+        })
+    }
+}
+}
@@ -11,35 +11,136 @@ pub(crate) fn pylist_to_string_slice(pylist: Bound<PyList>) -> PyResult<Vec<Stri
 }
 
 macro_rules! create_interface {
-    ($name: ident, $type: ident) => {
-        #[pyclass(frozen)]
+    ($name: ident, $type: ident, module = $module: literal $(, $field: ident: $field_type: ty)*) => {
+        #[pyclass(frozen, module = $module)]
         #[derive(Debug, Clone)]
         pub struct $name {
             inner: $type,
+            $($field: $field_type,)*
         }
 
         impl $name {
             pub(crate) fn from(origin: $type) -> Self {
                 Self {
                     inner: origin,
+                    $($field: Default::default(),)*
                 }
             }
+
+            #[allow(dead_code)]
+            pub(crate) fn inner(&self) -> &$type {
+                &self.inner
+            }
+        }
+
+        impl crate::state::JsonState for $name {
+            fn to_state(&self) -> Result<serde_json::Value, serde_json::Error> {
+                #[allow(unused_mut)]
+                let mut state = crate::state::InnerState::to_state(&self.inner)?;
+                $(state[stringify!($field)] = serde_json::to_value(&self.$field)?;)*
+                Ok(state)
+            }
+
+            fn from_state(state: serde_json::Value) -> Result<Self, serde_json::Error> {
+                Ok(Self {
+                    $($field: match state.get(stringify!($field)) {
+                        Some(value) => serde_json::from_value(value.clone())?,
+                        None => Default::default(),
+                    },)*
+                    inner: crate::state::InnerState::from_state(state)?,
+                })
+            }
+        }
+    };
+}
+
+/// The `#[pymethods]` of a type which implements `PyState` and `Identity`, with the methods every
+/// such type has.
+macro_rules! interface_methods {
+    (impl $name: ident { $($methods: tt)* }) => {
+        #[pyo3::pymethods]
+        impl $name {
+            $($methods)*
+
+            /// The state of the object as a dict, which `from_dict` restores it from.
+            fn to_dict<'py>(&self, py: pyo3::Python<'py>) -> pyo3::PyResult<pyo3::Bound<'py, pyo3::PyAny>> {
+                crate::state::PyState::to_dict(self, py)
+            }
+
+            #[classmethod]
+            fn from_dict(
+                _cls: &pyo3::Bound<'_, pyo3::types::PyType>,
+                dict: &pyo3::Bound<'_, pyo3::PyAny>,
+            ) -> pyo3::PyResult<Self> {
+                crate::state::PyState::from_dict(dict)
+            }
+
+            fn to_json(&self, py: pyo3::Python<'_>) -> pyo3::PyResult<String> {
+                let dict = self.to_dict(py)?;
+                py.import("json")?.call_method1("dumps", (dict,))?.extract()
+            }
+
+            #[classmethod]
+            fn from_json(cls: &pyo3::Bound<'_, pyo3::types::PyType>, json: &str) -> pyo3::PyResult<Self> {
+                let dict = cls.py().import("json")?.call_method1("loads", (json,))?;
+                Self::from_dict(cls, &dict)
+            }
+
+            fn __repr__(&self) -> String {
+                crate::identity::repr(stringify!($name), crate::identity::Identity::repr_fields(self))
+            }
+
+            fn __eq__(slf: &pyo3::Bound<'_, Self>, other: &pyo3::Bound<'_, pyo3::PyAny>) -> pyo3::PyResult<pyo3::Py<pyo3::PyAny>> {
+                let py = slf.py();
+                let Ok(other) = other.cast::<Self>() else {
+                    return Ok(py.NotImplemented());
+                };
+                let equal = match (
+                    crate::identity::Identity::resource_id(&*slf.borrow()),
+                    crate::identity::Identity::resource_id(&*other.borrow()),
+                ) {
+                    (Some(id), Some(other_id)) => id == other_id,
+                    _ => slf.is(other),
+                };
+                Ok(pyo3::types::PyBool::new(py, equal).to_owned().into_any().unbind())
+            }
+
+            fn __hash__(slf: &pyo3::Bound<'_, Self>) -> u64 {
+                use std::hash::{Hash, Hasher};
+
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                match crate::identity::Identity::resource_id(&*slf.borrow()) {
+                    Some(id) => id.hash(&mut hasher),
+                    None => slf.as_ptr().hash(&mut hasher),
+                }
+                hasher.finish()
+            }
+
+            #[allow(clippy::type_complexity)]
+            fn __reduce__<'py>(
+                slf: &pyo3::Bound<'py, Self>,
+            ) -> pyo3::PyResult<(pyo3::Bound<'py, pyo3::PyAny>, (pyo3::Bound<'py, pyo3::PyAny>,))> {
+                let dict = crate::state::PyState::to_dict(&*slf.borrow(), slf.py())?;
+                Ok((slf.get_type().getattr("from_dict")?, (dict,)))
+            }
         }
     };
 }
 
 macro_rules! create_interface_no_clone {
-    ($name: ident, $type: ident) => {
+    ($name: ident, $type: ident $(, $field: ident: $field_type: ty)*) => {
         #[pyclass(frozen)]
         #[derive(Debug)]
         pub struct $name {
             inner: $type,
+            $($field: $field_type,)*
         }
 
         impl $name {
             pub(crate) fn from(origin: $type) -> Self {
                 Self {
                     inner: origin,
+                    $($field: Default::default(),)*
                 }
             }
         }
@@ -47,4 +148,5 @@ macro_rules! create_interface_no_clone {
 }
 
 pub(crate) use create_interface;
+pub(crate) use interface_methods;
 pub(crate) use create_interface_no_clone;
\ No newline at end of file
@@ -0,0 +1,68 @@
+use pyo3::prelude::*;
+use pyo3::{pyclass, pymethods, pymodule, Bound, PyResult, Python};
+use serde::{Deserialize, Serialize};
+
+#[pymodule(name = "verification")]
+pub fn verification_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Hack: workaround for https://github.com/PyO3/pyo3/issues/759
+    Python::attach(|py| {
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("keygen_sh.verification", m)
+    })?;
+
+    m.add_class::<ResponseVerification>()?;
+    Ok(())
+}
+
+/// Outcome of the `Keygen-Signature` check of the API response an object was built from.
+#[pyclass(frozen)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResponseVerification {
+    pub verified: bool,
+    pub algorithm: Option<String>,
+    pub key_id: Option<String>,
+    pub date: Option<String>,
+    pub digest: Option<String>,
+    pub signature: Option<String>,
+    pub cached: bool,
+}
+
+#[pymethods]
+impl ResponseVerification {
+    #[getter]
+    fn verified(&self) -> PyResult<bool> {
+        Ok(self.verified)
+    }
+
+    #[getter]
+    fn algorithm(&self) -> PyResult<Option<String>> {
+        Ok(self.algorithm.clone())
+    }
+
+    #[getter]
+    fn key_id(&self) -> PyResult<Option<String>> {
+        Ok(self.key_id.clone())
+    }
+
+    #[getter]
+    fn date(&self) -> PyResult<Option<String>> {
+        Ok(self.date.clone())
+    }
+
+    #[getter]
+    fn digest(&self) -> PyResult<Option<String>> {
+        Ok(self.digest.clone())
+    }
+
+    #[getter]
+    fn signature(&self) -> PyResult<Option<String>> {
+        Ok(self.signature.clone())
+    }
+
+    /// Whether the response was replayed from the local cache because the API could not be reached.
+    #[getter]
+    fn cached(&self) -> PyResult<bool> {
+        Ok(self.cached)
+    }
+}
@@ -0,0 +1,240 @@
+use crate::verification::ResponseVerification;
+use base64::{engine::general_purpose, Engine};
+use ed25519_dalek::{PublicKey, Signature, Verifier as Ed25519Verifier};
+use keygen_rs::errors::Error;
+use keygen_rs::license_file::LicenseFile;
+use keygen_rs::machine_file::MachineFile;
+use pyo3::FromPyObject;
+use sha2::{Digest, Sha256};
+
+/// One public key or a list of trusted public keys, as accepted from Python.
+#[derive(Debug, Clone, FromPyObject)]
+pub(crate) enum PublicKeys {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Ed25519 verification against an explicit set of public keys.
+///
+/// `keygen_rs` only verifies against the public key of the global config, which does not
+/// allow verifying keys of several products or rotating keys.
+pub(crate) struct Verifier {
+    public_keys: Vec<String>,
+}
+
+impl Verifier {
+    /// Uses the given keys, or falls back to the public key of the global config.
+    pub(crate) fn new(public_keys: Option<PublicKeys>) -> Self {
+        let public_keys = match public_keys {
+            Some(PublicKeys::One(key)) => vec![key],
+            Some(PublicKeys::Many(keys)) => keys,
+            None => keygen_rs::config::get_config().public_key.into_iter().collect(),
+        };
+        Self {
+            public_keys: public_keys.into_iter().filter(|key| !key.is_empty()).collect(),
+        }
+    }
+
+    /// Verifies a signed key and returns its dataset and the public key that matched.
+    pub(crate) fn verify_key(&self, signed_key: &str) -> Result<(Vec<u8>, String), Error> {
+        if signed_key.is_empty() {
+            return Err(Error::LicenseKeyMissing);
+        }
+        self.ensure_public_keys()?;
+
+        let (signing_data, enc_sig) = signed_key.split_once('.').ok_or(Error::LicenseKeyNotGenuine)?;
+        let enc_dataset = match signing_data.split_once('/') {
+            Some(("key", enc_dataset)) => enc_dataset,
+            _ => return Err(Error::LicenseKeyNotGenuine),
+        };
+
+        let sig = general_purpose::URL_SAFE
+            .decode(enc_sig)
+            .map_err(|_| Error::LicenseKeyNotGenuine)?;
+        let dataset = general_purpose::URL_SAFE
+            .decode(enc_dataset)
+            .map_err(|_| Error::LicenseKeyNotGenuine)?;
+        let signature = Signature::from_bytes(&sig).map_err(|_| Error::LicenseKeyNotGenuine)?;
+
+        let public_key = self.find_signer(signing_data.as_bytes(), &signature, || Error::LicenseKeyNotGenuine)?;
+        Ok((dataset, public_key))
+    }
+
+    /// Verifies a license file and returns the public key that matched.
+    pub(crate) fn verify_license_file(&self, lic: &LicenseFile) -> Result<String, Error> {
+        let cert = lic.certificate()?;
+        self.verify_certificate(&cert.enc, &cert.sig, &cert.alg, "license")
+            .map_err(|e| match e {
+                Error::CertificateFileNotGenuine(msg) => Error::LicenseFileNotGenuine(msg),
+                Error::CertificateFileNotSupported(msg) => Error::LicenseFileNotSupported(msg),
+                _ => e,
+            })
+    }
+
+    /// Verifies a machine file and returns the public key that matched.
+    pub(crate) fn verify_machine_file(&self, lic: &MachineFile) -> Result<String, Error> {
+        let cert = lic.certificate()?;
+        self.verify_certificate(&cert.enc, &cert.sig, &cert.alg, "machine")
+            .map_err(|e| match e {
+                Error::CertificateFileNotGenuine(msg) => Error::MachineFileNotGenuine(msg),
+                Error::CertificateFileNotSupported(msg) => Error::MachineFileNotSupported(msg),
+                _ => e,
+            })
+    }
+
+    /// Verifies a `Keygen-Signature` header over `(request-target) host date digest`.
+    ///
+    /// `header` looks up a header by its lowercase name; `request_target` is the lowercase
+    /// method followed by the path and query, e.g. `get /v1/accounts/x/me`.
+    pub(crate) fn verify_signature(
+        &self,
+        header: impl Fn(&str) -> Option<String>,
+        body: &[u8],
+        request_target: &str,
+        host: &str,
+    ) -> Result<ResponseVerification, Error> {
+        let invalid = |reason: &str| Error::KeygenSignatureInvalid { reason: reason.to_string() };
+
+        let signature_header = header("keygen-signature").ok_or_else(|| invalid("Missing keygen-signature header"))?;
+        let date = header("date").ok_or_else(|| invalid("Missing date header"))?;
+        let digest = header("digest").ok_or_else(|| invalid("Missing digest header"))?;
+
+        let provided_digest = digest
+            .strip_prefix("sha-256=")
+            .ok_or_else(|| invalid("Unsupported digest algorithm"))?;
+        if provided_digest != general_purpose::STANDARD.encode(Sha256::digest(body)) {
+            return Err(invalid("Body digest does not match digest header"));
+        }
+
+        let parameters = SignatureParameters::parse(&signature_header)
+            .ok_or_else(|| invalid("Missing signature components"))?;
+        if parameters.algorithm != "ed25519" {
+            return Err(invalid(&format!("Unsupported algorithm: {}", parameters.algorithm)));
+        }
+        let signature = general_purpose::STANDARD
+            .decode(&parameters.signature)
+            .ok()
+            .and_then(|sig| Signature::from_bytes(&sig).ok())
+            .ok_or_else(|| invalid("Invalid signature format"))?;
+
+        let signing_data = format!(
+            "(request-target): {}\nhost: {}\ndate: {}\ndigest: {}",
+            request_target, host, date, digest
+        );
+        self.ensure_public_keys()?;
+        self.find_signer(signing_data.as_bytes(), &signature, || invalid("Signature verification failed"))?;
+
+        Ok(ResponseVerification {
+            verified: true,
+            algorithm: Some(parameters.algorithm),
+            key_id: Some(parameters.key_id),
+            date: Some(date),
+            digest: Some(digest),
+            signature: Some(signature_header),
+            cached: false,
+        })
+    }
+
+    fn verify_certificate(&self, enc: &str, sig: &str, alg: &str, prefix: &str) -> Result<String, Error> {
+        match alg {
+            "aes-256-gcm+ed25519" | "base64+ed25519" => {
+                self.ensure_public_keys()?;
+
+                let msg = format!("{}/{}", prefix, enc).into_bytes();
+                let sig = general_purpose::STANDARD
+                    .decode(sig)
+                    .map_err(|e| Error::CertificateFileNotGenuine(e.to_string()))?;
+                let signature = Signature::from_bytes(&sig)
+                    .map_err(|e| Error::CertificateFileNotGenuine(e.to_string()))?;
+
+                self.find_signer(&msg, &signature, || {
+                    Error::CertificateFileNotGenuine("Signature verification failed".to_string())
+                })
+            }
+            _ => Err(Error::CertificateFileNotSupported(alg.to_string())),
+        }
+    }
+
+    fn ensure_public_keys(&self) -> Result<(), Error> {
+        if self.public_keys.is_empty() {
+            return Err(Error::PublicKeyMissing);
+        }
+        Ok(())
+    }
+
+    fn find_signer(&self, msg: &[u8], signature: &Signature, not_genuine: impl Fn() -> Error) -> Result<String, Error> {
+        let mut any_valid_key = false;
+        for key in &self.public_keys {
+            let Ok(public_key) = Self::public_key(key) else {
+                continue;
+            };
+            any_valid_key = true;
+            if public_key.verify(msg, signature).is_ok() {
+                return Ok(key.clone());
+            }
+        }
+
+        if any_valid_key {
+            Err(not_genuine())
+        } else {
+            Err(Error::PublicKeyInvalid)
+        }
+    }
+
+    /// Verifies an Ed25519ph signature in base64, as made by `keygen dist` for artifacts, of the
+    /// message whose SHA-512 is `prehash`.
+    pub(crate) fn verify_prehashed(public_key: &str, prehash: sha2_09::Sha512, signature: &str) -> Result<(), String> {
+        let public_key = Self::public_key(public_key).map_err(|e| e.to_string())?;
+        let signature = general_purpose::STANDARD
+            .decode(signature)
+            .ok()
+            .and_then(|signature| Signature::from_bytes(&signature).ok())
+            .ok_or("signature is not a base64-encoded Ed25519 signature")?;
+        public_key
+            .verify_prehashed(prehash, None, &signature)
+            .map_err(|_| "signature does not match".to_string())
+    }
+
+    fn public_key(key: &str) -> Result<PublicKey, Error> {
+        let bytes = hex::decode(key).map_err(|_| Error::PublicKeyInvalid)?;
+        PublicKey::from_bytes(&bytes).map_err(|_| Error::PublicKeyInvalid)
+    }
+}
+
+/// The parameters of a `Keygen-Signature` header, e.g.
+/// `keyid="...", algorithm="ed25519", signature="...", headers="(request-target) host date digest"`.
+pub(crate) struct SignatureParameters {
+    pub key_id: String,
+    pub algorithm: String,
+    pub signature: String,
+}
+
+impl SignatureParameters {
+    pub(crate) fn parse(header: &str) -> Option<Self> {
+        let mut key_id = None;
+        let mut algorithm = None;
+        let mut signature = None;
+        let mut headers = None;
+
+        for part in header.split(',') {
+            let Some((name, value)) = part.trim().split_once('=') else {
+                continue;
+            };
+            let value = value.trim_matches('"').to_string();
+            match name {
+                "keyid" => key_id = Some(value),
+                "algorithm" => algorithm = Some(value),
+                "signature" => signature = Some(value),
+                "headers" => headers = Some(value),
+                _ => {}
+            }
+        }
+
+        headers?;
+        Some(Self {
+            key_id: key_id?,
+            algorithm: algorithm?,
+            signature: signature?,
+        })
+    }
+}
@@ -0,0 +1,294 @@
+use crate::client::from_resource;
+use crate::clock;
+use crate::date::Date;
+use crate::errors::KeygenError;
+use crate::identity::Identity;
+use crate::json::JsonValue;
+use crate::license::License;
+use crate::machine::Machine;
+use crate::state::JsonState;
+use crate::utils::interface_methods;
+use crate::verification::ResponseVerification;
+use crate::verifier::{PublicKeys, Verifier};
+use chrono::{DateTime, Utc};
+use keygen_rs::errors::Error;
+use pyo3::prelude::*;
+use pyo3::{pyclass, pyfunction, pymodule, wrap_pyfunction, Bound, FromPyObject, PyResult, Python};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+#[pymodule(name = "webhooks")]
+pub fn webhooks_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Hack: workaround for https://github.com/PyO3/pyo3/issues/759
+    Python::attach(|py| {
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("keygen_sh.webhooks", m)
+    })?;
+
+    m.add_function(wrap_pyfunction!(verify, m)?)?;
+    m.add_function(wrap_pyfunction!(parse, m)?)?;
+    m.add_class::<WebhookEvent>()?;
+    Ok(())
+}
+
+/// The signatures of the webhooks verified within `max_clock_drift`, with their dates, to reject
+/// a webhook which is delivered again by someone who captured it.
+///
+/// Only this process knows them, see the `seen` argument of `verify` for a shared store.
+static SEEN: Mutex<Vec<(String, DateTime<Utc>)>> = Mutex::new(Vec::new());
+
+/// The body of a webhook, as received by a web framework.
+#[derive(FromPyObject)]
+enum Body {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl Body {
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Body::Text(text) => text.as_bytes(),
+            Body::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+/// Verifies the `Keygen-Signature` of a webhook sent to `path`, its date and that it has not been
+/// received before, and parses it.
+///
+/// `path` has no default because the request-target, i.e. the method and path the webhook was
+/// sent to, is part of the signed string: it cannot be read from the signature.
+#[pyfunction]
+#[pyo3(signature = (headers, body, public_key=None, *, path, method="POST", seen=None))]
+fn verify(
+    headers: &Bound<'_, PyAny>,
+    body: Body,
+    public_key: Option<PublicKeys>,
+    path: &str,
+    method: &str,
+    seen: Option<&Bound<'_, PyAny>>,
+) -> PyResult<WebhookEvent> {
+    // headers may be any mapping, e.g. the headers of a request in Flask or Django
+    let mut lowercase = HashMap::new();
+    for item in headers.call_method0("items")?.try_iter()? {
+        let (name, value): (String, String) = item?.extract()?;
+        lowercase.insert(name.to_lowercase(), value);
+    }
+    let header = |name: &str| lowercase.get(name).cloned();
+
+    let invalid = |reason: &str| KeygenError::from_error(Error::KeygenSignatureInvalid { reason: reason.to_string() });
+    let host = header("host").ok_or_else(|| invalid("Missing host header"))?;
+    let request_target = format!("{} {}", method.to_lowercase(), path);
+
+    let verification = Verifier::new(public_key)
+        .verify_signature(header, body.as_bytes(), &request_target, &host)
+        .map_err(KeygenError::from_error)?;
+
+    let date = verification
+        .date
+        .as_deref()
+        .and_then(|date| DateTime::parse_from_rfc2822(date).ok())
+        .map(|date| date.with_timezone(&Utc))
+        .ok_or_else(|| invalid("Invalid date header"))?;
+    let now = Utc::now();
+    let max_drift = clock::max_drift();
+    if (now - date).abs() > max_drift {
+        return Err(KeygenError::from_kind(
+            "WebhookExpired",
+            json!({ "detail": format!("Webhook was signed at {}, more than {} minutes from now", date.to_rfc3339(), max_drift.num_minutes()) }),
+        ));
+    }
+
+    let event = WebhookEvent::parse(body.as_bytes())
+        .map_err(KeygenError::from_error)?
+        .with_verification(verification.clone());
+
+    let signature = verification.signature.unwrap_or_default();
+    let replayed = match seen {
+        // the signature only needs to be remembered for as long as the date passes the check above
+        Some(seen) => seen.call1((signature, Date::from(date + max_drift)))?.is_truthy()?,
+        None => seen_in_process(signature, date),
+    };
+    if replayed {
+        return Err(KeygenError::from_kind(
+            "WebhookReplayed",
+            json!({ "detail": format!("Webhook event {} was received before", event.id) }),
+        ));
+    }
+    Ok(event)
+}
+
+/// Whether this process verified a webhook with the given signature before, remembering it.
+fn seen_in_process(signature: String, date: DateTime<Utc>) -> bool {
+    let now = Utc::now();
+    let max_drift = clock::max_drift();
+    let mut seen = SEEN.lock().unwrap();
+    seen.retain(|(_, date)| (now - *date).abs() <= max_drift);
+    if seen.iter().any(|(seen, _)| *seen == signature) {
+        return true;
+    }
+    seen.push((signature, date));
+    false
+}
+
+/// Parses a webhook without verifying it, e.g. one which was verified by a proxy.
+#[pyfunction]
+fn parse(body: Body) -> PyResult<WebhookEvent> {
+    WebhookEvent::parse(body.as_bytes()).map_err(KeygenError::from_error)
+}
+
+#[pyclass(frozen, module = "keygen_sh.webhooks")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    pub id: String,
+    pub event: String,
+    pub endpoint: Option<String>,
+    pub status: Option<String>,
+    /// The document of the resource the event is about, e.g. `{"data": {"type": "licenses", ...}}`.
+    pub payload: Value,
+    pub created: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+    pub verification: Option<ResponseVerification>,
+}
+
+impl WebhookEvent {
+    /// Parses a webhook-event resource, whose payload is a JSON document encoded as a string.
+    fn parse(body: &[u8]) -> Result<WebhookEvent, Error> {
+        let body: Value = serde_json::from_slice(body)?;
+        let data = &body["data"];
+        let attributes = &data["attributes"];
+
+        let payload = match &attributes["payload"] {
+            Value::String(payload) => serde_json::from_str(payload)?,
+            payload => payload.clone(),
+        };
+        let id = data["id"]
+            .as_str()
+            .ok_or_else(|| Error::UnexpectedError("Webhook event has no id".to_string()))?;
+        let event = attributes["event"]
+            .as_str()
+            .ok_or_else(|| Error::UnexpectedError("Webhook event has no event".to_string()))?;
+
+        Ok(WebhookEvent {
+            id: id.to_string(),
+            event: event.to_string(),
+            endpoint: attributes["endpoint"].as_str().map(str::to_string),
+            status: attributes["status"].as_str().map(str::to_string),
+            payload,
+            created: serde_json::from_value(attributes["created"].clone())?,
+            updated: serde_json::from_value(attributes["updated"].clone())?,
+            verification: None,
+        })
+    }
+
+    fn with_verification(mut self, verification: ResponseVerification) -> Self {
+        self.verification = Some(verification);
+        self
+    }
+
+    /// The resource of the payload, if it is of the given type.
+    fn resource(&self, kind: &str) -> Option<&Value> {
+        let data = &self.payload["data"];
+        (data["type"] == kind).then_some(data)
+    }
+}
+
+impl JsonState for WebhookEvent {
+    fn to_state(&self) -> Result<Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
+
+    fn from_state(state: Value) -> Result<Self, serde_json::Error> {
+        serde_json::from_value(state)
+    }
+}
+
+impl Identity for WebhookEvent {
+    fn resource_id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+
+    fn repr_fields(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("id", Some(self.id.clone())),
+            ("event", Some(self.event.clone())),
+        ]
+    }
+}
+
+interface_methods! {
+impl WebhookEvent {
+    #[getter]
+    fn id(&self) -> PyResult<String> {
+        Ok(self.id.clone())
+    }
+
+    #[getter]
+    fn event(&self) -> PyResult<String> {
+        Ok(self.event.clone())
+    }
+
+    #[getter]
+    fn endpoint(&self) -> PyResult<Option<String>> {
+        Ok(self.endpoint.clone())
+    }
+
+    #[getter]
+    fn status(&self) -> PyResult<Option<String>> {
+        Ok(self.status.clone())
+    }
+
+    #[getter]
+    fn payload(&self) -> PyResult<JsonValue> {
+        Ok(JsonValue(self.payload.clone()))
+    }
+
+    #[getter]
+    fn resource_type(&self) -> PyResult<Option<String>> {
+        Ok(self.payload["data"]["type"].as_str().map(str::to_string))
+    }
+
+    #[getter]
+    fn created(&self) -> PyResult<Date> {
+        Ok(Date::from(self.created))
+    }
+
+    #[getter]
+    fn updated(&self) -> PyResult<Date> {
+        Ok(Date::from(self.updated))
+    }
+
+    #[getter]
+    fn verification(&self) -> PyResult<Option<ResponseVerification>> {
+        Ok(self.verification.clone())
+    }
+
+    #[getter]
+    fn license(&self) -> PyResult<Option<License>> {
+        let Some(data) = self.resource("licenses") else {
+            return Ok(None);
+        };
+        let license = License::from_resource(data).map_err(KeygenError::from_error)?;
+        let license = License::from(license).with_usage(data);
+        Ok(Some(match &self.verification {
+            Some(verification) => license.with_verification(verification.clone()),
+            None => license,
+        }))
+    }
+
+    #[getter]
+    fn machine(&self) -> PyResult<Option<Machine>> {
+        let Some(data) = self.resource("machines") else {
+            return Ok(None);
+        };
+        let machine = Machine::from(from_resource(data).map_err(KeygenError::from_error)?);
+        Ok(Some(match &self.verification {
+            Some(verification) => machine.with_verification(verification.clone()),
+            None => machine,
+        }))
+    }
+}
+}
@@ -27,3 +27,14 @@ def keygen_config():
 
     # Teardown code (this will execute after the test using this fixture is done)
     # Cleanup actions if necessary
+
+
+@pytest.fixture
+def mock_keygen():
+    from keygen_sh.testing import MockKeygenServer
+
+    with MockKeygenServer() as server:
+        policy = server.add_policy(id="policy", name="Pro", max_machines=2, floating=True)
+        server.add_license("MOCK-KEY", id="license", policy=policy, entitlements=["FEATURE"])
+        set_config(server.config(license_key="MOCK-KEY"))
+        yield server
@@ -0,0 +1,122 @@
+import json
+import time
+
+import pytest
+from config_fixture import mock_keygen
+
+
+def configure(server, tmp_path, **kwargs):
+    from keygen_sh.config import set_config, KeygenConfig
+
+    config = {**server.config(license_key="MOCK-KEY").to_dict(), "cache_dir": str(tmp_path / "cache"), **kwargs}
+    set_config(KeygenConfig.from_dict(config))
+
+
+ME = "get /v1/me"
+VALIDATE_KEY = "post /v1/licenses/actions/validate-key"
+
+
+async def cache_files(tmp_path, request):
+    """Makes the request and returns the files its responses were cached in, by request-target."""
+    before = set((tmp_path / "cache").glob("*.json"))
+    result = await request()
+    paths = set((tmp_path / "cache").glob("*.json")) - before
+    return result, {json.loads(path.read_text())["request_target"]: path for path in paths}
+
+
+async def assert_not_replayed(server, license, **kwargs):
+    from keygen_sh.errors import Error, KeygenError, UnexpectedError
+
+    server.inject_error("INTERNAL_ERROR", status=500, path="validate-key")
+    try:
+        await license.validate_key(**kwargs)
+        assert False, "expected an UnexpectedError"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), UnexpectedError)
+
+
+@pytest.mark.asyncio
+async def test_replay_on_network_failure(mock_keygen, tmp_path):
+    from keygen_sh import validate
+
+    configure(mock_keygen, tmp_path)
+    l = await validate([], ["FEATURE"])
+    assert not l.verification.cached
+
+    mock_keygen.stop()
+    l = await validate([], ["FEATURE"])
+    assert (l.id, l.key) == ("license", "MOCK-KEY")
+    assert l.verification.cached and l.verification.verified
+
+
+@pytest.mark.asyncio
+async def test_grace_period(mock_keygen, tmp_path):
+    from keygen_sh import validate
+
+    configure(mock_keygen, tmp_path, cache_grace_period=1)
+    l = await validate([], [])
+    time.sleep(2.1)
+    await assert_not_replayed(mock_keygen, l)
+
+
+@pytest.mark.asyncio
+async def test_tampered_entry(mock_keygen, tmp_path):
+    from keygen_sh import validate
+
+    configure(mock_keygen, tmp_path)
+    l, paths = await cache_files(tmp_path, lambda: validate([], []))
+    path = paths[VALIDATE_KEY]
+    entry = json.loads(path.read_text())
+
+    path.write_text(json.dumps({**entry, "body": entry["body"].replace('"ACTIVE"', '"ACTIVE" ', 1)}))
+    await assert_not_replayed(mock_keygen, l)
+
+    signature = entry["signature"].replace('signature="', 'signature="AAAA', 1)
+    path.write_text(json.dumps({**entry, "signature": signature}))
+    await assert_not_replayed(mock_keygen, l)
+
+    path.write_text(json.dumps(entry))
+    mock_keygen.inject_error("INTERNAL_ERROR", status=500, path="validate-key")
+    assert (await l.validate_key()).verification.cached
+
+
+@pytest.mark.asyncio
+async def test_swapped_entries(mock_keygen, tmp_path):
+    from keygen_sh import validate
+
+    mock_keygen.add_license("OTHER-KEY", id="other", policy="policy")
+    configure(mock_keygen, tmp_path, license_key="OTHER-KEY")
+    other = await validate([], [])
+
+    configure(mock_keygen, tmp_path)
+    l, paths = await cache_files(tmp_path, lambda: validate([], []))
+    me, license_entry = paths[ME], paths[VALIDATE_KEY]
+    _, paths = await cache_files(tmp_path, lambda: l.validate_key(entitlements=["FEATURE"]))
+    scoped_entry = paths[VALIDATE_KEY]
+    _, paths = await cache_files(tmp_path, other.validate_key)
+    other_entry = paths[VALIDATE_KEY]
+
+    def swap(a, b):
+        a_text, b_text = a.read_text(), b.read_text()
+        a.write_text(b_text)
+        b.write_text(a_text)
+
+    # the response of another license key
+    swap(license_entry, other_entry)
+    await assert_not_replayed(mock_keygen, l)
+    await assert_not_replayed(mock_keygen, other)
+    swap(license_entry, other_entry)
+
+    # the response of another scope
+    swap(license_entry, scoped_entry)
+    await assert_not_replayed(mock_keygen, l)
+    await assert_not_replayed(mock_keygen, l, entitlements=["FEATURE"])
+    swap(license_entry, scoped_entry)
+
+    # the response of another request
+    swap(license_entry, me)
+    await assert_not_replayed(mock_keygen, l)
+    swap(license_entry, me)
+
+    mock_keygen.inject_error("INTERNAL_ERROR", status=500, path="validate-key")
+    assert (await l.validate_key()).verification.cached
@@ -0,0 +1,105 @@
+import json
+from config_fixture import mock_keygen
+
+
+def cli_config(server, tmp_path, license_key="MOCK-KEY"):
+    path = tmp_path / "keygen.json"
+    config = server.config(license_key=license_key).to_dict()
+    path.write_text(json.dumps({k: v for k, v in config.items() if not callable(v)}))
+    return str(path)
+
+
+def test_imports():
+    from keygen_sh.cli import main
+    assert main
+
+
+def test_usage(capsys):
+    from keygen_sh.cli import main
+
+    assert main(["--help"]) == 0
+    assert "inspect-file" in capsys.readouterr().out
+    assert main(["unknown"]) == 2
+
+
+def test_validate(mock_keygen, tmp_path, capsys):
+    from keygen_sh.cli import main
+
+    config = cli_config(mock_keygen, tmp_path)
+    assert main(["validate", "--config", config, "--entitlement", "FEATURE"]) == 0
+    assert "License license is valid" in capsys.readouterr().out
+
+    assert main(["validate", "--config", config, "--json", "--fingerprint", "fp-1"]) == 1
+    error = json.loads(capsys.readouterr().out)["error"]
+    assert error["type"] == "LicenseNotActivated"
+
+    assert main(["validate", "--config", config, "--license-key", "UNKNOWN"]) == 1
+    assert capsys.readouterr().err.startswith("error: ")
+
+
+def test_machines(mock_keygen, tmp_path, capsys):
+    from keygen_sh.cli import main
+
+    config = cli_config(mock_keygen, tmp_path)
+    assert main(["activate", "--config", config, "--fingerprint", "fp-1", "--json"]) == 0
+    result = json.loads(capsys.readouterr().out)
+    assert result["activated"] and result["machine"]["fingerprint"] == "fp-1"
+
+    assert main(["activate", "--config", config, "--fingerprint", "fp-1"]) == 0
+    assert "already activated" in capsys.readouterr().out
+
+    assert main(["machines", "--config", config, "--json"]) == 0
+    assert [m["fingerprint"] for m in json.loads(capsys.readouterr().out)] == ["fp-1"]
+
+    assert main(["deactivate", "--config", config, "--fingerprint", "fp-1"]) == 0
+    assert "Deactivated" in capsys.readouterr().out
+    assert main(["machines", "--config", config, "--json"]) == 0
+    assert json.loads(capsys.readouterr().out) == []
+
+
+def test_checkout_and_inspect_file(mock_keygen, tmp_path, capsys):
+    from keygen_sh.cli import main
+
+    config = cli_config(mock_keygen, tmp_path)
+    lic = tmp_path / "license.lic"
+    assert main(["checkout", "--config", config, "--output", str(lic)]) == 0
+    assert lic.read_text().startswith("-----BEGIN LICENSE FILE-----")
+    assert str(lic) in capsys.readouterr().out
+
+    assert main(["inspect-file", str(lic), "--config", config, "--json"]) == 0
+    result = json.loads(capsys.readouterr().out)
+    assert (result["kind"], result["public_key"], result["expired"]) == ("license", mock_keygen.public_key, False)
+    assert result["data"]["data"]["id"] == "license"
+
+    assert main(["activate", "--config", config, "--fingerprint", "fp-1"]) == 0
+    capsys.readouterr()
+    assert main(["checkout", "--config", config, "--machine", "--fingerprint", "fp-1"]) == 0
+    mlic = tmp_path / "machine.lic"
+    mlic.write_text(capsys.readouterr().out)
+    assert main(["inspect-file", str(mlic), "--config", config, "--fingerprint", "fp-1"]) == 0
+    assert "The machine file is genuine" in capsys.readouterr().out
+
+    assert main(["inspect-file", str(mlic), "--config", config, "--fingerprint", "fp-2"]) == 1
+
+
+def test_offline(tmp_path, capsys):
+    from keygen_sh.cli import main
+    from keygen_sh.testing import Signer
+
+    signer = Signer()
+    config = tmp_path / "keygen.json"
+    config.write_text(json.dumps({"public_key": signer.public_key, "product": "product"}))
+
+    assert main(["verify", signer.sign_key('{"seats": 5}'), "--config", str(config), "--json"]) == 0
+    assert json.loads(capsys.readouterr().out) == {"public_key": signer.public_key, "data": '{"seats": 5}'}
+
+    lic = tmp_path / "expired.lic"
+    lic.write_text(signer.license_file("KEY", ttl=60, issued="2020-01-01T00:00:00Z"))
+    assert main(["inspect-file", str(lic), "--config", str(config), "--license-key", "KEY", "--json"]) == 0
+    assert json.loads(capsys.readouterr().out)["expired"]
+
+    assert main(["inspect-file", str(lic), "--config", str(config), "--license-key", "WRONG"]) == 1
+    assert "DecryptionError" in capsys.readouterr().err
+
+    assert main(["validate", "--config", str(config)]) == 1
+    assert "no account is configured" in capsys.readouterr().err
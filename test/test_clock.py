@@ -0,0 +1,90 @@
+import datetime
+import hashlib
+import hmac
+import json
+
+import pytest
+
+KEY = "CLOCK-KEY"
+
+
+@pytest.fixture
+def ledger(tmp_path):
+    from keygen_sh.config import set_config, KeygenConfig
+
+    path = tmp_path / "clock.json"
+    set_config(KeygenConfig(
+        api_url="https://api.keygen.sh",
+        api_prefix="v1",
+        api_version="v1.7",
+        account="account",
+        product="product",
+        license_key=KEY,
+        clock_ledger=str(path),
+    ))
+    yield path
+
+
+def open_license_file():
+    from keygen_sh.license_file import LicenseFile
+    from keygen_sh.testing import Signer
+
+    return LicenseFile.build_from_cert(KEY, Signer().license_file(KEY, ttl=3600))
+
+
+def assert_tampered(reason):
+    from keygen_sh.errors import Error, ClockTampered, KeygenError
+
+    try:
+        open_license_file()
+        assert False, "expected a ClockTampered error"
+    except KeygenError as e:
+        error = Error.from_error(e)
+        assert isinstance(error, ClockTampered)
+        assert reason in error.reason
+
+
+def write_ledger(path, last_seen):
+    # the key is derived from the config alone, which is why the ledger can be forged like this
+    key = hashlib.sha256(f"account:product:{KEY}".encode()).digest()
+    last_seen = last_seen.isoformat()
+    mac = hmac.new(key, last_seen.encode(), hashlib.sha256).hexdigest()
+    path.write_text(json.dumps({"last_seen": last_seen, "mac": mac}))
+
+
+def test_ledger(ledger):
+    assert not ledger.exists()
+    open_license_file()
+    last_seen = datetime.datetime.fromisoformat(json.loads(ledger.read_text())["last_seen"])
+    assert abs(last_seen - datetime.datetime.now(datetime.timezone.utc)) < datetime.timedelta(minutes=1)
+
+    open_license_file()
+
+
+def test_rollback(ledger):
+    now = datetime.datetime.now(datetime.timezone.utc).replace(microsecond=0)
+
+    write_ledger(ledger, now + datetime.timedelta(minutes=1))
+    open_license_file()
+
+    write_ledger(ledger, now + datetime.timedelta(hours=1))
+    assert_tampered("System clock moved backwards")
+
+
+def test_corrupt_ledger(ledger):
+    ledger.write_text("not a ledger")
+    assert_tampered("Clock ledger is corrupt")
+
+
+def test_forged_ledger(ledger):
+    open_license_file()
+    content = json.loads(ledger.read_text())
+    content["last_seen"] = "2020-01-01T00:00:00+00:00"
+    ledger.write_text(json.dumps(content))
+    assert_tampered("Clock ledger signature is invalid")
+
+
+def test_deleted_ledger(ledger):
+    open_license_file()
+    ledger.unlink()
+    assert_tampered("Clock ledger was deleted")
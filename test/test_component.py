@@ -1,3 +1,24 @@
 def test_imports():
     from keygen_sh.component import Component
-    assert Component
\ No newline at end of file
+    assert Component
+
+
+def test_serialization():
+    import pickle
+    from keygen_sh.component import Component
+
+    component = Component("id", "fingerprint", "name")
+
+    for restored in (pickle.loads(pickle.dumps(component)), Component.from_json(component.to_json())):
+        assert restored.to_dict() == {"id": "id", "fingerprint": "fingerprint", "name": "name"}
+
+
+def test_identity():
+    from keygen_sh.component import Component
+
+    component = Component("id", "fingerprint", "name")
+
+    assert component == Component("id", "other", "other")
+    assert component != Component("other", "fingerprint", "name")
+    assert len({component, Component("id", "fingerprint", "name")}) == 1
+    assert repr(component) == "Component(id='id', fingerprint='fingerprint', name='name')"
@@ -1,10 +1,12 @@
 from config_fixture import keygen_config
 
+
 def test_import():
     from keygen_sh.config import set_config, KeygenConfig
     assert KeygenConfig
     assert set_config
 
+
 def test_set_config(keygen_config):
     from keygen_sh.config import get_config
     config = get_config()
@@ -12,3 +14,110 @@ def test_set_config(keygen_config):
     assert config.api_url == "https://api.keygen.sh"
     assert config.user_agent is None
 
+
+def test_hooks():
+    from keygen_sh.config import set_config, get_config, KeygenConfig
+
+    def on_request(event):
+        pass
+
+    set_config(KeygenConfig(
+        api_url="https://api.keygen.sh",
+        api_prefix="v1",
+        api_version="v1.7",
+        account="account",
+        product="product",
+        on_request=on_request,
+    ))
+    config = get_config()
+
+    assert config.on_request is on_request
+    assert config.on_response is None
+    assert config.on_error is None
+
+
+def test_cache():
+    from keygen_sh.config import KeygenConfig
+
+    config = KeygenConfig(
+        api_url="https://api.keygen.sh",
+        api_prefix="v1",
+        api_version="v1.7",
+        account="account",
+        product="product",
+        cache_dir="/tmp/keygen",
+        cache_grace_period=3600,
+    )
+
+    assert config.cache_dir == "/tmp/keygen"
+    assert config.cache_grace_period == 3600
+
+
+def test_serialization():
+    import pickle
+    from keygen_sh.config import KeygenConfig
+
+    config = KeygenConfig(
+        api_url="https://api.keygen.sh",
+        api_prefix="v1",
+        api_version="v1.7",
+        account="account",
+        product="product",
+        on_request=print,
+    )
+
+    restored = pickle.loads(pickle.dumps(config))
+
+    assert restored.on_request is print
+    assert restored.to_dict() == config.to_dict()
+    assert KeygenConfig.from_dict({**config.to_dict(), "on_request": None}).account == "account"
+
+
+def test_repr():
+    from keygen_sh.config import KeygenConfig
+
+    config = KeygenConfig(
+        api_url="https://api.keygen.sh",
+        api_prefix="v1",
+        api_version="v1.7",
+        account="account",
+        product="product",
+        license_key="C1B6DE-39A6E3-DE1A0E",
+    )
+
+    assert "C1B6DE-39A6E3-DE1A0E" not in repr(config)
+    assert config == config
+
+
+def test_naive_datetimes():
+    import datetime
+    from keygen_sh.config import set_config, get_config, KeygenConfig
+    from keygen_sh.license_file import LicenseFile
+    from keygen_sh.testing import Signer
+
+    config = dict(
+        api_url="https://api.keygen.sh",
+        api_prefix="v1",
+        api_version="v1.7",
+        account="account",
+        product="product",
+    )
+    previous = get_config()
+    try:
+        set_config(KeygenConfig(**config))
+        # aware datetimes in another timezone are converted to UTC
+        tz = datetime.timezone(datetime.timedelta(hours=2))
+        issued, expiry = datetime.datetime(2024, 1, 1, 12, tzinfo=tz), datetime.datetime(2030, 1, 1, 12, tzinfo=tz)
+        file = LicenseFile.build_from_cert("KEY", Signer().license_file("KEY", issued=issued, expiry=expiry))
+
+        assert file.issued == datetime.datetime(2024, 1, 1, 10, tzinfo=datetime.timezone.utc)
+        assert file.issued.tzinfo == datetime.timezone.utc
+        assert file.expiry == datetime.datetime(2030, 1, 1, 10, tzinfo=datetime.timezone.utc)
+
+        set_config(KeygenConfig(**config, naive_datetimes=True))
+        assert get_config().naive_datetimes
+        assert file.issued == datetime.datetime(2024, 1, 1, 10)
+        assert file.issued.tzinfo is None
+        assert file.expiry == datetime.datetime(2030, 1, 1, 10)
+    finally:
+        set_config(previous)
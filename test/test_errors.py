@@ -1,3 +1,59 @@
 def test_imports():
     from keygen_sh.errors import Error
-    assert Error
\ No newline at end of file
+    assert Error
+
+
+def test_clock_tampered():
+    import json
+    from keygen_sh.errors import Error, ClockTampered, KeygenError
+
+    error = KeygenError(json.dumps({
+        "type": "ClockTampered",
+        "details": {"reason": "System clock moved backwards", "now": "2024-01-01T00:00:00+00:00", "last_seen": "2024-02-01T00:00:00+00:00"},
+    }))
+    error = Error.from_error(error)
+
+    assert isinstance(error, ClockTampered)
+    assert error.last_seen == "2024-02-01T00:00:00+00:00"
+
+
+def test_fingerprint_unavailable():
+    import json
+    from keygen_sh.errors import Error, FingerprintUnavailable, KeygenError
+
+    error = KeygenError(json.dumps({
+        "type": "FingerprintUnavailable",
+        "details": {"reason": "The machine id of the operating system cannot be read"},
+    }))
+    error = Error.from_error(error)
+
+    assert isinstance(error, FingerprintUnavailable)
+    assert error.reason == "The machine id of the operating system cannot be read"
+
+
+def test_license_too_many_uses():
+    import json
+    from keygen_sh.errors import Error, KeygenError, LicenseTooManyUses
+
+    error = KeygenError(json.dumps({
+        "type": "LicenseTooManyUses",
+        "details": {"code": "USAGE_EXCEEDED", "detail": "usage exceeds maximum allowed by current policy (5)"},
+    }))
+    error = Error.from_error(error)
+
+    assert isinstance(error, LicenseTooManyUses)
+    assert error.code == "USAGE_EXCEEDED"
+
+
+def test_webhook_replayed():
+    import json
+    from keygen_sh.errors import Error, KeygenError, WebhookReplayed
+
+    error = KeygenError(json.dumps({
+        "type": "WebhookReplayed",
+        "details": {"detail": "Webhook event evt was received before"},
+    }))
+    error = Error.from_error(error)
+
+    assert isinstance(error, WebhookReplayed)
+    assert error.detail == "Webhook event evt was received before"
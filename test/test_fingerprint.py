@@ -0,0 +1,49 @@
+def test_imports():
+    from keygen_sh.fingerprint import machine_fingerprint, current_fingerprint
+    assert machine_fingerprint
+    assert current_fingerprint
+
+
+def test_current_fingerprint():
+    from keygen_sh.config import KeygenConfig, get_config, set_config
+    from keygen_sh.fingerprint import current_fingerprint
+
+    previous = get_config()
+    try:
+        config = dict(api_url="https://api.keygen.sh", api_prefix="v1", api_version="v1.7", account="account", product="product")
+        set_config(KeygenConfig(**config, fingerprint="fixed"))
+        assert current_fingerprint() == "fixed"
+
+        set_config(KeygenConfig(**config, fingerprint=lambda: "computed"))
+        assert current_fingerprint() == "computed"
+    finally:
+        set_config(previous)
+
+
+def hashed_id(machine_id, app_id):
+    # the `hashed_id` of py-machineid: an HMAC of the machine id keyed by the app id
+    import hashlib
+    import hmac
+
+    return hmac.new(app_id.encode(), machine_id.encode(), hashlib.sha256).hexdigest()
+
+
+def test_machine_fingerprint():
+    import os
+    import pytest
+    from keygen_sh.config import KeygenConfig, get_config, set_config
+    from keygen_sh.fingerprint import machine_fingerprint
+
+    assert hashed_id("d3b07384d113edec49eaa6238ad5ff00", "product") == "1708cdfe166dc37179b81a53c7c4bf5fd0ccfd6ccf1db43b21efc0274cf1c744"
+
+    if not os.path.exists("/etc/machine-id"):
+        pytest.skip("the machine id is only read from a file on Linux")
+    with open("/etc/machine-id") as f:
+        machine_id = f.read().strip()
+
+    previous = get_config()
+    try:
+        set_config(KeygenConfig(api_url="https://api.keygen.sh", api_prefix="v1", api_version="v1.7", account="account", product="product"))
+        assert machine_fingerprint() == hashed_id(machine_id, "product")
+    finally:
+        set_config(previous)
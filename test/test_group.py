@@ -0,0 +1,16 @@
+def test_imports():
+    from keygen_sh.group import Group
+    assert Group
+
+
+def test_group_from_dict():
+    from keygen_sh.group import Group
+
+    group = Group.from_dict({
+        "id": "group", "name": "Team", "maxMachines": 10,
+        "created": "2024-01-01T00:00:00Z", "updated": "2024-01-01T00:00:00Z",
+    })
+
+    assert group.max_machines == 10
+    assert group.max_users is None
+    assert Group.from_json(group.to_json()) == group
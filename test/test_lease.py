@@ -0,0 +1,162 @@
+import asyncio
+
+import pytest
+from config_fixture import mock_keygen
+
+
+def test_imports():
+    from keygen_sh.lease import Lease
+    assert Lease
+
+
+async def fingerprints(license):
+    return sorted(m.fingerprint for m in await license.machines())
+
+
+@pytest.mark.asyncio
+async def test_checkout_and_release(mock_keygen):
+    from keygen_sh import validate
+
+    l = await validate([], [])
+    lease = l.lease("fp-1")
+    assert lease.machine is None
+
+    async with lease as machine:
+        assert machine.fingerprint == "fp-1"
+        assert lease.machine.id == machine.id
+        assert await fingerprints(l) == ["fp-1"]
+
+    assert await fingerprints(l) == []
+    assert ("DELETE", f"/v1/machines/{machine.id}") in mock_keygen.requests
+
+
+@pytest.mark.asyncio
+async def test_release_on_error(mock_keygen):
+    from keygen_sh import validate
+
+    l = await validate([], [])
+    try:
+        async with l.lease("fp-1"):
+            raise ValueError("failed while holding the lease")
+    except ValueError:
+        pass
+    assert await fingerprints(l) == []
+
+
+@pytest.mark.asyncio
+async def test_reuse_activated_machine(mock_keygen):
+    from keygen_sh import validate
+
+    l = await validate([], [])
+    activated = await l.activate("fp-1", [])
+    async with l.lease("fp-1") as machine:
+        assert machine.id == activated.id
+    assert await fingerprints(l) == []
+
+
+@pytest.mark.asyncio
+async def test_heartbeat(mock_keygen):
+    from keygen_sh import validate
+
+    l = await validate([], [])
+    async with l.lease("fp-1", heartbeat_interval=1) as machine:
+        await asyncio.sleep(0.2)
+        assert ("POST", f"/v1/machines/{machine.id}/actions/ping") in mock_keygen.requests
+
+
+@pytest.mark.asyncio
+async def test_wait_for_seat(mock_keygen):
+    from keygen_sh import validate
+    from keygen_sh.errors import Error, KeygenError, MachineLimitExceeded
+
+    l = await validate([], [])
+    await l.activate("fp-1", [])
+    taken = await l.activate("fp-2", [])
+
+    try:
+        async with l.lease("fp-3"):
+            assert False, "expected a MachineLimitExceeded error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), MachineLimitExceeded)
+
+    try:
+        async with l.lease("fp-3", wait=True, poll_interval=1, timeout=1):
+            assert False, "expected a MachineLimitExceeded error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), MachineLimitExceeded)
+
+    async def release():
+        await asyncio.sleep(0.5)
+        await taken.deactivate()
+
+    releasing = asyncio.create_task(release())
+    async with l.lease("fp-3", wait=True, poll_interval=1, timeout=5) as machine:
+        assert machine.fingerprint == "fp-3"
+        assert await fingerprints(l) == ["fp-1", "fp-3"]
+    await releasing
+    assert await fingerprints(l) == ["fp-1"]
+
+
+@pytest.mark.asyncio
+async def test_signal_while_waiting(mock_keygen):
+    import os
+    import signal
+    from keygen_sh import validate
+
+    l = await validate([], [])
+    await l.activate("fp-1", [])
+    await l.activate("fp-2", [])
+
+    received = []
+
+    def handler(signum, frame):
+        received.append(signum)
+
+    previous = signal.signal(signal.SIGTERM, handler)
+    try:
+        asyncio.get_running_loop().call_later(0.5, os.kill, os.getpid(), signal.SIGTERM)
+        try:
+            async with l.lease("fp-3", wait=True, poll_interval=1, timeout=30):
+                assert False, "expected the lease to be cancelled"
+        except asyncio.CancelledError:
+            pass
+        await asyncio.sleep(0.1)
+
+        # the handler of the application is restored and gets the signal
+        assert received == [signal.SIGTERM]
+        assert signal.getsignal(signal.SIGTERM) is handler
+        assert not asyncio.get_running_loop().remove_signal_handler(signal.SIGTERM)
+        assert not asyncio.get_running_loop().remove_signal_handler(signal.SIGINT)
+    finally:
+        signal.signal(signal.SIGTERM, previous)
+
+
+def test_signal_while_waiting_terminates():
+    import os
+    import signal
+    import subprocess
+    import sys
+    import textwrap
+
+    script = textwrap.dedent("""
+        import asyncio, os, signal
+        from keygen_sh import validate
+        from keygen_sh.config import set_config
+        from keygen_sh.testing import MockKeygenServer
+
+        async def main(server):
+            l = await validate([], [])
+            await l.activate("fp-1", [])
+            asyncio.get_running_loop().call_later(0.5, os.kill, os.getpid(), signal.SIGTERM)
+            async with l.lease("fp-2", wait=True, poll_interval=1, timeout=30):
+                pass
+
+        with MockKeygenServer() as server:
+            policy = server.add_policy(id="policy", max_machines=1, floating=True)
+            server.add_license("MOCK-KEY", id="license", policy=policy)
+            set_config(server.config(license_key="MOCK-KEY"))
+            asyncio.run(main(server))
+    """)
+    env = {**os.environ, "PYTHONPATH": os.pathsep.join(sys.path)}
+    result = subprocess.run([sys.executable, "-c", script], env=env, timeout=30)
+    assert result.returncode == -signal.SIGTERM
@@ -1,5 +1,5 @@
 import pytest
-from config_fixture import keygen_config
+from config_fixture import keygen_config, mock_keygen
 
 @pytest.mark.asyncio
 async def test_license_validate(keygen_config):
@@ -21,3 +21,123 @@ async def test_license_machines():
     assert ms
     m = await l.machine(id=ms[0].id)
     assert m
+
+
+def test_license_status():
+    from keygen_sh.license import LicenseStatus
+
+    assert LicenseStatus.Active == "ACTIVE"
+    assert LicenseStatus.Active != "EXPIRED"
+    assert LicenseStatus.Active == LicenseStatus.Active
+    assert hash(LicenseStatus.Active) == hash("ACTIVE")
+    assert str(LicenseStatus.Expired) == "EXPIRED"
+
+
+def test_license_expiry():
+    import datetime
+    from keygen_sh.license import License
+
+    l = License.from_dict({
+        "id": "id", "key": "key", "name": None, "status": "ACTIVE", "policy": None, "metadata": {},
+        "expiry": "2030-01-01T00:00:00Z",
+    })
+
+    assert not l.is_expired(now=datetime.datetime(2029, 12, 31, tzinfo=datetime.timezone.utc))
+    assert l.is_expired(now=datetime.datetime(2030, 1, 1, 1))
+    assert l.expires_in(now=datetime.datetime(2029, 12, 31)) == datetime.timedelta(days=1)
+
+
+def test_license_admin_requires_token(mock_keygen):
+    from keygen_sh.errors import Error, KeygenError, TokenMissing
+    from keygen_sh.license import License
+
+    # the fixture authenticates with a license key only
+    try:
+        License.create("policy")
+        assert False, "expected a TokenMissing error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), TokenMissing)
+
+
+
+def use_token(server):
+    from keygen_sh.config import set_config, KeygenConfig
+
+    set_config(KeygenConfig.from_dict({**server.config().to_dict(), "token": "ADMIN-TOKEN"}))
+
+
+@pytest.mark.asyncio
+async def test_license_admin_lifecycle(mock_keygen):
+    import datetime
+    from keygen_sh.errors import Error, KeygenError, NotFound
+    from keygen_sh.license import License
+
+    mock_keygen.add_policy(id="yearly", duration=365 * 24 * 60 * 60)
+    use_token(mock_keygen)
+    expiry = datetime.datetime(2030, 1, 1, tzinfo=datetime.timezone.utc)
+
+    l = await License.create("yearly", name="Pro", metadata={"seats": 5}, expiry=expiry, user="user")
+    assert ("POST", "/v1/licenses") in mock_keygen.requests
+    assert (l.name, l.status, l.expiry, l.policy) == ("Pro", "ACTIVE", expiry, "yearly")
+    assert l.to_dict()["metadata"] == {"seats": 5}
+    assert l.verification.verified
+
+    l = await l.update(name="Enterprise")
+    assert ("PATCH", f"/v1/licenses/{l.id}") in mock_keygen.requests
+    assert (l.name, l.expiry) == ("Enterprise", expiry)
+    assert l.to_dict()["metadata"] == {"seats": 5}
+
+    l = await l.suspend()
+    assert ("POST", f"/v1/licenses/{l.id}/actions/suspend") in mock_keygen.requests
+    assert l.status == "SUSPENDED"
+
+    l = await l.reinstate()
+    assert ("POST", f"/v1/licenses/{l.id}/actions/reinstate") in mock_keygen.requests
+    assert l.status == "ACTIVE"
+
+    l = await l.renew()
+    assert ("POST", f"/v1/licenses/{l.id}/actions/renew") in mock_keygen.requests
+    assert l.expiry == expiry + datetime.timedelta(days=365)
+
+    await l.revoke()
+    assert ("DELETE", f"/v1/licenses/{l.id}/actions/revoke") in mock_keygen.requests
+    try:
+        await l.update(name="Revoked")
+        assert False, "expected a NotFound error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), NotFound)
+
+    other = await License.create("yearly")
+    await other.delete()
+    assert ("DELETE", f"/v1/licenses/{other.id}") in mock_keygen.requests
+    try:
+        await other.suspend()
+        assert False, "expected a NotFound error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), NotFound)
+
+
+@pytest.mark.asyncio
+async def test_license_admin_token_not_allowed(mock_keygen):
+    from keygen_sh.errors import Error, KeygenError, TokenNotAllowed
+    from keygen_sh.license import License
+
+    use_token(mock_keygen)
+    # a token without the permission is answered with a 403 which has no token code
+    mock_keygen.inject_error("ACCESS_DENIED", status=403, path="licenses")
+    try:
+        await License.create("policy")
+        assert False, "expected a TokenNotAllowed error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), TokenNotAllowed)
+
+def test_license_usage():
+    from keygen_sh.license import License
+
+    l = License.from_dict({
+        "id": "id", "key": "key", "name": None, "expiry": None, "status": "ACTIVE", "policy": None, "metadata": {},
+        "uses": 3, "max_uses": 5,
+    })
+
+    assert (l.uses, l.max_uses) == (3, 5)
+    assert License.from_dict(l.to_dict()).uses == 3
@@ -0,0 +1,100 @@
+import asyncio
+import time
+
+import pytest
+from config_fixture import mock_keygen
+
+
+def test_imports():
+    from keygen_sh.license_file_manager import LicenseFileManager
+    assert LicenseFileManager
+
+
+def checkouts(server):
+    return len([path for method, path in server.requests if path.startswith("/v1/licenses/license/actions/check-out")])
+
+
+@pytest.mark.asyncio
+async def test_refresh_at_ratio(mock_keygen, tmp_path):
+    from keygen_sh import validate
+    from keygen_sh.license_file_manager import LicenseFileManager
+
+    events = []
+    l = await validate([], [])
+    manager = LicenseFileManager(l, tmp_path / "license.lic", ttl=2, refresh_ratio=0.5, on_event=events.append)
+    try:
+        first = await manager.start()
+        assert manager.running and checkouts(mock_keygen) == 1
+        assert (tmp_path / "license.lic").read_text() == first.certificate
+        assert [e["type"] for e in events] == ["refreshed"]
+        assert events[0]["id"] == first.id and events[0]["error"] is None
+
+        # half of the ttl has not passed yet
+        await asyncio.sleep(0.5)
+        assert checkouts(mock_keygen) == 1
+
+        await asyncio.sleep(1.0)
+        assert checkouts(mock_keygen) == 2
+        assert [e["type"] for e in events] == ["refreshed", "refreshed"]
+        assert manager.current.issued > first.issued
+        assert (tmp_path / "license.lic").read_text() == manager.current.certificate
+    finally:
+        manager.stop()
+    assert not manager.running
+
+
+@pytest.mark.asyncio
+async def test_file_on_disk(mock_keygen, tmp_path):
+    from keygen_sh import validate
+    from keygen_sh.license_file_manager import LicenseFileManager
+
+    l = await validate([], [])
+    first = LicenseFileManager(l, tmp_path / "license.lic", ttl=3600)
+    file = await first.start()
+    first.stop()
+
+    # not due for a refresh, so the file on disk is used as is
+    manager = LicenseFileManager(l, tmp_path / "license.lic", ttl=3600)
+    assert (await manager.start()).certificate == file.certificate
+    manager.stop()
+    assert checkouts(mock_keygen) == 1
+
+
+@pytest.mark.asyncio
+async def test_offline_fallback(mock_keygen, tmp_path):
+    from keygen_sh import validate
+    from keygen_sh.errors import KeygenError
+    from keygen_sh.license_file_manager import LicenseFileManager
+
+    events = []
+    l = await validate([], [])
+    manager = LicenseFileManager(l, tmp_path / "license.lic", ttl=3, refresh_ratio=0.0, on_event=events.append)
+    file = await manager.start()
+    manager.stop()
+    mock_keygen.stop()
+
+    # due for a refresh, but the API cannot be reached and the file on disk is still valid
+    manager = LicenseFileManager(
+        l, tmp_path / "license.lic", refresh_ratio=0.0, expiring_within=3, retry_interval=1, on_event=events.append
+    )
+    try:
+        assert (await manager.start()).certificate == file.certificate
+        assert manager.current.certificate == file.certificate
+        assert events[1]["type"] == "refresh_failed" and events[1]["error"]
+
+        deadline = time.monotonic() + 6
+        while "expired" not in [e["type"] for e in events] and time.monotonic() < deadline:
+            await asyncio.sleep(0.2)
+        kinds = [e["type"] for e in events]
+        assert "expiring" in kinds and "expired" in kinds
+        assert kinds.index("expiring") < kinds.index("expired")
+        assert kinds.count("expiring") == 1 and kinds.count("expired") == 1
+    finally:
+        manager.stop()
+
+    # neither the API nor a valid file on disk
+    try:
+        await LicenseFileManager(l, tmp_path / "missing.lic").start()
+        assert False, "expected a KeygenError"
+    except KeygenError:
+        pass
@@ -0,0 +1,104 @@
+import logging
+
+import pytest
+from config_fixture import mock_keygen
+
+
+class Records(logging.Handler):
+    def __init__(self):
+        super().__init__(logging.DEBUG)
+        self.messages = []
+
+    def emit(self, record):
+        self.messages.append(record.getMessage())
+
+
+@pytest.fixture
+def records():
+    handler = Records()
+    logger = logging.getLogger("keygen_sh")
+    level = logger.level
+    logger.addHandler(handler)
+    logger.setLevel(logging.DEBUG)
+    yield handler
+    logger.removeHandler(handler)
+    logger.setLevel(level)
+
+
+def configure(server, **kwargs):
+    from keygen_sh.config import set_config, KeygenConfig
+
+    set_config(KeygenConfig.from_dict({**server.config(license_key="MOCK-KEY").to_dict(), **kwargs}))
+
+
+@pytest.mark.asyncio
+async def test_redact_license_key_of_call(mock_keygen, records):
+    from keygen_sh import validate
+    from keygen_sh.errors import KeygenError
+
+    mock_keygen.add_license("OTHER-KEY", id="other", policy="policy")
+    configure(mock_keygen, license_key="OTHER-KEY")
+    other = await validate([], [])
+
+    events = []
+    configure(mock_keygen, on_error=events.append)
+    mock_keygen.inject_error("INTERNAL_ERROR", status=500, detail="failed to validate OTHER-KEY", path="validate-key")
+    try:
+        await other.validate_key([], [])
+        assert False, "expected an UnexpectedError"
+    except KeygenError:
+        pass
+
+    assert len(events) == 1 and "failed to validate [REDACTED]" in events[0]["error"]
+    assert any("failed to validate [REDACTED]" in m for m in records.messages)
+    assert not any("OTHER-KEY" in m for m in records.messages)
+
+
+@pytest.mark.asyncio
+async def test_redact_refreshed_token(mock_keygen, records):
+    from keygen_sh.product import Product
+    from keygen_sh.errors import KeygenError
+
+    events = []
+    configure(
+        mock_keygen,
+        license_key=None,
+        token="OLD-TOKEN",
+        token_refresher=lambda: "NEW-TOKEN",
+        on_error=events.append,
+    )
+    mock_keygen.inject_error("TOKEN_EXPIRED", path="products")
+    mock_keygen.inject_error("INTERNAL_ERROR", status=500, detail="neither OLD-TOKEN nor NEW-TOKEN", path="products")
+    try:
+        await Product.list()
+        assert False, "expected an UnexpectedError"
+    except KeygenError:
+        pass
+
+    assert "neither [REDACTED] nor [REDACTED]" in events[-1]["error"]
+    assert any("neither [REDACTED] nor [REDACTED]" in m for m in records.messages)
+    assert not any("-TOKEN" in m for m in records.messages)
+
+
+@pytest.mark.asyncio
+async def test_level_read_on_set_config(mock_keygen):
+    from keygen_sh import validate
+
+    handler = Records()
+    logger = logging.getLogger("keygen_sh")
+    level = logger.level
+    logger.addHandler(handler)
+    try:
+        logger.setLevel(logging.WARNING)
+        configure(mock_keygen)
+        logger.setLevel(logging.DEBUG)
+        await validate([], [])
+        # records below the level of the last `set_config` do not reach Python
+        assert handler.messages == []
+
+        configure(mock_keygen)
+        await validate([], [])
+        assert handler.messages
+    finally:
+        logger.removeHandler(handler)
+        logger.setLevel(level)
@@ -0,0 +1,82 @@
+import pytest
+from config_fixture import mock_keygen
+
+
+def test_imports():
+    from keygen_sh.testing import MockKeygenServer
+    assert MockKeygenServer
+
+
+@pytest.mark.asyncio
+async def test_validate(mock_keygen):
+    from keygen_sh import validate
+
+    l = await validate([], ["FEATURE"])
+    assert (l.id, l.key, l.status) == ("license", "MOCK-KEY", "ACTIVE")
+    assert l.verification.verified
+    assert ("POST", "/v1/licenses/actions/validate-key") in mock_keygen.requests
+
+
+@pytest.mark.asyncio
+async def test_machines(mock_keygen):
+    from keygen_sh import validate
+    from keygen_sh.errors import Error, KeygenError, LicenseNotActivated, MachineLimitExceeded
+
+    l = await validate([], [])
+    try:
+        await l.validate(["fp-1"], [])
+        assert False, "expected a LicenseNotActivated error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), LicenseNotActivated)
+
+    m = await l.activate("fp-1", [])
+    await l.activate("fp-2", [])
+    assert (await l.validate(["fp-1"], [])).id == "license"
+    assert sorted(m.fingerprint for m in await l.machines()) == ["fp-1", "fp-2"]
+    try:
+        await l.activate("fp-3", [])
+        assert False, "expected a MachineLimitExceeded error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), MachineLimitExceeded)
+
+    assert (await m.ping()).heartbeat_status == "ALIVE"
+    await m.deactivate()
+    assert [m.fingerprint for m in await l.machines()] == ["fp-2"]
+
+
+@pytest.mark.asyncio
+async def test_checkout(mock_keygen):
+    from keygen_sh import validate
+
+    l = await validate([], [])
+    lic = await l.checkout(ttl=3600)
+    assert lic.ttl == 3600
+    assert lic.verify(mock_keygen.public_key) == mock_keygen.public_key
+    assert lic.decrypt("MOCK-KEY").license.id == "license"
+    assert [e.code for e in await l.entitlements()] == ["FEATURE"]
+
+    m = await l.activate("fp-1", [])
+    machine_file = await m.checkout(None, None)
+    assert machine_file.decrypt("MOCK-KEYfp-1").machine.fingerprint == "fp-1"
+
+
+@pytest.mark.asyncio
+async def test_injected_errors(mock_keygen):
+    from keygen_sh import validate
+    from keygen_sh.errors import Error, KeygenError, LicenseSuspended, RateLimitExceeded
+
+    mock_keygen.inject_error("RATE_LIMIT_EXCEEDED", path="validate-key")
+    try:
+        await validate([], [])
+        assert False, "expected a RateLimitExceeded error"
+    except KeygenError as e:
+        error = Error.from_error(e)
+        assert isinstance(error, RateLimitExceeded)
+        assert error.retry_after == 30
+
+    mock_keygen.add_license("MOCK-KEY", id="license", status="SUSPENDED")
+    try:
+        await validate([], [])
+        assert False, "expected a LicenseSuspended error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), LicenseSuspended)
@@ -0,0 +1,76 @@
+import pytest
+from config_fixture import mock_keygen
+
+
+def test_imports():
+    from keygen_sh.pagination import PageIterator
+    assert PageIterator
+
+
+def pages(server, path):
+    return [target for method, target in server.requests if target.startswith(path + "?")]
+
+
+@pytest.mark.asyncio
+async def test_iterate_pages(mock_keygen):
+    from keygen_sh import validate
+
+    mock_keygen.add_license("MOCK-KEY", id="license", policy="policy", entitlements=["A", "B", "C", "D", "E"])
+    l = await validate([], [])
+
+    codes = [e.code async for e in l.iter_entitlements(page_size=2)]
+    assert codes == ["A", "B", "C", "D", "E"]
+    requests = pages(mock_keygen, "/v1/licenses/license/entitlements")
+    assert len(requests) == 3
+    assert all("page%5Bsize%5D=2" in target for target in requests)
+    assert [target.split("page%5Bnumber%5D=")[1][0] for target in requests] == ["1", "2", "3"]
+
+
+@pytest.mark.asyncio
+async def test_stop_at_last_page(mock_keygen):
+    from keygen_sh import validate
+
+    mock_keygen.add_policy(id="team", name="Team", max_machines=10, floating=True)
+    mock_keygen.add_license("MOCK-KEY", id="license", policy="team")
+    l = await validate([], [])
+    for i in range(4):
+        await l.activate(f"fp-{i}", [])
+
+    # the last page is full, but links no next page
+    fingerprints = [m.fingerprint async for m in l.iter_machines(page_size=2)]
+    assert sorted(fingerprints) == ["fp-0", "fp-1", "fp-2", "fp-3"]
+    assert len(pages(mock_keygen, "/v1/machines")) == 2
+
+    assert [m.fingerprint async for m in l.iter_machines(page_size=10, fingerprint="fp-2")] == ["fp-2"]
+    assert sorted(m.fingerprint for m in await l.machines()) == ["fp-0", "fp-1", "fp-2", "fp-3"]
+
+
+@pytest.mark.asyncio
+async def test_machine_filters(mock_keygen):
+    from keygen_sh import validate
+
+    machines = [
+        ("fp-0", "linux", "build-1", "ALIVE"),
+        ("fp-1", "macos", "build-1", "DEAD"),
+        ("fp-2", "linux", "build-2", "ALIVE"),
+        ("fp-3", "macos", "build-2", "NOT_STARTED"),
+        ("fp-4", "linux", "build-1", "DEAD"),
+    ]
+    mock_keygen.add_policy(id="team", name="Team", max_machines=10, floating=True)
+    mock_keygen.add_license("MOCK-KEY", id="license", policy="team")
+    for fingerprint, platform, hostname, status in machines:
+        mock_keygen.add_machine("license", fingerprint, platform=platform, hostname=hostname, heartbeat_status=status)
+    l = await validate([], [])
+
+    async def fingerprints(**filters):
+        return [m.fingerprint async for m in l.iter_machines(page_size=2, **filters)]
+
+    assert await fingerprints(hostname="build-1") == ["fp-0", "fp-1", "fp-4"]
+    assert await fingerprints(heartbeat_status="alive") == ["fp-0", "fp-2"]
+    assert await fingerprints(hostname="build-1", heartbeat_status="dead") == ["fp-1", "fp-4"]
+
+    # the platform is filtered locally, within each page of the API
+    requested = len(pages(mock_keygen, "/v1/machines"))
+    assert await fingerprints(platform="linux") == ["fp-0", "fp-2", "fp-4"]
+    assert len(pages(mock_keygen, "/v1/machines")) - requested == 3
+    assert await fingerprints(platform="windows") == []
@@ -0,0 +1,22 @@
+def test_imports():
+    from keygen_sh.policy import Policy
+    assert Policy
+
+
+def test_policy_from_dict():
+    from keygen_sh.policy import Policy
+
+    policy = Policy.from_dict({
+        "id": "pol", "name": "Pro", "duration": 86400, "floating": True, "maxMachines": 3,
+        "fingerprintUniquenessStrategy": "UNIQUE_PER_LICENSE", "heartbeatDuration": 600,
+        "created": "2024-01-01T00:00:00Z", "updated": "2024-01-01T00:00:00Z",
+    })
+
+    assert policy.duration == 86400
+    assert policy.floating
+    assert not policy.strict
+    assert policy.max_machines == 3
+    assert policy.max_cores is None
+    assert policy.fingerprint_uniqueness_strategy == "UNIQUE_PER_LICENSE"
+    assert policy.metadata == {}
+    assert Policy.from_json(policy.to_json()) == policy
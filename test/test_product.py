@@ -0,0 +1,17 @@
+def test_imports():
+    from keygen_sh.product import Product
+    assert Product
+
+
+def test_product_from_dict():
+    from keygen_sh.product import Product
+
+    product = Product.from_dict({
+        "id": "prod", "name": "App", "distributionStrategy": "LICENSED", "platforms": ["linux"],
+        "created": "2024-01-01T00:00:00Z", "updated": "2024-01-01T00:00:00Z",
+    })
+
+    assert product.distribution_strategy == "LICENSED"
+    assert product.platforms == ["linux"]
+    assert product.code is None
+    assert Product.from_json(product.to_json()) == product
@@ -0,0 +1,164 @@
+import base64
+import hashlib
+import os
+
+import pytest
+from config_fixture import mock_keygen
+
+
+def test_imports():
+    from keygen_sh.release import Release
+    from keygen_sh.artifact import Artifact
+    assert Release
+    assert Artifact
+
+
+def test_release_from_dict():
+    from keygen_sh.release import Release
+
+    release = Release.from_dict({
+        "id": "rel", "name": "App 1.2.0", "channel": "stable", "version": "1.2.0", "yanked": None,
+        "created": "2024-01-01T00:00:00Z", "updated": "2024-01-01T00:00:00Z",
+    })
+
+    assert release.version == "1.2.0"
+    assert release.channel == "stable"
+    assert release.yanked is None
+    assert Release.from_json(release.to_json()) == release
+
+
+def test_artifact_from_dict():
+    from keygen_sh.artifact import Artifact
+
+    artifact = Artifact.from_dict({
+        "id": "art", "filename": "app-linux-amd64.tar.gz", "filesize": 1024, "platform": "linux", "arch": "amd64",
+        "checksum": "abc", "created": "2024-01-01T00:00:00Z", "updated": "2024-01-01T00:00:00Z",
+    })
+
+    assert artifact.filesize == 1024
+    assert artifact.signature is None
+    assert Artifact.from_json(artifact.to_json()) == artifact
+
+
+def test_upgrade_requires_semver():
+    from keygen_sh.release import Release
+
+    try:
+        Release.upgrade("not a version")
+        assert False, "expected a ValueError"
+    except ValueError:
+        pass
+
+
+def add_artifact(server, content=b"app", **kwargs):
+    release = server.add_release("1.0.0", id="release")
+    return server.add_artifact(release, "app-linux-amd64.tar.gz", content, platform="linux", arch="amd64", **kwargs)
+
+
+async def artifact_of(server):
+    from keygen_sh.release import Release
+
+    [release] = await Release.list()
+    [artifact] = await release.artifacts()
+    return artifact
+
+
+@pytest.mark.asyncio
+async def test_list(mock_keygen):
+    from keygen_sh.release import Release
+
+    mock_keygen.add_release("1.0.0")
+    mock_keygen.add_release("1.1.0")
+    mock_keygen.add_release("2.0.0-beta.1", channel="beta")
+
+    assert [r.version for r in await Release.list(page_size=2)] == ["1.0.0", "1.1.0", "2.0.0-beta.1"]
+    assert [r.version for r in await Release.list(channel="beta")] == ["2.0.0-beta.1"]
+
+
+@pytest.mark.asyncio
+async def test_upgrade(mock_keygen):
+    from keygen_sh.release import Release
+
+    mock_keygen.add_release("1.0.0")
+    mock_keygen.add_release("1.1.0")
+    mock_keygen.add_release("2.0.0")
+    mock_keygen.add_release("3.0.0-beta.1", channel="beta")
+
+    assert (await Release.upgrade("1.0.0")).version == "2.0.0"
+    assert (await Release.upgrade("v1.0.0", constraint="1.0")).version == "1.1.0"
+    assert (await Release.upgrade("2.0.0", channel="beta")).version == "3.0.0-beta.1"
+    assert await Release.upgrade("2.0.0") is None
+
+
+@pytest.mark.asyncio
+async def test_artifacts(mock_keygen):
+    from keygen_sh.release import Release
+
+    release = mock_keygen.add_release("1.0.0")
+    mock_keygen.add_artifact(release, "app-linux-amd64.tar.gz", b"linux", platform="linux", arch="amd64")
+    mock_keygen.add_artifact(release, "app-darwin-arm64.tar.gz", b"darwin", platform="darwin", arch="arm64")
+
+    [release] = await Release.list()
+    assert len(await release.artifacts()) == 2
+    [artifact] = await release.artifacts(platform="darwin")
+    assert artifact.filename == "app-darwin-arm64.tar.gz"
+    assert artifact.arch == "arm64"
+    assert artifact.filesize == len(b"darwin")
+
+
+@pytest.mark.asyncio
+async def test_download(mock_keygen, tmp_path):
+    content = os.urandom(64 * 1024)
+    checksums = [
+        hashlib.sha256(content).hexdigest(),
+        base64.b64encode(hashlib.sha256(content).digest()).decode(),
+        hashlib.sha512(content).hexdigest(),
+        base64.b64encode(hashlib.sha512(content).digest()).decode(),
+    ]
+
+    for checksum in checksums:
+        add_artifact(mock_keygen, content, id="artifact", checksum=checksum)
+        artifact = await artifact_of(mock_keygen)
+        path = str(tmp_path / "app.tar.gz")
+
+        assert await artifact.download(path, public_key=mock_keygen.public_key) == path
+        with open(path, "rb") as f:
+            assert f.read() == content
+        assert not os.path.exists(path + ".part")
+        os.remove(path)
+
+
+@pytest.mark.asyncio
+async def test_download_corrupted(mock_keygen, tmp_path):
+    from keygen_sh.errors import Error, KeygenError, ArtifactChecksumInvalid
+
+    add_artifact(mock_keygen, b"app", served=b"corrupted app")
+    artifact = await artifact_of(mock_keygen)
+    path = str(tmp_path / "app.tar.gz")
+
+    try:
+        await artifact.download(path)
+        assert False, "expected an ArtifactChecksumInvalid error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), ArtifactChecksumInvalid)
+    assert os.listdir(tmp_path) == []
+
+
+@pytest.mark.asyncio
+async def test_download_bad_signature(mock_keygen, tmp_path):
+    from keygen_sh.errors import Error, KeygenError, ArtifactSignatureInvalid
+    from keygen_sh.testing import Signer
+
+    add_artifact(mock_keygen, b"app", signature=Signer().sign_artifact(b"app"))
+    artifact = await artifact_of(mock_keygen)
+    path = str(tmp_path / "app.tar.gz")
+
+    try:
+        await artifact.download(path, public_key=mock_keygen.public_key)
+        assert False, "expected an ArtifactSignatureInvalid error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), ArtifactSignatureInvalid)
+    assert os.listdir(tmp_path) == []
+
+    # without a public key, only the checksum is checked
+    assert await artifact.download(path) == path
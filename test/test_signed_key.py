@@ -0,0 +1,78 @@
+def test_imports():
+    from keygen_sh import verify_key
+    from keygen_sh.signed_key import SignedKey
+    assert verify_key
+    assert SignedKey
+
+
+def test_verify_key_with_public_key():
+    from keygen_sh import verify_key
+    from keygen_sh.license import SchemeCode
+    from keygen_sh.testing import Signer
+
+    signer = Signer()
+    key = verify_key(SchemeCode.Ed25519Sign, signer.sign_key('{"seats": 5}'), public_key=signer.public_key)
+
+    assert key.public_key == signer.public_key
+    assert key.json() == {"seats": 5}
+
+
+def test_verify_key_with_trusted_keys():
+    from keygen_sh import verify_key
+    from keygen_sh.license import SchemeCode
+    from keygen_sh.testing import Signer
+
+    previous, current = Signer(), Signer()
+    key = verify_key(SchemeCode.Ed25519Sign, current.sign_key("data"), public_key=[previous.public_key, current.public_key])
+
+    assert key.public_key == current.public_key
+    assert key.data == "data"
+
+
+def test_verify_key_skips_invalid_keys():
+    from keygen_sh import verify_key
+    from keygen_sh.errors import Error, KeygenError, LicenseKeyNotGenuine, PublicKeyInvalid
+    from keygen_sh.license import SchemeCode
+    from keygen_sh.testing import Signer
+
+    signer = Signer()
+    signed = signer.sign_key("data")
+
+    key = verify_key(SchemeCode.Ed25519Sign, signed, public_key=["not hex", "abcd", signer.public_key])
+    assert key.public_key == signer.public_key
+
+    try:
+        verify_key(SchemeCode.Ed25519Sign, signed, public_key=["not hex", Signer().public_key])
+        assert False, "expected a LicenseKeyNotGenuine error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), LicenseKeyNotGenuine)
+
+    try:
+        verify_key(SchemeCode.Ed25519Sign, signed, public_key=["not hex", "abcd"])
+        assert False, "expected a PublicKeyInvalid error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), PublicKeyInvalid)
+
+
+def test_binary_data():
+    from keygen_sh import verify_key
+    from keygen_sh.errors import Error, KeygenError, LicenseKeyDataInvalid
+    from keygen_sh.license import SchemeCode
+    from keygen_sh.testing import Signer
+
+    signer = Signer()
+    key = verify_key(SchemeCode.Ed25519Sign, signer.sign_key(b"\xff\xfe"), public_key=signer.public_key)
+
+    assert key.bytes == b"\xff\xfe"
+
+    try:
+        key.data
+        assert False, "expected a LicenseKeyDataInvalid error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), LicenseKeyDataInvalid)
+
+    try:
+        key.json()
+        assert False, "expected a LicenseKeyDataInvalid error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), LicenseKeyDataInvalid)
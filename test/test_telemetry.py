@@ -0,0 +1,105 @@
+import pytest
+from config_fixture import mock_keygen
+
+
+def test_imports():
+    from keygen_sh.telemetry import set_exporter, metrics, reset_metrics, Span, InMemoryExporter
+    assert set_exporter
+    assert metrics
+    assert reset_metrics
+    assert Span
+    assert InMemoryExporter
+
+
+def test_in_memory_exporter():
+    from keygen_sh.telemetry import set_exporter, InMemoryExporter
+    exporter = InMemoryExporter()
+    set_exporter(exporter)
+
+    assert len(exporter) == 0
+    assert exporter.spans == []
+
+    set_exporter(None)
+
+
+def test_metrics():
+    from keygen_sh.telemetry import metrics, reset_metrics
+    reset_metrics()
+
+    assert metrics().counters == {}
+    assert metrics().count("license.validate") == 0
+
+
+@pytest.mark.asyncio
+async def test_spans(mock_keygen, tmp_path):
+    from keygen_sh import validate
+    from keygen_sh.config import set_config, KeygenConfig
+    from keygen_sh.errors import KeygenError
+    from keygen_sh.telemetry import set_exporter, metrics, reset_metrics, InMemoryExporter
+
+    reset_metrics()
+    exporter = InMemoryExporter()
+    set_exporter(exporter)
+    try:
+        l = await validate([], [])
+        span = exporter.spans[-1]
+        assert (span.operation, span.license_id, span.outcome, span.error) == ("validate", "license", "OK", None)
+        assert span.duration > 0 and span.requests == 2 and span.retry_count == 0
+
+        mock_keygen.inject_error("LICENSE_SUSPENDED", path="validate-key")
+        try:
+            await l.validate_key()
+            assert False, "expected a LicenseSuspended error"
+        except KeygenError:
+            pass
+        span = exporter.spans[-1]
+        assert (span.operation, span.license_id, span.outcome) == ("license.validate_key", "license", "LicenseSuspended")
+        assert span.error and span.retry_count == 0
+
+        config = {**mock_keygen.config(license_key="MOCK-KEY").to_dict(), "cache_dir": str(tmp_path)}
+        set_config(KeygenConfig.from_dict(config))
+        await l.validate_key()
+        mock_keygen.inject_error("INTERNAL_ERROR", status=500, path="validate-key")
+        assert (await l.validate_key()).verification.cached
+        span = exporter.spans[-1]
+        assert (span.outcome, span.requests, span.retry_count) == ("OK", 1, 1)
+
+        set_config(KeygenConfig.from_dict({**config, "license_key": None, "token": "OLD-TOKEN", "token_refresher": lambda: "NEW-TOKEN"}))
+        mock_keygen.inject_error("TOKEN_EXPIRED", path="validate-key")
+        await l.validate_key()
+        span = exporter.spans[-1]
+        assert (span.outcome, span.requests, span.retry_count) == ("OK", 2, 1)
+
+        assert metrics().count("license.validate_key") == 4
+        assert metrics().count("license.validate_key", "LicenseSuspended") == 1
+    finally:
+        set_exporter(None)
+
+
+@pytest.mark.asyncio
+async def test_error_outcome():
+    from keygen_sh import validate
+    from keygen_sh.config import set_config, KeygenConfig
+    from keygen_sh.errors import Error, KeygenError, UrlParse
+    from keygen_sh.telemetry import set_exporter, InMemoryExporter
+
+    set_config(KeygenConfig(
+        api_url="https://invalid host",
+        api_prefix="v1",
+        api_version="v1.7",
+        account="account",
+        product="product",
+        license_key="KEY",
+    ))
+    exporter = InMemoryExporter()
+    set_exporter(exporter)
+    try:
+        await validate([], [])
+        assert False, "expected a UrlParse error"
+    except KeygenError as e:
+        error = Error.from_error(e)
+        assert isinstance(error, UrlParse)
+        # the outcome of a span is the class the error is raised as
+        assert exporter.spans[-1].outcome == type(error).__name__
+    finally:
+        set_exporter(None)
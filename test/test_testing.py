@@ -0,0 +1,91 @@
+import datetime
+
+KEY = "ABCD-EFGH-IJKL-MNOP"
+
+
+def test_imports():
+    from keygen_sh.testing import Signer
+    assert Signer
+
+
+def test_signer_keypair():
+    from keygen_sh.testing import Signer
+
+    signer = Signer()
+
+    assert len(signer.public_key) == 64
+    assert Signer(signer.private_key).public_key == signer.public_key
+    assert Signer().public_key != signer.public_key
+
+
+def test_sign_key():
+    from keygen_sh import verify
+    from keygen_sh.errors import Error, KeygenError, LicenseKeyNotGenuine
+    from keygen_sh.license import SchemeCode
+    from keygen_sh.testing import Signer
+
+    signer = Signer()
+    key = signer.sign_key('{"seats": 5}')
+
+    assert verify(SchemeCode.Ed25519Sign, key, signer.public_key) == '{"seats": 5}'
+    try:
+        verify(SchemeCode.Ed25519Sign, key, Signer().public_key)
+        assert False, "expected a LicenseKeyNotGenuine error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), LicenseKeyNotGenuine)
+
+
+def test_license_file():
+    from keygen_sh.license_file import LicenseFile
+    from keygen_sh.testing import Signer
+
+    signer = Signer()
+    issued = datetime.datetime.now(datetime.timezone.utc).replace(microsecond=0)
+    content = signer.license_file(
+        KEY,
+        license_id="lic",
+        attributes={"name": "Pro", "metadata": {"seats": 5}},
+        included=[{
+            "id": "pol", "type": "policies",
+            "attributes": {"name": "Pro", "duration": None, "created": "2024-01-01T00:00:00Z", "updated": "2024-01-01T00:00:00Z"},
+        }],
+        issued=issued,
+        ttl=3600,
+    )
+
+    lic = LicenseFile.build_from_cert(KEY, content)
+    assert lic.verify(signer.public_key) == signer.public_key
+    dataset = lic.decrypt(KEY)
+    assert (dataset.license.id, dataset.license.key, dataset.license.name) == ("lic", KEY, "Pro")
+    assert dataset.issued == issued
+    assert dataset.expiry == issued + datetime.timedelta(hours=1)
+    assert dataset.policy.name == "Pro"
+
+
+def test_expired_license_file():
+    from keygen_sh.errors import Error, KeygenError, LicenseFileExpired
+    from keygen_sh.license_file import LicenseFile
+    from keygen_sh.testing import Signer
+
+    now = datetime.datetime.now(datetime.timezone.utc)
+    content = Signer().license_file(KEY, issued=now - datetime.timedelta(days=2), expiry=now - datetime.timedelta(days=1))
+
+    try:
+        LicenseFile.build_from_cert(KEY, content)
+        assert False, "expected a LicenseFileExpired error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), LicenseFileExpired)
+
+
+def test_machine_file():
+    from keygen_sh.machine_file import MachineFile
+    from keygen_sh.testing import Signer
+
+    signer = Signer()
+    content = signer.machine_file(KEY, "fp-1", machine_id="m1", attributes={"platform": "linux"}, license_id="lic")
+
+    machine_file = MachineFile.from_cert(KEY + "fp-1", content)
+    assert machine_file.verify(signer.public_key) == signer.public_key
+    dataset = machine_file.decrypt(KEY + "fp-1")
+    assert (dataset.machine.id, dataset.machine.fingerprint, dataset.machine.platform) == ("m1", "fp-1", "linux")
+    assert dataset.license.id == "lic"
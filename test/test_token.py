@@ -0,0 +1,40 @@
+def test_imports():
+    from keygen_sh.token import Token
+    assert Token
+
+
+def test_token_from_dict():
+    from keygen_sh.token import Token
+
+    token = Token.from_dict({
+        "id": "tok", "kind": "activation-token", "token": "activ-0123456789abcdef", "maxActivations": 5,
+        "activations": 1, "expiry": "2030-01-01T00:00:00Z",
+        "created": "2024-01-01T00:00:00Z", "updated": "2024-01-01T00:00:00Z",
+    })
+
+    assert token.kind == "activation-token"
+    assert token.max_activations == 5
+    assert token.max_deactivations is None
+    assert token.expiry.year == 2030
+    assert "0123456789abcdef" not in repr(token)
+    assert Token.from_json(token.to_json()) == token
+
+
+def test_token_refresher():
+    from keygen_sh.config import KeygenConfig
+
+    def refresh():
+        return "new-token"
+
+    config = KeygenConfig(
+        api_url="https://api.keygen.sh",
+        api_prefix="v1",
+        api_version="v1.7",
+        account="account",
+        product="product",
+        token="token",
+        token_refresher=refresh,
+    )
+
+    assert config.token_refresher is refresh
+    assert KeygenConfig.from_dict(config.to_dict()).token_refresher is refresh
@@ -0,0 +1,17 @@
+def test_imports():
+    from keygen_sh.user import User
+    assert User
+
+
+def test_user_from_dict():
+    from keygen_sh.user import User
+
+    user = User.from_dict({
+        "id": "user", "email": "jane@example.com", "firstName": "Jane", "fullName": "Jane Doe", "status": "ACTIVE",
+        "created": "2024-01-01T00:00:00Z", "updated": "2024-01-01T00:00:00Z",
+    })
+
+    assert user.first_name == "Jane"
+    assert user.full_name == "Jane Doe"
+    assert user.last_name is None
+    assert User.from_json(user.to_json()) == user
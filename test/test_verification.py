@@ -0,0 +1,91 @@
+import pytest
+from config_fixture import mock_keygen
+
+
+def test_imports():
+    from keygen_sh.verification import ResponseVerification
+    assert ResponseVerification
+
+
+def test_license_file_with_trusted_keys():
+    from keygen_sh.errors import Error, KeygenError, LicenseFileNotGenuine
+    from keygen_sh.license_file import LicenseFile
+    from keygen_sh.testing import Signer
+
+    previous, current = Signer(), Signer()
+    lf = LicenseFile.build_from_cert("KEY", current.license_file("KEY"))
+
+    assert lf.verify([previous.public_key, "not hex", current.public_key]) == current.public_key
+    assert lf.verify(current.public_key) == current.public_key
+    try:
+        lf.verify([previous.public_key])
+        assert False, "expected a LicenseFileNotGenuine error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), LicenseFileNotGenuine)
+
+
+def test_machine_file_with_trusted_keys():
+    from keygen_sh.machine_file import MachineFile
+    from keygen_sh.testing import Signer
+
+    previous, current = Signer(), Signer()
+    mf = MachineFile.from_cert("KEYfp", current.machine_file("KEY", "fp"))
+
+    assert mf.verify([previous.public_key, current.public_key]) == current.public_key
+
+
+def configure(server, **kwargs):
+    from keygen_sh.config import KeygenConfig, set_config
+
+    set_config(KeygenConfig.from_dict({**server.config(license_key="MOCK-KEY").to_dict(), **kwargs}))
+
+
+@pytest.mark.asyncio
+async def test_signed_response(mock_keygen):
+    from keygen_sh import validate
+
+    configure(mock_keygen, strict_signatures=True)
+    l = await validate([], [])
+
+    assert l.verification.verified
+    assert (l.verification.algorithm, l.verification.key_id) == ("ed25519", "mock")
+    assert l.verification.digest.startswith("sha-256=")
+
+
+@pytest.mark.asyncio
+async def test_invalid_signature_rejected(mock_keygen):
+    from keygen_sh import validate
+    from keygen_sh.errors import Error, KeygenError, KeygenSignatureInvalid
+
+    for strict in (False, True):
+        configure(mock_keygen, strict_signatures=strict)
+        for tamper in ("unsigned", "signature", "body"):
+            mock_keygen.tamper(tamper, path="validate-key")
+            try:
+                await validate([], [])
+                assert False, "expected a KeygenSignatureInvalid error"
+            except KeygenError as e:
+                assert isinstance(Error.from_error(e), KeygenSignatureInvalid)
+
+
+@pytest.mark.asyncio
+async def test_unverified_response(mock_keygen):
+    from keygen_sh import validate
+    from keygen_sh.errors import Error, KeygenError, KeygenSignatureMissing
+
+    configure(mock_keygen, verify_keygen_signature=False)
+    mock_keygen.tamper("unsigned", path="validate-key")
+    l = await validate([], [])
+    assert not l.verification.verified and l.verification.signature is None
+    mock_keygen.tamper("body", path="validate-key")
+    assert not (await validate([], [])).verification.verified
+
+    configure(mock_keygen, verify_keygen_signature=False, strict_signatures=True)
+    for tamper in ("unsigned", None):
+        if tamper:
+            mock_keygen.tamper(tamper, path="validate-key")
+        try:
+            await validate([], [])
+            assert False, "expected a KeygenSignatureMissing error"
+        except KeygenError as e:
+            assert isinstance(Error.from_error(e), KeygenSignatureMissing)
@@ -0,0 +1,159 @@
+import json
+
+LICENSE = {
+    "id": "lic", "type": "licenses",
+    "attributes": {
+        "name": None, "key": "KEY-1", "expiry": None, "status": "EXPIRED", "metadata": {},
+        "created": "2024-01-01T00:00:00Z", "updated": "2024-01-01T00:00:00Z",
+    },
+}
+
+
+def event(name, data):
+    return json.dumps({
+        "data": {
+            "id": "evt", "type": "webhook-events",
+            "attributes": {
+                "endpoint": "https://example.com/webhooks/keygen", "event": name, "status": "DELIVERING",
+                "payload": json.dumps({"data": data}),
+                "created": "2024-01-01T00:00:00Z", "updated": "2024-01-01T00:00:00Z",
+            },
+        },
+    })
+
+
+def test_imports():
+    from keygen_sh.webhooks import WebhookEvent, parse, verify
+    assert WebhookEvent
+    assert parse
+    assert verify
+
+
+def test_parse_license_event():
+    from keygen_sh.webhooks import WebhookEvent, parse
+
+    e = parse(event("license.expired", LICENSE).encode())
+
+    assert (e.id, e.event, e.resource_type) == ("evt", "license.expired", "licenses")
+    assert e.license.key == "KEY-1"
+    assert e.machine is None
+    assert e.verification is None
+    assert WebhookEvent.from_json(e.to_json()) == e
+
+
+def test_verify_requires_signature():
+    from keygen_sh.errors import Error, KeygenError, KeygenSignatureInvalid
+    from keygen_sh.webhooks import verify
+
+    try:
+        verify({"Host": "example.com"}, event("license.expired", LICENSE), "00" * 32, path="/webhooks/keygen")
+        assert False, "expected a KeygenSignatureInvalid error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), KeygenSignatureInvalid)
+
+
+def signed(signer, body, path="/webhooks/keygen", age=0):
+    import base64
+    import email.utils
+    import hashlib
+    import time
+
+    date = email.utils.formatdate(time.time() - age, usegmt=True)
+    digest = "sha-256=" + base64.b64encode(hashlib.sha256(body.encode()).digest()).decode()
+    signature = signer.sign(f"(request-target): post {path}\nhost: example.com\ndate: {date}\ndigest: {digest}")
+    return {
+        "Host": "example.com",
+        "Date": date,
+        "Digest": digest,
+        "Keygen-Signature": f'keyid="mock", algorithm="ed25519", signature="{signature}", '
+                            f'headers="(request-target) host date digest"',
+    }
+
+
+def test_verify_once():
+    from keygen_sh.errors import Error, KeygenError, KeygenSignatureInvalid, WebhookReplayed
+    from keygen_sh.testing import Signer
+    from keygen_sh.webhooks import verify
+
+    signer = Signer()
+    body = event("license.expired", LICENSE)
+    headers = signed(signer, body)
+
+    # the path is part of the signed string
+    try:
+        verify(headers, body, signer.public_key, path="/webhooks/other")
+        assert False, "expected a KeygenSignatureInvalid error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), KeygenSignatureInvalid)
+
+    e = verify(headers, body, signer.public_key, path="/webhooks/keygen")
+    assert e.license.key == "KEY-1"
+    assert e.verification.verified
+
+    try:
+        verify(headers, body, signer.public_key, path="/webhooks/keygen")
+        assert False, "expected a WebhookReplayed error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), WebhookReplayed)
+
+
+def test_verify_with_seen_store():
+    import datetime
+    from keygen_sh.errors import Error, KeygenError, WebhookReplayed
+    from keygen_sh.testing import Signer
+    from keygen_sh.webhooks import verify
+
+    store = {}
+
+    def seen(signature, until):
+        if signature in store:
+            return True
+        store[signature] = until
+        return False
+
+    signer = Signer()
+    body = event("license.expired", LICENSE)
+    headers = signed(signer, body)
+
+    assert verify(headers, body, signer.public_key, path="/webhooks/keygen", seen=seen).license.key == "KEY-1"
+    assert len(store) == 1
+    until = next(iter(store.values()))
+    assert until > datetime.datetime.now(datetime.timezone.utc)
+
+    try:
+        verify(headers, body, signer.public_key, path="/webhooks/keygen", seen=seen)
+        assert False, "expected a WebhookReplayed error"
+    except KeygenError as e:
+        assert isinstance(Error.from_error(e), WebhookReplayed)
+
+
+
+def test_verify_expired():
+    from keygen_sh.config import get_config, set_config, KeygenConfig
+    from keygen_sh.errors import Error, KeygenError, WebhookExpired
+    from keygen_sh.testing import Signer
+    from keygen_sh.webhooks import verify
+
+    previous = get_config()
+    set_config(KeygenConfig(
+        api_url="https://api.keygen.sh",
+        api_prefix="v1",
+        api_version="v1.7",
+        account="account",
+        product="product",
+        max_clock_drift=2,
+    ))
+    try:
+        signer = Signer()
+        body = event("license.expired", LICENSE)
+
+        # signed within the drift
+        verify(signed(signer, body, age=60), body, signer.public_key, path="/webhooks/keygen")
+
+        try:
+            verify(signed(signer, body, age=3 * 60), body, signer.public_key, path="/webhooks/keygen")
+            assert False, "expected a WebhookExpired error"
+        except KeygenError as e:
+            assert isinstance(Error.from_error(e), WebhookExpired)
+    finally:
+        set_config(previous)